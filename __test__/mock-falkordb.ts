@@ -0,0 +1,375 @@
+/**
+ * A minimal stand-in for a password-protected FalkorDB server, for connection tests.
+ *
+ * It speaks just enough RESP to answer `AUTH` (accepting only `password`), `SELECT` and `PING`;
+ * every other command gets an error reply, so a client never gets past connecting unless `graphs`
+ * is set, in which case `INFO` and `GRAPH.LIST` are answered too, every `GRAPH.RO_QUERY` (or
+ * `GRAPH.QUERY`) on a listed graph gets an empty result, as if all graphs were empty, unless it is
+ * one of schema discovery's queries and `schema` is set, and `GRAPH.EXPLAIN` stands in for
+ * FalkorDB's parser by accepting only queries that start with a Cypher clause. With `queryTimeMs`
+ * set, queries take that long to answer, and one sent with a shorter `timeout` argument fails after
+ * it like a query FalkorDB aborted. A query listed in `queryErrors` gets its error reply instead,
+ * the way FalkorDB rejects a query that fails at run time, and one listed in `queryResults` gets
+ * its rows, or the window of them a trailing `SKIP <n> LIMIT <m>` selects; both are looked up
+ * without the client's trailing `trace_id` comment. Rows may hold
+ * `MockNode` and `MockEdge` values, whose labels, property keys, and relationship types the mock
+ * also reports to the client's id lookups (`CALL db.labels()` and the like, sent without
+ * `--compact`). Every command is
+ * recorded in `commands` so tests can assert on how the client authenticated or which queries it
+ * ran. With `tls` set it only accepts TLS connections, presenting the given certificate.
+ */
+
+import { createServer, type AddressInfo, type Server, type Socket } from 'node:net';
+import { createServer as createTlsServer, type TlsOptions } from 'node:tls';
+
+export interface MockFalkorDb {
+  /** Port the server listens on, on 127.0.0.1 */
+  port: number;
+  /** Every command received, as its arguments, in arrival order */
+  commands: string[][];
+  /** Number of connections accepted so far */
+  readonly connections: number;
+  /** Closes every open connection from the server side, as a server restart would */
+  dropConnections(): void;
+  /** Stops the server, dropping connections a client still holds open */
+  close(): Promise<void>;
+}
+
+/** Parses one RESP array of bulk strings from the front of `buffer`, if it is complete. */
+function parseCommand(buffer: string): { args: string[]; length: number } | undefined {
+  let end = buffer.indexOf('\r\n');
+  if (!buffer.startsWith('*') || end < 0) return undefined;
+
+  const count = Number(buffer.slice(1, end));
+  const args: string[] = [];
+  let position = end + 2;
+  for (let i = 0; i < count; i++) {
+    end = buffer.indexOf('\r\n', position);
+    if (end < 0) return undefined;
+    const length = Number(buffer.slice(position + 1, end));
+    if (buffer.length < end + 2 + length + 2) return undefined;
+    args.push(buffer.slice(end + 2, end + 2 + length));
+    position = end + 2 + length + 2;
+  }
+  return { args, length: position };
+}
+
+export interface MockFalkorDbOptions {
+  /** Listen on this port instead of one picked by the OS */
+  port?: number;
+  /** Serve TLS with this key and certificate instead of plain TCP */
+  tls?: Pick<TlsOptions, 'key' | 'cert'>;
+  /**
+   * Let clients finish connecting and report these graphs from `GRAPH.LIST`; queries on any other
+   * graph fail like they do on a missing key
+   */
+  graphs?: string[];
+  /** Schema every listed graph reports to schema discovery */
+  schema?: MockSchema;
+  /** How long each `GRAPH.RO_QUERY` on a listed graph takes to run; instant by default */
+  queryTimeMs?: number;
+  /** Error messages FalkorDB replies with, by query text */
+  queryErrors?: Record<string, string>;
+  /**
+   * Columns and rows FalkorDB returns, by query text; values are strings, integers, lists, maps,
+   * nodes, or relationships
+   */
+  queryResults?: Record<string, { columns: string[]; rows: CompactValue[][] }>;
+}
+
+/** A graph schema for the mock to serve to schema discovery */
+export interface MockSchema {
+  /** Property types (`String`, `Integer`, ...) by property name, by node label */
+  nodes: Record<string, Record<string, string>>;
+  /** Relationship types with the labels they connect and their property types */
+  relationships: {
+    type: string;
+    source: string;
+    target: string;
+    properties?: Record<string, string>;
+  }[];
+}
+
+const UNKNOWN_COMMAND = '-ERR unknown command\r\n';
+/** What FalkorDB replies to a read query on a graph that does not exist. */
+const EMPTY_KEY = '-ERR Invalid graph operation on empty key\r\n';
+/** What FalkorDB replies to a query that ran past its `timeout` argument. */
+const TIMED_OUT = '-Query timed out\r\n';
+
+/** Encodes `value` as a RESP bulk string. */
+const bulk = (value: string) => `$${Buffer.byteLength(value)}\r\n${value}\r\n`;
+
+/** A node in a `queryResults` row, with its internal id */
+export class MockNode {
+  constructor(
+    readonly id: number,
+    readonly labels: string[] = [],
+    readonly properties: Record<string, CompactValue> = {}
+  ) {}
+}
+
+/** A relationship in a `queryResults` row, with its internal id and its endpoints' ids */
+export class MockEdge {
+  constructor(
+    readonly id: number,
+    readonly type: string,
+    readonly source: number,
+    readonly target: number,
+    readonly properties: Record<string, CompactValue> = {}
+  ) {}
+}
+
+type CompactValue =
+  | string
+  | number
+  | MockNode
+  | MockEdge
+  | CompactValue[]
+  | { [key: string]: CompactValue };
+
+/**
+ * Names compact replies refer to by position, as FalkorDB numbers labels, property keys, and
+ * relationship types. Shared by every mock so that a name keeps its id.
+ */
+const names = {
+  labels: [] as string[],
+  propertyKeys: [] as string[],
+  relationshipTypes: [] as string[],
+};
+
+/** The id of `name` in `list`, adding it when it is new. */
+function nameId(list: string[], name: string): number {
+  if (!list.includes(name)) list.push(name);
+  return list.indexOf(name);
+}
+
+/** Encodes an entity's properties as FalkorDB's `[key id, type, value]` triples. */
+function compactProperties(properties: Record<string, CompactValue>): string {
+  const entries = Object.entries(properties);
+  const triples = entries.map(
+    // A compact value is `*2` followed by its type and value, which the triple holds inline.
+    ([key, value]) => `*3\r\n:${nameId(names.propertyKeys, key)}\r\n${compact(value).slice(4)}`
+  );
+  return `*${entries.length}\r\n${triples.join('')}`;
+}
+
+/**
+ * Encodes `value` as a compact-format FalkorDB value: a string, an integer, a node, a
+ * relationship, an array, or a map.
+ */
+function compact(value: CompactValue): string {
+  if (typeof value === 'string') return `*2\r\n:2\r\n${bulk(value)}`;
+  if (typeof value === 'number') return `*2\r\n:3\r\n:${value}\r\n`;
+  if (value instanceof MockNode) {
+    const labels = value.labels.map((label) => `:${nameId(names.labels, label)}\r\n`);
+    return (
+      `*2\r\n:8\r\n*3\r\n:${value.id}\r\n*${labels.length}\r\n${labels.join('')}` +
+      compactProperties(value.properties)
+    );
+  }
+  if (value instanceof MockEdge) {
+    const type = nameId(names.relationshipTypes, value.type);
+    return (
+      `*2\r\n:7\r\n*5\r\n:${value.id}\r\n:${type}\r\n:${value.source}\r\n:${value.target}\r\n` +
+      compactProperties(value.properties)
+    );
+  }
+  if (Array.isArray(value)) {
+    return `*2\r\n:6\r\n*${value.length}\r\n${value.map(compact).join('')}`;
+  }
+  const entries = Object.entries(value);
+  const pairs = entries.map(([key, item]) => bulk(key) + compact(item)).join('');
+  return `*2\r\n:10\r\n*${entries.length * 2}\r\n${pairs}`;
+}
+
+/** A compact-format query reply with the given columns and rows. */
+function table(columns: string[], rows: CompactValue[][]): string {
+  const header = columns.map((column) => `*2\r\n:1\r\n${bulk(column)}`).join('');
+  const records = rows.map((row) => `*${row.length}\r\n${row.map(compact).join('')}`).join('');
+  return `*3\r\n*${columns.length}\r\n${header}*${rows.length}\r\n${records}*1\r\n${bulk(
+    'Query internal execution time: 0.1 milliseconds'
+  )}`;
+}
+
+/** A compact-format query reply with one column and no rows. */
+const EMPTY_RESULT = table(['result'], []);
+
+/**
+ * Answers the client's id lookups (`CALL db.labels()` and the like, without `--compact`) in the
+ * plain reply format, one name per row; `undefined` for any other query.
+ */
+function lookup(query: string): string | undefined {
+  // The client sends these in upper case.
+  const procedures: Record<string, string[]> = {
+    'call db.labels()': names.labels,
+    'call db.propertykeys()': names.propertyKeys,
+    'call db.relationshiptypes()': names.relationshipTypes,
+  };
+  const list = procedures[query.trim().toLowerCase()];
+  if (!list) return undefined;
+  const rows = list.map((name) => `*1\r\n${bulk(name)}`).join('');
+  return `*3\r\n*1\r\n${bulk('name')}*${list.length}\r\n${rows}*1\r\n${bulk(
+    'Query internal execution time: 0.1 milliseconds'
+  )}`;
+}
+
+/** Answers schema discovery's queries from `schema`; any other query gets no rows. */
+function discover(query: string, schema?: MockSchema): string {
+  if (!schema) return EMPTY_RESULT;
+  const types = [...new Set(schema.relationships.map(({ type }) => type))];
+  // One row per property: its name and type, and how many sampled entities have it.
+  const properties = (propertyTypes: Record<string, string>) =>
+    table(['kt', 'count(1)'], Object.entries(propertyTypes).map((entry) => [entry, 1]));
+
+  if (query.includes('CALL db.labels()')) {
+    return table(['label'], Object.keys(schema.nodes).map((label) => [label]));
+  }
+  if (query.includes('CALL db.relationshipTypes()')) {
+    return table(['relationshipType'], types.map((type) => [type]));
+  }
+  let match = /^\s*MATCH \(a:(\w+)\)\s+CALL/.exec(query);
+  if (match) return properties(schema.nodes[match[1]] ?? {});
+  match = /^\s*MATCH \(\)-\[a:(\w+)\]->\(\)\s+CALL/.exec(query);
+  if (match) {
+    const relationships = schema.relationships.filter(({ type }) => type === match![1]);
+    return properties(Object.assign({}, ...relationships.map((r) => r.properties ?? {})));
+  }
+  match = /^MATCH \(s:(\w+)\)-\[a:(\w+)\]->\(t:(\w+)\) return a limit 1$/.exec(query);
+  if (match) {
+    const [, source, type, target] = match;
+    const connects = schema.relationships.some(
+      (r) => r.type === type && r.source === source && r.target === target
+    );
+    return table(['a'], connects ? [[1]] : []);
+  }
+  return EMPTY_RESULT;
+}
+
+/** Clauses a query passed to `GRAPH.EXPLAIN` must start with. */
+const CLAUSE = /^\s*(MATCH|OPTIONAL|RETURN|WITH|UNWIND|CALL|CREATE|MERGE)\b/i;
+
+/** Answers `GRAPH.EXPLAIN` with a one-operation plan, or a syntax error like FalkorDB's. */
+function explain(query: string): string {
+  if (CLAUSE.test(query)) return `*1\r\n${bulk('Results')}`;
+  const token = query.trim().split(/\s+/)[0] ?? '';
+  return `-errMsg: Invalid input '${token}': expected a clause line: 1, column: 1, offset: 0\r\n`;
+}
+
+export async function startMockFalkorDb(
+  password: string,
+  {
+    port: listenPort = 0,
+    tls,
+    graphs,
+    schema,
+    queryTimeMs,
+    queryErrors = {},
+    queryResults = {},
+  }: MockFalkorDbOptions = {}
+): Promise<MockFalkorDb> {
+  const commands: string[][] = [];
+  const untraced = (query: string) => query.replace(/ \/\* trace_id: [^*]* \*\/$/, '');
+  const result = (query: string, args: string[]) => {
+    // A known query with `SKIP` and `LIMIT` appended gets that window of its rows.
+    const [, base, skip, limit] = query.match(/^(.*) SKIP (\d+) LIMIT (\d+)$/s) ?? [];
+    if (base in queryResults) {
+      const { columns, rows } = queryResults[base];
+      return table(columns, rows.slice(Number(skip), Number(skip) + Number(limit)));
+    }
+    return (
+      (!args.includes('--compact') && lookup(query)) ||
+      (query in queryResults
+        ? table(queryResults[query].columns, queryResults[query].rows)
+        : discover(query, schema))
+    );
+  };
+
+  const serve = (socket: Socket) => {
+    let buffer = '';
+    socket.on('error', () => {});
+    socket.on('data', (data) => {
+      buffer += data.toString('latin1');
+      for (let command = parseCommand(buffer); command; command = parseCommand(buffer)) {
+        buffer = buffer.slice(command.length);
+        commands.push(command.args);
+        switch (command.args[0].toUpperCase()) {
+          case 'AUTH':
+            socket.write(
+              command.args[command.args.length - 1] === password
+                ? '+OK\r\n'
+                : '-WRONGPASS invalid username-password pair or user is disabled.\r\n'
+            );
+            break;
+          case 'SELECT':
+            socket.write('+OK\r\n');
+            break;
+          case 'PING':
+            socket.write('+PONG\r\n');
+            break;
+          case 'INFO':
+            socket.write(graphs ? bulk('# Server\r\nredis_mode:standalone\r\n') : UNKNOWN_COMMAND);
+            break;
+          case 'GRAPH.LIST':
+            socket.write(
+              graphs ? `*${graphs.length}\r\n${graphs.map(bulk).join('')}` : UNKNOWN_COMMAND
+            );
+            break;
+          case 'GRAPH.QUERY':
+          case 'GRAPH.RO_QUERY': {
+            const query = untraced(command.args[2]);
+            if (!graphs) socket.write(UNKNOWN_COMMAND);
+            else if (!graphs.includes(command.args[1])) socket.write(EMPTY_KEY);
+            else if (query in queryErrors) socket.write(`-${queryErrors[query]}\r\n`);
+            else if (!queryTimeMs) socket.write(result(query, command.args));
+            else {
+              const reply = result(query, command.args);
+              const at = command.args.indexOf('timeout');
+              const timeout = at < 0 ? Infinity : Number(command.args[at + 1]);
+              setTimeout(
+                () => socket.write(queryTimeMs > timeout ? TIMED_OUT : reply),
+                Math.min(queryTimeMs, timeout)
+              );
+            }
+            break;
+          }
+          case 'GRAPH.EXPLAIN':
+            socket.write(graphs ? explain(command.args[2]) : UNKNOWN_COMMAND);
+            break;
+          default:
+            socket.write(UNKNOWN_COMMAND);
+        }
+      }
+    });
+  };
+  const server: Server = tls ? createTlsServer(tls, serve) : createServer(serve);
+  // Failed handshakes (e.g. a client rejecting the certificate) are expected in TLS tests.
+  server.on('tlsClientError', () => {});
+  // Clients keep pooled connections open, so closing has to drop them.
+  const sockets = new Set<Socket>();
+  let connections = 0;
+  server.on('connection', (socket: Socket) => {
+    connections++;
+    sockets.add(socket);
+    socket.on('close', () => sockets.delete(socket));
+  });
+  const dropConnections = () => {
+    for (const socket of sockets) socket.destroy();
+  };
+
+  await new Promise<void>((resolve) => server.listen(listenPort, '127.0.0.1', resolve));
+  const { port } = server.address() as AddressInfo;
+
+  return {
+    port,
+    commands,
+    get connections() {
+      return connections;
+    },
+    dropConnections,
+    close: () =>
+      new Promise<void>((resolve) => {
+        server.close(() => resolve());
+        dropConnections();
+      }),
+  };
+}
@@ -0,0 +1,154 @@
+/**
+ * A minimal OpenAI-compatible chat completions server for tests.
+ *
+ * Point a client at it with `model: 'openai::mock-model'` and `llmEndpoint: mock.endpoint`.
+ * Each request is answered with the next scripted reply (the last reply repeats once the
+ * script runs out), or with whatever a reply function returns for the request body when the
+ * order of requests is not deterministic (e.g. concurrent calls). A `{ status }` reply answers
+ * with that HTTP error status instead, and any `headers` and error `message` it has, to simulate
+ * provider failures such as rate limits. Every request is recorded in `requests`, with when it
+ * arrived, so tests can assert on what was sent. Replies report 10 prompt and 5 completion tokens
+ * unless `reportUsage` is false.
+ *
+ * Requests to `/messages`, as sent for `anthropic::` models, get an Anthropic Messages reply
+ * (not streamed). The mock plays the provider's prompt cache: the first request with a system
+ * block marked `cache_control` writes 8 of its 10 prompt tokens to the cache, and later requests
+ * with the same block read them from it.
+ */
+
+import { createServer, type IncomingHttpHeaders, type Server } from 'node:http';
+import type { AddressInfo } from 'node:net';
+
+export interface RecordedRequest {
+  path: string;
+  headers: IncomingHttpHeaders;
+  body: any;
+  /** `Date.now()` when the request arrived */
+  receivedAt: number;
+}
+
+export interface MockLlm {
+  /** Base URL to pass as `llmEndpoint` */
+  endpoint: string;
+  /** Every request received, in arrival order */
+  requests: RecordedRequest[];
+  close(): Promise<void>;
+}
+
+const usage = { prompt_tokens: 10, completion_tokens: 5, total_tokens: 15 };
+
+/** Reply content, or an HTTP error status (response headers, error message) to fail with */
+export type MockReply =
+  | string
+  | { status: number; headers?: Record<string, string>; message?: string };
+
+export type MockReplies = MockReply[] | ((body: any) => MockReply);
+
+/** True when `body` is an answer-generation request rather than a Cypher-generation one. */
+export function isAnswerRequest(body: any): boolean {
+  const last = body.messages?.[body.messages.length - 1]?.content ?? '';
+  return typeof last === 'string' && last.startsWith("You are answering a user's question.");
+}
+
+/** True when `body` asks the model to rate a generated query (the `withConfidence` option). */
+export function isRatingRequest(body: any): boolean {
+  const first = body.messages?.[0]?.content ?? '';
+  return typeof first === 'string' && first.startsWith('You review Cypher queries');
+}
+
+export interface MockLlmOptions {
+  /** Include token usage in replies, like most providers do (default true) */
+  reportUsage?: boolean;
+}
+
+export async function startMockLlm(
+  replies: MockReplies,
+  { reportUsage = true }: MockLlmOptions = {}
+): Promise<MockLlm> {
+  const requests: RecordedRequest[] = [];
+  const cached = new Set<string>();
+
+  const server: Server = createServer((req, res) => {
+    let raw = '';
+    req.on('data', (chunk) => (raw += chunk));
+    req.on('end', () => {
+      const body = raw ? JSON.parse(raw) : {};
+      requests.push({ path: req.url ?? '', headers: req.headers, body, receivedAt: Date.now() });
+      const reply =
+        typeof replies === 'function'
+          ? replies(body)
+          : replies[Math.min(requests.length - 1, replies.length - 1)] ?? '';
+
+      if (typeof reply !== 'string') {
+        res.writeHead(reply.status, { 'content-type': 'application/json', ...reply.headers });
+        const message = reply.message ?? `Mock failure ${reply.status}`;
+        res.end(JSON.stringify({ error: { message } }));
+        return;
+      }
+      const content = reply;
+
+      if (req.url?.endsWith('/messages')) {
+        const blocks = Array.isArray(body.system) ? body.system : [];
+        const marked = blocks.find((block: any) => block.cache_control)?.text;
+        const cacheHit = marked !== undefined && cached.has(marked);
+        if (marked !== undefined) cached.add(marked);
+        res.writeHead(200, { 'content-type': 'application/json' });
+        res.end(
+          JSON.stringify({
+            id: 'mock',
+            type: 'message',
+            role: 'assistant',
+            model: body.model,
+            content: [{ type: 'text', text: content }],
+            stop_reason: 'end_turn',
+            usage: {
+              input_tokens: marked === undefined ? 10 : 2,
+              output_tokens: 5,
+              cache_creation_input_tokens: marked === undefined || cacheHit ? 0 : 8,
+              cache_read_input_tokens: cacheHit ? 8 : 0,
+            },
+          })
+        );
+        return;
+      }
+
+      if (body.stream) {
+        res.writeHead(200, { 'content-type': 'text/event-stream' });
+        // Split the reply into a few pieces so consumers see more than one chunk.
+        for (const piece of content.match(/.{1,8}/gs) ?? []) {
+          const delta = { choices: [{ index: 0, delta: { content: piece } }] };
+          res.write(`data: ${JSON.stringify(delta)}\n\n`);
+        }
+        const end = {
+          choices: [{ index: 0, delta: {}, finish_reason: 'stop' }],
+          ...(reportUsage && { usage }),
+        };
+        res.write(`data: ${JSON.stringify(end)}\n\n`);
+        res.end('data: [DONE]\n\n');
+        return;
+      }
+
+      res.writeHead(200, { 'content-type': 'application/json' });
+      res.end(
+        JSON.stringify({
+          id: 'mock',
+          object: 'chat.completion',
+          model: body.model,
+          choices: [
+            { index: 0, message: { role: 'assistant', content }, finish_reason: 'stop' },
+          ],
+          ...(reportUsage && { usage }),
+        })
+      );
+    });
+  });
+
+  await new Promise<void>((resolve) => server.listen(0, '127.0.0.1', resolve));
+  const { port } = server.address() as AddressInfo;
+
+  return {
+    endpoint: `http://127.0.0.1:${port}/v1`,
+    requests,
+    close: () => new Promise<void>((resolve) => server.close(() => resolve())),
+  };
+}
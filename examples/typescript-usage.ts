@@ -2,7 +2,12 @@
  * TypeScript example for @falkordb/text-to-cypher
  */
 
-import { TextToCypher, ClientOptions, TextToCypherResponse } from '../index';
+import {
+  TextToCypher,
+  ClientOptions,
+  TextToCypherCallOptions,
+  TextToCypherResponse
+} from '../index';
 
 async function main(): Promise<void> {
   // Create client with type safety
@@ -37,6 +42,26 @@ async function main(): Promise<void> {
     }
   }
 
+  // Per-call options, type-checked as well
+  try {
+    const callOptions: TextToCypherCallOptions = {
+      signal: AbortSignal.timeout(10_000),
+      maxRows: 20,
+      generateAnswer: false
+    };
+    const { resultSet } = await client.textToCypher(
+      graphName,
+      'List all movies',
+      undefined,
+      callOptions
+    );
+    console.log('\nRows:', resultSet?.rows);
+  } catch (error) {
+    if (error instanceof Error) {
+      console.error('Exception:', error.message);
+    }
+  }
+
   // Schema discovery with type safety
   try {
     const schemaJson: string = await client.discoverSchema(graphName);
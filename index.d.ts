@@ -40,6 +40,30 @@ export declare class TextToCypher {
    * ```
    */
   constructor(options: ClientOptions)
+  /**
+   * Checks client options without creating a client
+   *
+   * Runs the constructor's checks on `options`, after filling unset ones from
+   * `setDefaultOptions` as the constructor does, and reports every invalid option instead of
+   * throwing on the first. Nothing is connected to and no client is created.
+   *
+   * # Arguments
+   *
+   * * `options` - Client configuration to check
+   *
+   * # Returns
+   *
+   * `{ valid, errors }`, where each error names its option in `field` and holds the
+   * constructor's message for it in `message`
+   *
+   * # Example
+   *
+   * ```javascript
+   * const { valid, errors } = TextToCypher.validateOptions(config);
+   * for (const { field, message } of errors) console.error(`${field}: ${message}`);
+   * ```
+   */
+  static validateOptions(options: ClientOptions): OptionsValidation
   /**
    * Converts natural language text to Cypher and executes the query
    *
@@ -53,6 +77,11 @@ export declare class TextToCypher {
    *
    * * `graph_name` - Name of the graph to query
    * * `question` - Natural language question or request
+   * * `schema` - Optional pre-discovered schema (the JSON string `discoverSchema` returns) to
+   *   use instead of discovering it again
+   * * `options` - Optional per-call options: an abort `signal`, `maxRows`, `model`,
+   *   `onProgress`, `generateAnswer`, `answerColumns`, `apiKey`, `resultFormat`, `traceId`,
+   *   and `groupBy` (see `TextToCypherCallOptions`)
    *
    * # Returns
    *
@@ -67,13 +96,157 @@ export declare class TextToCypher {
    * );
    * console.log('Query:', response.cypherQuery);
    * console.log('Answer:', response.answer);
+   *
+   * // Reuse a cached schema to skip discovery
+   * const schema = await client.discoverSchema('movies');
+   * await client.textToCypher('movies', 'Find all actors', schema);
+   *
+   * // Cancel the call if it takes longer than 5 seconds
+   * await client.textToCypher('movies', 'Find all actors', undefined, {
+   *   signal: AbortSignal.timeout(5000),
+   * });
+   *
+   * // Keep at most 100 rows
+   * const top = await client.textToCypher('movies', 'List all actors', schema, {
+   *   maxRows: 100,
+   * });
+   * console.log(top.truncated);
+   *
+   * // Send a hard question to a stronger model
+   * await client.textToCypher('movies', 'Which directors ...', schema, { model: 'gpt-4o' });
+   *
+   * // Show which stage is running
+   * await client.textToCypher('movies', 'Find all actors', undefined, {
+   *   onProgress: (event) => console.log(event.stage, event.phase),
+   * });
+   *
+   * // Only the rows, without a natural-language answer
+   * const { resultSet } = await client.textToCypher('movies', 'List all actors', schema, {
+   *   generateAnswer: false,
+   * });
+   *
+   * // Answer from the titles alone, leaving out the long plot summaries
+   * await client.textToCypher('movies', 'Return the title and plot of every movie', schema, {
+   *   answerColumns: ['m.title'],
+   * });
+   *
+   * // Bill the LLM calls to the tenant asking the question
+   * await client.textToCypher('movies', 'Find all actors', schema, { apiKey: tenant.apiKey });
+   *
+   * // The rows as CSV, ready for an ETL job
+   * const { cypherResult: csv } = await client.textToCypher('movies', 'List all actors',
+   *   schema, { generateAnswer: false, resultFormat: 'csv' });
+   *
+   * // Tag the query with the id of the HTTP request being served
+   * await client.textToCypher('movies', 'Find all actors', schema, { traceId: req.id });
+   *
+   * // How many of the listed movies came out each year
+   * const { groups } = await client.textToCypher('movies', 'List movies and their release year',
+   *   schema, { generateAnswer: false, groupBy: 'm.released' });
+   * ```
+   */
+  textToCypher(graphName: string, question: string, schema?: string | undefined | null, options?: TextToCypherCallOptions | undefined | null): Promise<TextToCypherResponse>
+  /**
+   * Answers a question about a graph, resolving to just the natural-language answer
+   *
+   * Runs the same pipeline as `textToCypher` and hands back its `answer`, for callers that do
+   * not need the query, the rows, or the other response fields.
+   *
+   * # Arguments
+   *
+   * * `graph_name` - Name of the graph to query
+   * * `question` - Natural language question
+   *
+   * # Returns
+   *
+   * A promise that resolves to the answer text. It rejects like `textToCypher` when a stage
+   * fails, and with `ANSWER_GENERATION` when the answer could not be generated or the model
+   * returned an empty one; the full response is attached as `error.response` either way.
+   *
+   * # Example
+   *
+   * ```javascript
+   * const answer = await client.answer('movies', 'Who directed The Matrix?');
+   * console.log(answer);
+   * ```
+   */
+  answer(graphName: string, question: string): Promise<string>
+  /**
+   * Converts natural language text to Cypher and returns the first page of its rows
+   *
+   * Runs the `textToCypher` pipeline without generating an answer, keeping the first
+   * `page_size` rows in `resultSet`. When the query returned more, `cursor` is set; pass it to
+   * `nextPage` for the following rows, without asking the model again.
+   *
+   * A cursor holds the graph name, the query, and where the next page starts, and nothing is
+   * kept on the client or the server between pages, so cursors never expire and any client
+   * connected to the same FalkorDB can use them. Each page runs the query again and keeps its
+   * own rows: rows added or removed in between shift the pages, and the row order is only
+   * stable when the query has an `ORDER BY`.
+   *
+   * # Arguments
+   *
+   * * `graph_name` - Name of the graph to query
+   * * `question` - Natural language question
+   * * `page_size` - Rows per page; must be at least 1
+   * * `schema` - Optional pre-discovered schema (the JSON string `discoverSchema` returns) to
+   *   use instead of discovering it again
+   *
+   * # Returns
+   *
+   * A promise that resolves to a TextToCypherResponse with the query, the first page in
+   * `resultSet`, and a `cursor` unless that page holds every row
+   *
+   * # Example
+   *
+   * ```javascript
+   * let page = await client.textToCypherPaged('movies', 'List all actors', 100);
+   * const rows = [...page.resultSet.rows];
+   * while (page.cursor) {
+   *   page = await client.nextPage(page.cursor);
+   *   rows.push(...page.resultSet.rows);
+   * }
+   * ```
+   */
+  textToCypherPaged(graphName: string, question: string, pageSize: number, schema?: string | undefined | null): Promise<TextToCypherResponse>
+  /**
+   * Fetches the next page of rows of a query run by `textToCypherPaged`
+   *
+   * Runs the query the cursor holds again and keeps the page of rows it points to; the model is
+   * not involved. See `textToCypherPaged` for how long cursors stay valid.
+   *
+   * # Arguments
+   *
+   * * `cursor` - The `cursor` of a `textToCypherPaged` response or of the previous page
+   *
+   * # Returns
+   *
+   * A promise that resolves to a ResultPage, whose `cursor` is unset on the last page. Rejects
+   * with `INVALID_ARGUMENT` when `cursor` is not a cursor.
+   *
+   * # Example
+   *
+   * ```javascript
+   * const first = await client.textToCypherPaged('movies', 'List all actors', 100);
+   * if (first.cursor) {
+   *   const second = await client.nextPage(first.cursor);
+   *   console.log(second.resultSet.rows);
+   * }
    * ```
    */
-  textToCypher(graphName: string, question: string): Promise<TextToCypherResponse>
+  nextPage(cursor: string): Promise<ResultPage>
   /**
    * Converts natural language text to Cypher and executes the query with multiple messages
    *
-   * This method allows for conversation history by accepting multiple messages.
+   * This method allows for conversation history by accepting multiple messages. Roles are
+   * `user`, `assistant`, and `system` (case-insensitive); any other role rejects the call with
+   * `INVALID_ARGUMENT` rather than being treated as a user message. System messages stay system
+   * messages wherever they appear, for query generation and answer generation alike.
+   *
+   * An assistant message can carry its turn's `cypherQuery` and `cypherResult`, which are sent
+   * to the model with it, so a follow-up such as "now only the ones from 2020" can refine the
+   * earlier query and refer to its rows. Only the first 4,000 characters' worth of lines of a
+   * result are sent; other roles carrying them reject with `INVALID_ARGUMENT`.
    *
    * # Arguments
    *
@@ -92,9 +265,118 @@ export declare class TextToCypher {
    *   { role: 'assistant', content: 'Here are the actors...' },
    *   { role: 'user', content: 'Filter those who acted after 2020' }
    * ]);
+   *
+   * // Feeding the previous turn's query and rows back for a follow-up
+   * const first = await client.textToCypher('movies', 'Which movies did Keanu Reeves act in?');
+   * const followUp = await client.textToCypherWithMessages('movies', [
+   *   { role: 'user', content: 'Which movies did Keanu Reeves act in?' },
+   *   {
+   *     role: 'assistant',
+   *     content: first.answer ?? '',
+   *     cypherQuery: first.cypherQuery,
+   *     cypherResult: first.cypherResult,
+   *   },
+   *   { role: 'user', content: 'Now filter those to only 2020' },
+   * ]);
    * ```
    */
   textToCypherWithMessages(graphName: string, messages: Array<Message>): Promise<TextToCypherResponse>
+  /**
+   * Converts natural language text to Cypher, executes the query, and streams the answer
+   *
+   * Works like `textToCypher`, but `onChunk` is called with each partial piece of the
+   * natural-language answer as the model generates it, which lets chat UIs render the answer
+   * incrementally. Providers that cannot stream deliver the whole answer as a single chunk.
+   *
+   * # Arguments
+   *
+   * * `graph_name` - Name of the graph to query
+   * * `question` - Natural language question or request
+   * * `on_chunk` - Callback invoked with each partial chunk of the answer text
+   *
+   * # Returns
+   *
+   * A promise that resolves to the complete TextToCypherResponse once streaming finishes
+   *
+   * # Example
+   *
+   * ```javascript
+   * const response = await client.textToCypherStream('movies', 'Who directed The Matrix?',
+   *   (chunk) => process.stdout.write(chunk));
+   * console.log('\nQuery:', response.cypherQuery);
+   * ```
+   */
+  textToCypherStream(graphName: string, question: string, onChunk: (arg: string) => void): Promise<TextToCypherResponse>
+  /**
+   * Answers several questions against the same graph, discovering its schema only once
+   *
+   * Each question runs through the same steps as `textToCypher`, a few at a time so the model
+   * provider is not flooded. A question that fails does not fail the batch: its slot holds an
+   * error-status response (the same one a rejected `textToCypher` attaches as
+   * `error.response`) while the other questions are still answered.
+   *
+   * # Arguments
+   *
+   * * `graph_name` - Name of the graph to query
+   * * `questions` - Natural language questions or requests
+   * * `schema` - Optional pre-discovered schema (the JSON string `discoverSchema` returns) to
+   *   use instead of discovering it
+   * * `on_progress` - Optional callback invoked with `(completed, total)` each time a question
+   *   finishes, successfully or not. It is not awaited, and an exception it throws is ignored
+   *
+   * # Returns
+   *
+   * A promise that resolves to one TextToCypherResponse per question, in input order. It only
+   * rejects when the schema cannot be discovered or the supplied one is invalid.
+   *
+   * # Example
+   *
+   * ```javascript
+   * const responses = await client.textToCypherBatch(
+   *   'movies',
+   *   ['How many movies are there?', 'Who directed The Matrix?'],
+   *   undefined,
+   *   (completed, total) => console.log(`${Math.round((100 * completed) / total)}%`)
+   * );
+   * for (const response of responses) {
+   *   console.log(response.status === 'success' ? response.answer : response.error);
+   * }
+   * ```
+   */
+  textToCypherBatch(graphName: string, questions: Array<string>, schema?: string | undefined | null, onProgress?: ((arg0: number, arg1: number) => void) | undefined | null): Promise<Array<TextToCypherResponse>>
+  /**
+   * Answers several questions against the same graph like `textToCypherBatch`, handing each
+   * response to a callback as soon as it is ready
+   *
+   * Questions run a few at a time, as in `textToCypherBatch`, and `on_result` is called once
+   * per question, in completion order, with the question's index in `questions` and its
+   * response (an error-status one for a question that failed). Each call is awaited before the
+   * next, so the promise settles only after every response was delivered. An exception thrown
+   * by `on_result` is ignored.
+   *
+   * # Arguments
+   *
+   * * `graph_name` - Name of the graph to query
+   * * `questions` - Natural language questions or requests
+   * * `on_result` - Called with a BatchResult (`{ index, response }`) for each question
+   * * `schema` - Optional pre-discovered schema (the JSON string `discoverSchema` returns) to
+   *   use instead of discovering it
+   *
+   * # Returns
+   *
+   * A promise that resolves once every question was answered and delivered. It only rejects
+   * when the schema cannot be discovered or the supplied one is invalid, before any question
+   * runs.
+   *
+   * # Example
+   *
+   * ```javascript
+   * await client.textToCypherBatchEach('movies', questions, ({ index, response }) => {
+   *   socket.send(JSON.stringify({ question: questions[index], answer: response.answer }));
+   * });
+   * ```
+   */
+  textToCypherBatchEach(graphName: string, questions: Array<string>, onResult: ((arg: BatchResult) => void), schema?: string | undefined | null): Promise<void>
   /**
    * Generates a Cypher query without executing it
    *
@@ -104,6 +386,15 @@ export declare class TextToCypher {
    *
    * * `graph_name` - Name of the graph to generate query for
    * * `question` - Natural language question or request
+   * * `schema` - Optional pre-discovered schema (the JSON string `discoverSchema` returns) to
+   *   use instead of discovering it again
+   * * `signal` - Optional `AbortSignal`; aborting it cancels the call, which rejects with an
+   *   `ABORTED` error
+   * * `model` - Optional model to use for this call instead of the client's `model`
+   * * `api_key` - Optional API key to send this call's LLM requests with instead of the
+   *   client's `apiKey`
+   * * `trace_id` - Optional identifier for the call, returned in `traceId`, as for
+   *   `textToCypher`. No generated query runs, so none is tagged with it
    *
    * # Returns
    *
@@ -117,10 +408,135 @@ export declare class TextToCypher {
    * // You can now review, modify, or execute the query yourself
    * ```
    */
-  cypherOnly(graphName: string, question: string): Promise<TextToCypherResponse>
+  cypherOnly(graphName: string, question: string, schema?: string | undefined | null, signal?: AbortSignal | undefined | null, model?: string | undefined | null, apiKey?: string | undefined | null, traceId?: string | undefined | null): Promise<TextToCypherResponse>
+  /**
+   * Generates a Cypher query without executing it, streaming the query as it is generated
+   *
+   * Works like `cypherOnly`, but `onChunk` is called with each partial piece of the query as the
+   * model writes it, which lets editors show the query taking shape. The chunks are the model's
+   * raw text: `cypherQuery` in the resolved response is the finished query, with any code fences
+   * removed and `enforceLimit` applied. Providers that cannot stream, and `structuredOutput`,
+   * deliver the finished query as a single chunk. A streamed reply is not retried.
+   *
+   * # Arguments
+   *
+   * * `graph_name` - Name of the graph to generate query for
+   * * `question` - Natural language question or request
+   * * `on_chunk` - Callback invoked with each partial chunk of the query text
+   *
+   * # Returns
+   *
+   * A promise that resolves to a TextToCypherResponse with only the schema and query once
+   * streaming finishes
+   *
+   * # Example
+   *
+   * ```javascript
+   * const response = await client.cypherOnlyStream('movies', 'Find all actors', (chunk) => {
+   *   process.stdout.write(chunk);
+   * });
+   * console.log('\nQuery:', response.cypherQuery);
+   * ```
+   */
+  cypherOnlyStream(graphName: string, question: string, onChunk: (arg: string) => void): Promise<TextToCypherResponse>
+  /**
+   * Generates a Cypher query from a supplied schema, without any FalkorDB connection
+   *
+   * For schemas maintained outside FalkorDB: nothing is discovered or executed and no
+   * connection is opened, so it works where FalkorDB is unreachable. UDFs from the `udfs`
+   * option are shown to the model; `discoverUdfs` is ignored.
+   *
+   * # Arguments
+   *
+   * * `schema_json` - The schema as a JSON object in the format `discoverSchema` returns
+   * * `question` - Natural language question or request
+   *
+   * # Returns
+   *
+   * A promise that resolves to a TextToCypherResponse with only the schema and query. It
+   * rejects with `INVALID_ARGUMENT` when `schema_json` is not a JSON object.
+   *
+   * # Example
+   *
+   * ```javascript
+   * const schema = JSON.stringify({
+   *   entities: [{ label: 'Movie', attributes: [{ name: 'title', type: 'String' }] }],
+   *   relations: [],
+   * });
+   * const { cypherQuery } = await client.cypherFromSchema(schema, 'List every movie title');
+   * ```
+   */
+  cypherFromSchema(schemaJson: string, question: string): Promise<TextToCypherResponse>
+  /**
+   * Generates a Cypher query and returns FalkorDB's execution plan for it, without executing it
+   *
+   * Works like `cypherOnly`, then has FalkorDB plan the generated query with `GRAPH.EXPLAIN`,
+   * which shows the operations (scans, index lookups, expansions) it would run.
+   *
+   * # Arguments
+   *
+   * * `graph_name` - Name of the graph to generate and plan the query for
+   * * `question` - Natural language question or request
+   * * `schema` - Optional pre-discovered schema (the JSON string `discoverSchema` returns) to
+   *   use instead of discovering it again
+   * * `signal` - Optional `AbortSignal`; aborting it cancels the call, which rejects with an
+   *   `ABORTED` error
+   * * `model` - Optional model to use for this call instead of the client's `model`
+   *
+   * # Returns
+   *
+   * A promise that resolves to a TextToCypherResponse with the schema, the query, and its
+   * `queryPlan`. A query FalkorDB cannot plan rejects with a `QUERY_EXECUTION` error whose
+   * `error.response.cypherQuery` holds it.
+   *
+   * # Example
+   *
+   * ```javascript
+   * const response = await client.explainQuery('movies', 'Find all actors');
+   * console.log(response.cypherQuery);
+   * console.log(response.queryPlan);
+   * ```
+   */
+  explainQuery(graphName: string, question: string, schema?: string | undefined | null, signal?: AbortSignal | undefined | null, model?: string | undefined | null): Promise<TextToCypherResponse>
+  /**
+   * Revises a generated Cypher query according to feedback, without executing it
+   *
+   * Sends `previous_query` back to the model as its own earlier reply, followed by `feedback`
+   * as the user's correction, so a wrong query can be fixed conversationally. Works like
+   * `cypherOnly` otherwise: the schema comes from `schema`, the schema cache, or discovery,
+   * and the revised query is returned without running it.
+   *
+   * # Arguments
+   *
+   * * `graph_name` - Name of the graph the query is for
+   * * `previous_query` - The query to revise, e.g. a previous response's `cypherQuery`
+   * * `feedback` - What is wrong with the query, in natural language
+   * * `schema` - Optional pre-discovered schema (the JSON string `discoverSchema` returns) to
+   *   use instead of discovering it again
+   *
+   * # Returns
+   *
+   * A promise that resolves to a TextToCypherResponse with the schema and the revised query
+   *
+   * # Example
+   *
+   * ```javascript
+   * const first = await client.cypherOnly('movies', 'Who acted in The Matrix?');
+   * const revised = await client.refineCypher(
+   *   'movies',
+   *   first.cypherQuery,
+   *   'Match the movie by title, not by id'
+   * );
+   * console.log('Revised query:', revised.cypherQuery);
+   * ```
+   */
+  refineCypher(graphName: string, previousQuery: string, feedback: string, schema?: string | undefined | null): Promise<TextToCypherResponse>
   /**
    * Discovers and returns the schema of a graph
    *
+   * With `schemaCacheTtlMs` set, a schema cached within the TTL is returned without querying
+   * FalkorDB, and a freshly discovered one is cached.
+   *
    * # Arguments
    *
    * * `graph_name` - Name of the graph to discover schema for
@@ -138,68 +554,516 @@ export declare class TextToCypher {
    */
   discoverSchema(graphName: string): Promise<string>
   /**
-   * Lists all available AI models across all supported providers
+   * Discovers the schema of a graph, blocking until it is available
    *
-   * This method queries all provider APIs (OpenAI, Anthropic, Gemini, Ollama) and
-   * returns a combined list of available models with provider prefixes.
+   * The same schema `discoverSchema` returns, but the call blocks the calling thread (and with
+   * it the whole event loop) until schema discovery finishes. Meant for CLI tools and scripts
+   * that want the schema without `await`; servers should use `discoverSchema` instead. Honours
+   * `timeoutMs` and the schema cache like `discoverSchema`, and throws the same errors.
+   *
+   * # Arguments
+   *
+   * * `graph_name` - Name of the graph to discover schema for
    *
    * # Returns
    *
-   * A promise that resolves to an array of model names with provider prefixes
+   * A JSON string representing the graph schema
    *
    * # Example
    *
    * ```javascript
-   * const allModels = await client.listModels();
-   * console.log('All models:', allModels);
-   * // Output: ['gpt-4o-mini', 'gpt-4o', 'anthropic:claude-sonnet-4-5', 'gemini:gemini-2.5-pro', ...]
+   * const schema = client.discoverSchemaSync('movies');
+   * console.log('Schema:', JSON.parse(schema));
    * ```
    */
-  listModels(): Promise<Array<string>>
+  discoverSchemaSync(graphName: string): string
   /**
-   * Lists available AI models from a specific provider
+   * Discovers the schema of a graph as a typed object
+   *
+   * The same schema `discoverSchema` returns, already parsed: `entities` lists the node labels
+   * and `relations` the relationship types with their endpoint labels, each with its
+   * `attributes`. Uses the schema cache like `discoverSchema`.
    *
    * # Arguments
    *
-   * * `provider` - Provider name: "openai", "anthropic", "gemini", or "ollama" (case-insensitive)
+   * * `graph_name` - Name of the graph to discover schema for
    *
-   * # Note
+   * # Returns
    *
-   * Each provider's live results are merged with a curated static catalog, so
-   * providers with a curated list (OpenAI, Anthropic, Gemini) still return their
-   * well-known models even when no matching API key is configured. Providers without
-   * a curated list (e.g. Ollama) are only returned when reachable.
+   * A promise that resolves to the graph's Schema
+   *
+   * # Example
+   *
+   * ```javascript
+   * const schema = await client.discoverSchemaTyped('movies');
+   * for (const entity of schema.entities) {
+   *   console.log(entity.label, entity.attributes.map((attribute) => attribute.name));
+   * }
+   * ```
+   */
+  discoverSchemaTyped(graphName: string): Promise<Schema>
+  /**
+   * Discovers the schemas of several graphs at once
+   *
+   * Graphs are discovered a few at a time, each bounded by `timeoutMs` on its own. A graph whose
+   * discovery fails does not fail the call: its entry holds the error while the other graphs
+   * are still discovered. The schema cache is used as in `discoverSchema`.
+   *
+   * # Arguments
+   *
+   * * `graph_names` - Names of the graphs to discover schemas for
    *
    * # Returns
    *
-   * A promise that resolves to an array of model names for the specified provider (without prefixes)
+   * A promise that resolves to an object keyed by graph name, holding either the schema JSON
+   * string or the error message for each graph
    *
    * # Example
    *
    * ```javascript
-   * const openaiModels = await client.listModelsByProvider('openai');
-   * console.log('OpenAI models:', openaiModels);
-   * // Output: ['gpt-4o-mini', 'gpt-4o', 'gpt-4-turbo', ...]
+   * const schemas = await client.discoverSchemas(['movies', 'social']);
+   * for (const [graphName, { schema, error }] of Object.entries(schemas)) {
+   *   console.log(graphName, schema ? JSON.parse(schema) : error);
+   * }
    * ```
    */
-  listModelsByProvider(provider: string): Promise<Array<string>>
-}
-
-/** Options for creating a TextToCypher client */
-export interface ClientOptions {
-  /** The AI model to use (e.g., "gpt-4o-mini", "anthropic:claude-3") */
-  model: string
-  /** API key for the AI service */
-  apiKey: string
-  /** FalkorDB connection string (e.g., "falkor://localhost:6379") */
-  falkordbConnection: string
-  /** Optional LLM provider endpoint/base URL override */
-  llmEndpoint?: string
+  discoverSchemas(graphNames: Array<string>): Promise<Record<string, GraphSchema>>
   /**
-   * When true, discover the connected instance's user-defined functions (UDFs) and surface their
-   * `library.function` call targets to the model. Off by default. This is a client-level option,
-   * so it applies to `textToCypher`, `textToCypherWithMessages`, and `cypherOnly` (i.e. it may
-   * run `GRAPH.UDF LIST` against FalkorDB during query generation). Ignored when `udfs` is set.
+   * Describes the schema of a graph in natural language
+   *
+   * Discovers the schema like `discoverSchema` and has the model summarize it in a few short
+   * paragraphs: the node labels, how they are connected, and the properties most useful for
+   * asking questions. The description is generated with `answerModel` when set, and otherwise
+   * with `model`. With `schemaCacheTtlMs` set, it is cached along with the schema, so calls
+   * within the TTL neither query FalkorDB nor call the model.
+   *
+   * # Arguments
+   *
+   * * `graph_name` - Name of the graph to describe
+   *
+   * # Returns
+   *
+   * A promise that resolves to the description. It rejects with `SCHEMA_DISCOVERY` when the
+   * schema cannot be discovered, and with `ANSWER_GENERATION` when the model fails or replies
+   * with nothing.
+   *
+   * # Example
+   *
+   * ```javascript
+   * console.log(await client.describeSchema('movies'));
+   * // "The graph holds movies and the people who made them. Person nodes connect to Movie ..."
+   * ```
+   */
+  describeSchema(graphName: string): Promise<string>
+  /**
+   * Estimates how many tokens the query-generation prompt for a question will use
+   *
+   * Builds the prompt `textToCypher` would send to `model` for `question` (system prompt,
+   * schema as pruned by `schemaTopK`, UDF context, skills, and examples), without calling the
+   * model, and counts its tokens approximately: about 4 characters per token, 3.5 for
+   * Anthropic models, plus a few per message. It is an estimate, not the provider's tokenizer,
+   * so expect it to be off by 10-20% (more for non-English text or code-heavy schemas), and it
+   * covers only the input of query generation: the reply, the answer call, and self-healing
+   * add more. The schema is taken from the schema cache or discovered, like `textToCypher`
+   * does.
+   *
+   * # Arguments
+   *
+   * * `graph_name` - Name of the graph the question is about
+   * * `question` - The question to estimate
+   *
+   * # Returns
+   *
+   * A promise that resolves to the estimated number of prompt tokens. It rejects like schema
+   * discovery does, and with `SCHEMA_TOO_LARGE` when the schema is over `maxSchemaBytes`.
+   *
+   * # Example
+   *
+   * ```javascript
+   * const tokens = await client.estimateTokens('movies', 'Who directed The Matrix?');
+   * // Pick a cheaper model for small prompts, for instance.
+   * ```
+   */
+  estimateTokens(graphName: string, question: string): Promise<number>
+  /**
+   * Drops cached schemas so the next call discovers them again
+   *
+   * Call this after changing a graph's schema. Does nothing unless `schemaCacheTtlMs` is set.
+   *
+   * # Arguments
+   *
+   * * `graph_name` - Graph whose cached schema to drop; omit to clear the whole cache
+   *
+   * # Example
+   *
+   * ```javascript
+   * // After adding labels or properties to the movies graph
+   * client.invalidateSchemaCache('movies');
+   * ```
+   */
+  invalidateSchemaCache(graphName?: string | undefined | null): void
+  /**
+   * Drops every cached `textToCypher` response, so the next calls run the pipeline again
+   *
+   * Call this after writing to a graph whose answers are cached. Does nothing unless
+   * `resultCacheTtlMs` is set.
+   *
+   * # Example
+   *
+   * ```javascript
+   * // After loading new movies into the graph
+   * client.clearResultCache();
+   * ```
+   */
+  clearResultCache(): void
+  /**
+   * Sets notes about a graph that every query generated for it should follow
+   *
+   * The hints are added to the query-generation prompt of every later call on `graph_name`,
+   * for knowledge the schema cannot carry, such as the scale a property uses or which of two
+   * similar relationship types to prefer. They are kept in memory on the client, replacing
+   * any hints the graph had; an empty list removes them.
+   *
+   * # Arguments
+   *
+   * * `graph_name` - Name of the graph the hints apply to
+   * * `hints` - One note per entry; a blank entry throws an `INVALID_ARGUMENT` error
+   *
+   * # Example
+   *
+   * ```javascript
+   * client.setGraphHints('movies', ['The rating property is 0-10, not 0-100.']);
+   * ```
+   */
+  setGraphHints(graphName: string, hints: Array<string>): void
+  /** Returns the hints set for a graph with `setGraphHints`, or an empty array */
+  getGraphHints(graphName: string): Array<string>
+  /**
+   * Drops the hints of a graph, or of every graph when no name is given
+   *
+   * # Example
+   *
+   * ```javascript
+   * client.clearGraphHints('movies'); // just the movies graph
+   * client.clearGraphHints();         // every graph
+   * ```
+   */
+  clearGraphHints(graphName?: string | undefined | null): void
+  /**
+   * Returns a snapshot of the client's usage counters
+   *
+   * Counts the calls that ran the text-to-cypher pipeline since the client was created: how
+   * many resolved and rejected, which stage failed, schema and result cache hits and misses,
+   * and the mean call duration. Reading them is cheap and never waits on a running call.
+   *
+   * # Example
+   *
+   * ```javascript
+   * const { calls, failures, averageLatencyMs } = client.metrics();
+   * console.log(`${failures}/${calls} failed, ${averageLatencyMs.toFixed(0)} ms on average`);
+   * ```
+   */
+  metrics(): Metrics
+  /**
+   * Checks that a Cypher query parses, without executing it
+   *
+   * FalkorDB plans the query with `GRAPH.EXPLAIN`, which never runs it, so write queries are
+   * checked without changing the graph. Use it to vet a query from `cypherOnly` before running
+   * it yourself.
+   *
+   * # Arguments
+   *
+   * * `graph_name` - Name of the graph to plan the query against
+   * * `cypher_query` - The Cypher query to check
+   *
+   * # Returns
+   *
+   * A promise that resolves to a CypherValidation; an invalid query resolves with `valid: false`
+   * and FalkorDB's error rather than rejecting. Rejects when FalkorDB cannot be reached.
+   *
+   * # Example
+   *
+   * ```javascript
+   * const { cypherQuery } = await client.cypherOnly('movies', 'Find all actors');
+   * const { valid, error } = await client.validateCypher('movies', cypherQuery);
+   * if (!valid) console.error('Generated query does not parse:', error);
+   * ```
+   */
+  validateCypher(graphName: string, cypherQuery: string): Promise<CypherValidation>
+  /**
+   * Runs a Cypher query you supply, without generating one or an answer
+   *
+   * Use it to run a generated query after refining it by hand, over the client's own
+   * connection. The query gets the same treatment as a generated one: read-only mode refuses
+   * write clauses before the query reaches FalkorDB, `queryTimeoutMs` and `timeoutMs` bound it,
+   * and `maxRows` caps the rows kept. With read-only mode off, a write query is run as one,
+   * without retries, so it is never applied twice; call `clearResultCache` afterwards if
+   * answers are cached.
+   *
+   * # Arguments
+   *
+   * * `graph_name` - Name of the graph to run the query against
+   * * `cypher_query` - The Cypher query to run
+   *
+   * # Returns
+   *
+   * A promise that resolves to a TextToCypherResponse with `cypherQuery`, `cypherResult`,
+   * `resultSet`, and `rowCount`, and no `schema` or `answer`. A query FalkorDB rejects rejects
+   * with `QUERY_EXECUTION`, its `error.response.executionError` holding FalkorDB's message.
+   *
+   * # Example
+   *
+   * ```javascript
+   * const { cypherQuery } = await client.cypherOnly('movies', 'Find all actors');
+   * const edited = `${cypherQuery} ORDER BY a.name`;
+   * const response = await client.executeCypher('movies', edited);
+   * console.log(response.resultSet.rows);
+   * ```
+   */
+  executeCypher(graphName: string, cypherQuery: string): Promise<TextToCypherResponse>
+  /**
+   * Checks that the configured FalkorDB connection works
+   *
+   * Connects with the client's connection settings and sends a `PING`, without touching any
+   * graph, so it is cheap enough for readiness probes. Bounded by `timeoutMs` like the other
+   * methods.
+   *
+   * # Returns
+   *
+   * A promise that resolves to `true` when FalkorDB answers, and rejects with a `CONNECTION`
+   * error when it cannot be reached (or `TIMEOUT` when `timeoutMs` runs out first)
+   *
+   * # Example
+   *
+   * ```javascript
+   * app.get('/ready', async (req, res) => {
+   *   try {
+   *     await client.ping();
+   *     res.sendStatus(200);
+   *   } catch {
+   *     res.sendStatus(503);
+   *   }
+   * });
+   * ```
+   */
+  ping(): Promise<boolean>
+  /**
+   * Prepares the client for its first calls
+   *
+   * Opens the FalkorDB connection pool, connecting all `poolSize` connections, and discovers
+   * the schemas of `graphNames` into the schema cache, so the first calls on those graphs skip
+   * both steps. Schemas already cached are kept. Connecting and each graph's discovery are
+   * bounded by `timeoutMs` on their own.
+   *
+   * # Arguments
+   *
+   * * `graph_names` - Names of the graphs whose schemas to cache; may be empty to only connect
+   *
+   * # Returns
+   *
+   * A promise that resolves once the pool is open and every schema is cached. It rejects with a
+   * `CONNECTION` error when FalkorDB cannot be reached, with the first graph's discovery error
+   * (e.g. `GRAPH_NOT_FOUND`), and with `INVALID_ARGUMENT` when graphs are named but
+   * `schemaCacheTtlMs` is unset, as there would be no cache to fill.
+   *
+   * # Example
+   *
+   * ```javascript
+   * await client.warmup(['movies', 'social']);
+   * server.listen(3000);
+   * ```
+   */
+  warmup(graphNames: Array<string>): Promise<void>
+  /**
+   * Lists the graphs on the connected FalkorDB instance
+   *
+   * Uses the same connection settings as the other methods.
+   *
+   * # Returns
+   *
+   * A promise that resolves to an array of graph names
+   *
+   * # Example
+   *
+   * ```javascript
+   * const graphs = await client.listGraphs();
+   * if (graphs.includes('movies')) {
+   *   const schema = await client.discoverSchema('movies');
+   * }
+   * ```
+   */
+  listGraphs(): Promise<Array<string>>
+  /**
+   * Lists the node labels and relationship types of a graph
+   *
+   * A lightweight alternative to `discoverSchema` for callers that only need the names, e.g.
+   * for autocomplete or validation UIs: it asks FalkorDB's catalog (`db.labels()` and
+   * `db.relationshipTypes()`) rather than sampling entities for their properties, and neither
+   * uses nor fills the schema cache.
+   *
+   * # Arguments
+   *
+   * * `graph_name` - Name of the graph
+   *
+   * # Returns
+   *
+   * A promise that resolves to the graph's GraphMetadata
+   *
+   * # Example
+   *
+   * ```javascript
+   * const { labels, relationshipTypes } = await client.graphMetadata('movies');
+   * console.log(labels); // ['Movie', 'Person']
+   * console.log(relationshipTypes); // ['ACTED_IN', 'DIRECTED']
+   * ```
+   */
+  graphMetadata(graphName: string): Promise<GraphMetadata>
+  /**
+   * Lists all available AI models across all supported providers
+   *
+   * This method queries all provider APIs (OpenAI, Anthropic, Gemini, Ollama) and
+   * returns a combined list of available models with provider prefixes.
+   *
+   * # Returns
+   *
+   * A promise that resolves to an array of model names with provider prefixes
+   *
+   * # Example
+   *
+   * ```javascript
+   * const allModels = await client.listModels();
+   * console.log('All models:', allModels);
+   * // Output: ['gpt-4o-mini', 'gpt-4o', 'anthropic:claude-sonnet-4-5', 'gemini:gemini-2.5-pro', ...]
+   * ```
+   */
+  listModels(): Promise<Array<string>>
+  /**
+   * Lists available AI models from a specific provider
+   *
+   * # Arguments
+   *
+   * * `provider` - Provider name: "openai", "anthropic", "gemini" (or "google"), or "ollama"
+   *   (case-insensitive)
+   *
+   * # Note
+   *
+   * Each provider's live results are merged with a curated static catalog, so
+   * providers with a curated list (OpenAI, Anthropic, Gemini) still return their
+   * well-known models even when no matching API key is configured. Providers without
+   * a curated list (e.g. Ollama) are only returned when reachable.
+   *
+   * # Returns
+   *
+   * A promise that resolves to an array of model names for the specified provider (without prefixes)
+   *
+   * # Example
+   *
+   * ```javascript
+   * const openaiModels = await client.listModelsByProvider('openai');
+   * console.log('OpenAI models:', openaiModels);
+   * // Output: ['gpt-4o-mini', 'gpt-4o', 'gpt-4-turbo', ...]
+   * ```
+   */
+  listModelsByProvider(provider: string): Promise<Array<string>>
+  /**
+   * Closes the client
+   *
+   * Closes the FalkorDB connection pool. Calls already running finish first, and their
+   * connections close as they finish; every later call rejects with a `CLIENT_CLOSED` error.
+   * Closing again does nothing.
+   *
+   * # Example
+   *
+   * ```javascript
+   * const client = new TextToCypher(options);
+   * try {
+   *   console.log(await client.textToCypher('movies', 'Who directed The Matrix?'));
+   * } finally {
+   *   await client.close();
+   * }
+   * ```
+   */
+  close(): Promise<void>
+}
+
+/** One question's response, as `textToCypherBatchEach` delivers it */
+export interface BatchResult {
+  /** Position of the question in the `questions` array */
+  index: number
+  /** The question's response; an error-status one when it failed */
+  response: TextToCypherResponse
+}
+
+/** Options for creating a TextToCypher client */
+export interface ClientOptions {
+  /** The AI model to use (e.g., "gpt-4o-mini", "anthropic:claude-3") */
+  model: string
+  /**
+   * Optional provider serving `model`: "openai", "anthropic", "gemini" (or "google"), or
+   * "ollama", case-insensitive. Takes precedence over a `provider:` prefix on `model`, which is
+   * still accepted when this is omitted. Unknown providers make the constructor throw.
+   */
+  provider?: string
+  /**
+   * Optional model for phrasing the natural-language answer, e.g. a cheaper model than the
+   * one generating Cypher. Accepts the same forms as `model`, a bare name going to `provider`.
+   * Only `textToCypher` and its variants generate answers. Defaults to `model`, or to a call's
+   * `model` override.
+   */
+  answerModel?: string
+  /** API key for the AI service. Required unless `apiKeyEnv` is set. */
+  apiKey?: string
+  /**
+   * Optional name of an environment variable holding the API key, in place of `apiKey`. The
+   * variable is read at the start of every call, so a rotated key takes effect without
+   * creating a new client; a call made while it is unset rejects with `INVALID_ARGUMENT`.
+   */
+  apiKeyEnv?: string
+  /** FalkorDB connection string (e.g., "falkor://localhost:6379") */
+  falkordbConnection: string
+  /**
+   * Optional FalkorDB connection strings to fail over to, in order, when `falkordbConnection`
+   * cannot be reached, e.g. a read replica. The client opens its connection pool on the first
+   * endpoint that accepts a connection and stays on it. Meant for read queries: a replica
+   * refuses writes. The credential, database, and TLS options apply to every endpoint.
+   */
+  falkordbFallbacks?: Array<string>
+  /**
+   * Optional FalkorDB username. Takes precedence over a username embedded in
+   * `falkordbConnection`.
+   */
+  falkordbUsername?: string
+  /**
+   * Optional FalkorDB password. Takes precedence over a password embedded in
+   * `falkordbConnection`.
+   */
+  falkordbPassword?: string
+  /**
+   * Optional database number to select after connecting. Takes precedence over a database
+   * embedded in `falkordbConnection` (e.g. the `2` in `falkor://localhost:6379/2`).
+   */
+  falkordbDatabase?: number
+  /**
+   * When true, connect to FalkorDB over TLS, as a `falkors://` URL would. Certificates are
+   * verified against the system trust store. Off by default.
+   */
+  tls?: boolean
+  /**
+   * When true, accept any TLS certificate, e.g. a self-signed one in development. Requires a
+   * TLS connection (`tls` or a `falkors://` URL). Never enable this in production.
+   */
+  tlsInsecure?: boolean
+  /**
+   * Optional LLM provider endpoint/base URL override, e.g. an OpenAI-compatible gateway. Must be
+   * an http(s) URL; `model` and `apiKey` keep their meaning, with requests sent here instead of
+   * to the provider's default endpoint.
+   */
+  llmEndpoint?: string
+  /**
+   * When true, discover the connected instance's user-defined functions (UDFs) and surface their
+   * `library.function` call targets to the model. Off by default. This is a client-level option,
+   * so it applies to `textToCypher`, `textToCypherWithMessages`, and `cypherOnly` (i.e. it may
+   * run `GRAPH.UDF LIST` against FalkorDB during query generation). Ignored when `udfs` is set.
    */
   discoverUdfs?: boolean
   /**
@@ -208,28 +1072,895 @@ export interface ClientOptions {
    * discovery round-trip.
    */
   udfs?: Array<UdfLibraryInput>
+  /**
+   * Optional upper bound, in milliseconds, on how long `textToCypher`, `answer`,
+   * `textToCypherPaged`, `nextPage`, `textToCypherWithMessages`, `cypherOnly`, `explainQuery`,
+   * `refineCypher`, `discoverSchema`, `discoverSchemaSync`, `discoverSchemaTyped`,
+   * `describeSchema`, `estimateTokens`, `listGraphs`, `graphMetadata`, `validateCypher`,
+   * `executeCypher`, and `ping` may run; `discoverSchemas` applies it to each graph. When
+   * exceeded the promise rejects (`discoverSchemaSync` throws) with an "Operation timed out
+   * after <N>ms" error. Omit for no limit.
+   */
+  timeoutMs?: number
+  /**
+   * Optional limit, in milliseconds, on how long FalkorDB may run a generated query, enforced
+   * by the server. A query that runs longer is aborted and the call rejects with a
+   * `QUERY_TIMEOUT` error whose `error.response.cypherQuery` holds the query; it is neither
+   * retried nor self-healed. Independent of `timeoutMs`, which bounds the whole call. Omit to
+   * use the server's own query timeout.
+   */
+  queryTimeoutMs?: number
+  /**
+   * Optional sampling temperature (0.0-2.0) for every LLM call. Lower values make the
+   * generated Cypher more deterministic. Omit to use the model provider's default.
+   */
+  temperature?: number
+  /**
+   * Optional nucleus-sampling `top_p` (0.0-1.0) for every LLM call. Omit to use the model
+   * provider's default.
+   */
+  topP?: number
+  /**
+   * Optional seed sent with every LLM call, so repeated calls with the same question and seed
+   * tend to generate the same Cypher. Best-effort: only providers that accept a seed (OpenAI
+   * and OpenAI-compatible APIs, Ollama) use it, others ignore it, and even those do not
+   * guarantee identical output. Must be 0 or greater. Omit for no seed.
+   */
+  seed?: number
+  /**
+   * Optional cap on the tokens the model may generate for a query. A query cut off by the cap
+   * is incomplete, so it fails validation or execution like any other bad query. Omit to use
+   * the model provider's default.
+   */
+  maxTokens?: number
+  /**
+   * Optional cap on the tokens the model may generate for the natural-language answer. An
+   * answer cut off by the cap is returned as far as it got, and its confidence is usually lost.
+   * Defaults to `maxTokens`.
+   */
+  answerMaxTokens?: number
+  /**
+   * Optional language for the natural-language answer, as a code ("es", "fr") or a free-form
+   * description ("Brazilian Portuguese, informal"). The answer is written in it even when the
+   * question is in another language; query generation is unaffected. Omit to let the model
+   * answer in the question's language.
+   */
+  answerLanguage?: string
+  /**
+   * Optional JSON schema for the answer, for answers a program consumes (a UI card, a form).
+   * Answer generation asks the model for a JSON object matching it, constrained by the
+   * provider's structured output feature, and a reply that matches is returned as
+   * `answerJson`, with `answer` holding the same JSON as text. Applies to OpenAI, Anthropic,
+   * and Gemini models; other providers, and replies that do not match the schema, keep the
+   * free-text answer and leave `answerJson` unset. The root must be an object schema
+   * (`"type": "object"`). Omit for free-text answers.
+   */
+  answerSchema?: Record<string, any>
+  /**
+   * When true, generated Cypher, and Cypher passed to `executeCypher`, containing write clauses
+   * (CREATE, MERGE, DELETE, SET, REMOVE, DROP) is rejected before it reaches FalkorDB; the
+   * rejection's `error.response.cypherQuery` holds the blocked query. Off by default. Does not
+   * affect `cypherOnly`, which never executes.
+   */
+  readOnly?: boolean
+  /**
+   * Optional instructions for Cypher generation (e.g. domain naming conventions), sent as a
+   * system message at the start of the conversation, right after the built-in schema prompt.
+   * System messages passed to `textToCypherWithMessages` follow it, so they take precedence on
+   * conflicts. Not used for answer generation. Omit to use the default prompt unchanged.
+   */
+  systemPrompt?: string
+  /**
+   * Optional Cypher dialect generated queries must be compatible with, such as a FalkorDB
+   * version ("FalkorDB 4.2") or a description of what is available ("openCypher 9, no `CALL {}`
+   * subqueries, no APOC"). It is sent to the model as a constraint with every query-generation
+   * request, self-healing included, ahead of `systemPrompt`. Omit to target the latest FalkorDB
+   * dialect, which the built-in prompt is written for.
+   */
+  cypherDialect?: string
+  /**
+   * Optional few-shot examples for query generation, sent ahead of the conversation as a user
+   * question followed by an assistant reply with its Cypher. Every example is resent with every
+   * query-generation request, so keep them short: a handful (3-5) covering the tricky parts of
+   * the schema usually helps most, while dozens mostly add prompt tokens and latency.
+   */
+  examples?: Array<CypherExample>
+  /**
+   * Optional template replacing the built-in query-generation prompt, e.g. read from a file
+   * with `fs.readFileSync`. It is sent as the last user message, with no built-in system
+   * prompt ahead of it, after filling in the placeholders `{{schema}}` and `{{question}}`
+   * (both required) and `{{examples}}`, `{{skills}}`, and `{{udfs}}` (optional). Examples used
+   * by the template are not also sent as conversation turns. The constructor throws on a
+   * missing or unknown placeholder. Omit to use the built-in prompt.
+   */
+  promptTemplate?: string
+  /**
+   * Optional number of times a failed LLM call or query execution is retried when the failure
+   * is transient (rate limits, 502/503/504 responses, timeouts, dropped connections). Errors
+   * such as invalid Cypher are never retried. A rate-limited reply's `Retry-After` (or
+   * `retry-after-ms`) replaces the backoff delay; a wait over 60s fails the call instead.
+   * Defaults to 0 (no retries).
+   */
+  maxRetries?: number
+  /**
+   * Optional delay, in milliseconds, before the first retry; each further retry doubles it
+   * (capped at 10s) and applies random jitter. Defaults to 500.
+   */
+  retryBaseDelayMs?: number
+  /**
+   * Optional retries by failure category, in place of `maxRetries`: `rateLimit`,
+   * `serverError`, `timeout`, `connection`, and `generation` (a model reply holding no valid
+   * query). Each category sets its own retry count and first delay (`retryBaseDelayMs` when
+   * unset), and its retries are counted separately within a call. Categories left out are not
+   * retried. Cannot be combined with `maxRetries`. Omit to retry per `maxRetries`.
+   */
+  retryPolicy?: RetryPolicy
+  /**
+   * Optional number of times opening a FalkorDB connection is retried when no endpoint accepts
+   * it, e.g. while a service starts before FalkorDB is ready. This only covers establishing a
+   * connection (the first one, and a new one after the previous attempt failed); commands on
+   * an open connection are retried per `maxRetries`. Only a connection that is refused,
+   * dropped, or times out is retried, not a rejected password or TLS handshake. `timeoutMs`
+   * still bounds the whole call. Defaults to 0 (no retries).
+   */
+  connectRetries?: number
+  /**
+   * Optional delay, in milliseconds, before the first connection retry; each further retry
+   * doubles it (capped at 10s) and applies random jitter. Defaults to 500.
+   */
+  connectRetryDelayMs?: number
+  /**
+   * Optional cap on the rows kept from an executed query; further rows are dropped, the answer
+   * is generated from the rows kept, and the response's `truncated` flag is set. `textToCypher`
+   * can override it per call. Omit to keep every row.
+   */
+  maxRows?: number
+  /**
+   * Optional `LIMIT` appended to each generated query that reads rows and whose final `RETURN`
+   * has no `LIMIT` of its own, so FalkorDB never returns more rows than that. Queries that
+   * write, return no rows, or combine results with `UNION` are left as generated, and so is a
+   * `LIMIT` the model already wrote, whatever its value. Unlike `maxRows`, which drops rows
+   * after FalkorDB returned them, the limit bounds the work FalkorDB does and shows in
+   * `cypherQuery`. Must be at least 1; omit to run queries as generated.
+   */
+  enforceLimit?: number
+  /**
+   * Optional time, in milliseconds, a discovered schema is reused for further calls on the same
+   * graph instead of being discovered again. Use `invalidateSchemaCache` after changing a
+   * graph's schema. Omit or set to 0 to discover the schema on every call.
+   */
+  schemaCacheTtlMs?: number
+  /**
+   * Optional time, in milliseconds, a successful `textToCypher` response is reused for a
+   * repeated call with the same graph, question (ignoring extra whitespace), model, schema,
+   * and options, without calling the model or FalkorDB again. A cached response can be stale:
+   * it holds the rows as they were when it was stored, so pick a TTL the data can tolerate and
+   * use `clearResultCache` after writing to a graph. Omit or set to 0 to disable the cache.
+   */
+  resultCacheTtlMs?: number
+  /**
+   * Optional number of responses the result cache holds; once full, the least recently used
+   * one is evicted. Must be at least 1; defaults to 100. Ignored unless `resultCacheTtlMs` is
+   * set.
+   */
+  resultCacheSize?: number
+  /**
+   * Optional headers added to every request to the model provider (e.g. an org ID or routing
+   * tag for an API gateway). Reserved headers (`Authorization`, `x-api-key`, `x-goog-api-key`,
+   * `Content-Type`, `Content-Length`, `Host`) make the constructor throw unless
+   * `allowReservedHeaders` is set. Headers never replace ones the provider integration sets
+   * itself, such as the `apiKey` credentials.
+   */
+  extraHeaders?: Record<string, string>
+  /**
+   * Accept reserved names in `extraHeaders`, e.g. an `Authorization` header for a gateway in
+   * front of a provider that authenticates with `x-api-key`. Off by default.
+   */
+  allowReservedHeaders?: boolean
+  /**
+   * Optional `User-Agent` sent with every request to the model provider, to identify the
+   * application's traffic, e.g. for a provider's allowlist. It is also reported to FalkorDB as
+   * the connections' library name (`CLIENT SETINFO LIB-NAME`, with whitespace replaced by
+   * `_`), which `CLIENT LIST` shows. Takes precedence over a `User-Agent` in `extraHeaders`.
+   * Defaults to `text-to-cypher-node/<version>`.
+   */
+  userAgent?: string
+  /**
+   * Optional size (1-255) of the FalkorDB connection pool the client opens on first use and
+   * shares across calls; each command borrows a connection and returns it when done, and a
+   * broken connection is replaced with a fresh one. Defaults to 8.
+   */
+  poolSize?: number
+  /**
+   * Optional time, in milliseconds, after which an unused connection pool is closed and
+   * replaced with a fresh one before the next command, instead of sending the command on
+   * connections the server (or a proxy) may have dropped meanwhile. Set it below the server's
+   * idle `timeout`. Omit to keep the pool open however long it sits unused.
+   */
+  connectionIdleTimeoutMs?: number
+  /**
+   * Optional idle time, in milliseconds, after which TCP keepalive probes are sent on FalkorDB
+   * connections, so NATs and firewalls see traffic on them and a connection that died without
+   * closing is noticed. Omit to send no probes (the system default).
+   */
+  connectionKeepaliveMs?: number
+  /**
+   * When true, responses carry the full prompt sent to the model for query generation in
+   * `prompt`, for debugging wrong Cypher. Off by default because the prompt embeds the graph
+   * schema, which should not end up in logs that record responses.
+   */
+  debug?: boolean
+  /**
+   * When true, a generated query that names a node label or relationship type missing from
+   * the schema is rejected with a `QUERY_GENERATION` error instead of being run with
+   * `warnings`. Off by default.
+   */
+  strict?: boolean
+  /**
+   * Optional number of node labels to keep in the query-generation prompt. When set, only the
+   * labels whose names, property names, and relationship types best match the question, and
+   * the relationship types between them, are sent to the model, which keeps prompts for graphs
+   * with hundreds of labels small. `response.schema` still holds the full schema. Omit to send
+   * the full schema (the default).
+   */
+  schemaTopK?: number
+  /**
+   * Optional limit, in bytes, on the schema JSON sent to the model, so a graph too large for
+   * the model's context fails clearly instead of with a provider error. A schema over the
+   * limit rejects with `SCHEMA_TOO_LARGE`, unless `schemaTopK` is set, in which case the
+   * schema is pruned to fewer labels until it fits. Must be at least 1; omit for no limit.
+   */
+  maxSchemaBytes?: number
+  /**
+   * Optional number of nodes per label whose properties schema discovery adds to the schema,
+   * as each entity's `samples`, so the model sees real values (e.g. a status's spelling) when
+   * mapping the question's terms. Long strings are truncated, long lists shortened, and
+   * vectors left out. The samples are sent to the model with the schema, so only enable this
+   * for data the model provider may see; each label costs one more query at discovery and
+   * more prompt tokens. Must be at least 1; omit to send no data rows (the default).
+   */
+  sampleRows?: number
+  /**
+   * Optional property names whose values are kept from the model and from debug output, e.g.
+   * `["email", "ssn"]` for a graph holding personal data. They are masked as `[REDACTED]` in
+   * the rows the answer is generated from, in nodes, relationships, paths, and maps, and in
+   * columns named after them (`ssn` or `p.ssn`), but not in a column aliased to another name.
+   * Schema discovery leaves their values out of attribute examples and `samples`, so neither
+   * the query-generation prompt nor `prompt` shows them. Queries can still filter on and return
+   * them: `cypherResult` and `resultSet` hold the values, as does the generated query when the
+   * question names one. Names are case-sensitive; blank ones make the constructor throw.
+   */
+  redactProperties?: Array<string>
+  /**
+   * When true, the client never discovers a schema from FalkorDB: every call that needs one
+   * must be given it (the `schema` argument of `textToCypher`, `cypherOnly`, and the like),
+   * and that schema is used as given. A call without one rejects with `INVALID_ARGUMENT`
+   * before contacting FalkorDB, as do the `discoverSchema` methods and `describeSchema`. For
+   * applications that keep their own schema. Off by default.
+   */
+  skipDiscovery?: boolean
+  /**
+   * When true, the model rates each generated query from 0 to 1 against the question and
+   * schema in an extra LLM call, reported as `queryConfidence`. The rating is a heuristic, and
+   * the extra call adds latency and tokens (counted in `tokenUsage`). A rating call that fails
+   * leaves `queryConfidence` unset rather than failing the call. Off by default.
+   */
+  withConfidence?: boolean
+  /**
+   * When true, query generation asks the model for a JSON object holding the query and its
+   * parameters, constrained by the provider's structured output feature, instead of
+   * extracting the query from free text. Applies to OpenAI, Anthropic, and Gemini models;
+   * other providers, and replies that do not match the schema, use the text extraction.
+   * Off by default.
+   */
+  structuredOutput?: boolean
+  /**
+   * When true, the query-generation system prompt, which holds the schema and is the same for
+   * every question about a graph, is marked cacheable for providers that cache a prompt only
+   * when asked (Anthropic), so repeated calls are billed the cheaper cached rate for it.
+   * OpenAI and Gemini cache long prompts on their own; other providers get the request
+   * unchanged. Cache reads and writes show in `tokenUsage`. Off by default.
+   */
+  promptCaching?: boolean
+  /**
+   * When true (the default), a generated query FalkorDB rejects is regenerated with the error
+   * as feedback and executed again, up to `selfHealMaxAttempts` times. Set to false to reject
+   * with the first execution error instead.
+   */
+  selfHeal?: boolean
+  /**
+   * Optional number of times self-healing may regenerate a rejected query, each attempt seeing
+   * every earlier failed query and its error; defaults to 1. A query FalkorDB aborts for
+   * running past `queryTimeoutMs` is never self-healed.
+   */
+  selfHealMaxAttempts?: number
+  /**
+   * When true, a model reply holding no Cypher query, such as an apology or a clarifying
+   * question, is followed up once with an instruction to reply with a query only, choosing the
+   * question's most likely meaning over asking. A second reply without a query rejects with
+   * `NO_QUERY_GENERATED`, as the first does when this is off. Off by default.
+   */
+  noQueryRetry?: boolean
+  /**
+   * When true, calls that resolve to a `TextToCypherResponse` (`textToCypher`, `cypherOnly`,
+   * `executeCypher`, and the like) resolve a failure with its error-status response, whose
+   * `errorCode` and `errorStage` say what failed, instead of rejecting. Only failures of the
+   * call's own work resolve: an invalid argument (`INVALID_ARGUMENT`) or a closed client
+   * (`CLIENT_CLOSED`) still rejects, as do the other methods. Off by default.
+   */
+  resolveErrors?: boolean
+  /**
+   * Optional callback receiving structured log events (`{ level, message, stage, timestamp }`)
+   * as a call progresses: the model called, the query generated, the rows returned, queries
+   * sent back for self-healing, and failures. It is not awaited and an exception it throws is
+   * ignored, so it never slows or breaks a call, and it does not keep the process alive.
+   */
+  onLog?: ((arg: LogEvent) => void) | undefined | null
+  /**
+   * Optional predicate called with each row a generated query returns, as an object keyed by
+   * column name, returning whether to keep it. Rows it returns false for are left out of
+   * `cypherResult`, `resultSet`, and the rows the answer is written from. Each row is a call
+   * into JavaScript on the main thread, so it suits results capped by `maxRows` rather than
+   * large ones; a throwing predicate fails the call with `QUERY_EXECUTION`.
+   */
+  rowFilter?: ((arg: Record<string, any>) => boolean) | undefined | null
+}
+
+/** Tabular result of an executed Cypher query */
+export interface CypherResultSet {
+  /** Column names, in `RETURN` order */
+  columns: Array<string>
+  /**
+   * One array per record, holding one value per column. Nodes become
+   * `{ type: "node", id, labels, properties }`, relationships
+   * `{ type: "edge", id, relationship_type, src_node_id, dst_node_id, properties }`,
+   * and paths `{ type: "path", nodes, relationships }`. Ids are FalkorDB's internal ids, for
+   * follow-up queries such as `MATCH (n) WHERE id(n) = $id`; FalkorDB reuses the id of a
+   * deleted entity, so an id kept for long may name a different one.
+   */
+  rows: Array<Array<any>>
+}
+
+/** A question paired with the Cypher that answers it, shown to the model as a worked example */
+export interface CypherExample {
+  /** A question about the graph, phrased the way users ask */
+  question: string
+  /** The Cypher query that answers it */
+  cypher: string
+}
+
+/** Outcome of checking a Cypher query with `validateCypher` */
+export interface CypherValidation {
+  /** Whether FalkorDB could parse and plan the query */
+  valid: boolean
+  /** FalkorDB's error message when the query is not valid */
+  error?: string
+}
+
+/**
+ * Options `new TextToCypher(...)` falls back to for the ones it is not given: every
+ * `ClientOptions` field but `model`, `falkordbConnection`, `onLog`, and `rowFilter`.
+ */
+export interface DefaultOptions {
+  /** Default for `provider` */
+  provider?: string
+  /** Default for `answerModel` */
+  answerModel?: string
+  /** Default for `apiKey` */
+  apiKey?: string
+  /** Default for `apiKeyEnv` */
+  apiKeyEnv?: string
+  /** Default for `falkordbFallbacks` */
+  falkordbFallbacks?: Array<string>
+  /** Default for `falkordbUsername` */
+  falkordbUsername?: string
+  /** Default for `falkordbPassword` */
+  falkordbPassword?: string
+  /** Default for `falkordbDatabase` */
+  falkordbDatabase?: number
+  /** Default for `tls` */
+  tls?: boolean
+  /** Default for `tlsInsecure` */
+  tlsInsecure?: boolean
+  /** Default for `llmEndpoint` */
+  llmEndpoint?: string
+  /** Default for `discoverUdfs` */
+  discoverUdfs?: boolean
+  /** Default for `udfs` */
+  udfs?: Array<UdfLibraryInput>
+  /** Default for `timeoutMs` */
+  timeoutMs?: number
+  /** Default for `queryTimeoutMs` */
+  queryTimeoutMs?: number
+  /** Default for `temperature` */
+  temperature?: number
+  /** Default for `topP` */
+  topP?: number
+  /** Default for `seed` */
+  seed?: number
+  /** Default for `maxTokens` */
+  maxTokens?: number
+  /** Default for `answerMaxTokens` */
+  answerMaxTokens?: number
+  /** Default for `answerLanguage` */
+  answerLanguage?: string
+  /** Default for `answerSchema` */
+  answerSchema?: Record<string, any>
+  /** Default for `readOnly` */
+  readOnly?: boolean
+  /** Default for `systemPrompt` */
+  systemPrompt?: string
+  /** Default for `cypherDialect` */
+  cypherDialect?: string
+  /** Default for `examples` */
+  examples?: Array<CypherExample>
+  /** Default for `promptTemplate` */
+  promptTemplate?: string
+  /** Default for `maxRetries` */
+  maxRetries?: number
+  /** Default for `retryBaseDelayMs` */
+  retryBaseDelayMs?: number
+  /** Default for `retryPolicy` */
+  retryPolicy?: RetryPolicy
+  /** Default for `connectRetries` */
+  connectRetries?: number
+  /** Default for `connectRetryDelayMs` */
+  connectRetryDelayMs?: number
+  /** Default for `maxRows` */
+  maxRows?: number
+  /** Default for `enforceLimit` */
+  enforceLimit?: number
+  /** Default for `schemaCacheTtlMs` */
+  schemaCacheTtlMs?: number
+  /** Default for `resultCacheTtlMs` */
+  resultCacheTtlMs?: number
+  /** Default for `resultCacheSize` */
+  resultCacheSize?: number
+  /** Default for `extraHeaders` */
+  extraHeaders?: Record<string, string>
+  /** Default for `allowReservedHeaders` */
+  allowReservedHeaders?: boolean
+  /** Default for `userAgent` */
+  userAgent?: string
+  /** Default for `poolSize` */
+  poolSize?: number
+  /** Default for `connectionIdleTimeoutMs` */
+  connectionIdleTimeoutMs?: number
+  /** Default for `connectionKeepaliveMs` */
+  connectionKeepaliveMs?: number
+  /** Default for `debug` */
+  debug?: boolean
+  /** Default for `strict` */
+  strict?: boolean
+  /** Default for `schemaTopK` */
+  schemaTopK?: number
+  /** Default for `maxSchemaBytes` */
+  maxSchemaBytes?: number
+  /** Default for `sampleRows` */
+  sampleRows?: number
+  /** Default for `redactProperties` */
+  redactProperties?: Array<string>
+  /** Default for `skipDiscovery` */
+  skipDiscovery?: boolean
+  /** Default for `withConfidence` */
+  withConfidence?: boolean
+  /** Default for `structuredOutput` */
+  structuredOutput?: boolean
+  /** Default for `promptCaching` */
+  promptCaching?: boolean
+  /** Default for `selfHeal` */
+  selfHeal?: boolean
+  /** Default for `selfHealMaxAttempts` */
+  selfHealMaxAttempts?: number
+  /** Default for `noQueryRetry` */
+  noQueryRetry?: boolean
+  /** Default for `resolveErrors` */
+  resolveErrors?: boolean
+}
+
+/** One graph's entry in the map `discoverSchemas` resolves to */
+/** The labels and relationship types of a graph, as `graphMetadata` returns them */
+export interface GraphMetadata {
+  /** The graph's node labels */
+  labels: Array<string>
+  /** The graph's relationship types */
+  relationshipTypes: Array<string>
+}
+
+export interface GraphSchema {
+  /** The discovered schema as a JSON string, when discovery succeeded */
+  schema?: string
+  /** Why discovery failed, when it did */
+  error?: string
+}
+
+/** Stable error codes exposed as `error.code` on rejected promises */
+export declare enum ErrorCode {
+  /** FalkorDB could not be reached or the connection string is invalid */
+  Connection = 'CONNECTION',
+  /** The graph does not exist in FalkorDB */
+  GraphNotFound = 'GRAPH_NOT_FOUND',
+  /** The graph schema could not be discovered */
+  SchemaDiscovery = 'SCHEMA_DISCOVERY',
+  /** The schema is larger than `maxSchemaBytes`, even after pruning */
+  SchemaTooLarge = 'SCHEMA_TOO_LARGE',
+  /** The model failed to produce a valid Cypher query */
+  QueryGeneration = 'QUERY_GENERATION',
+  /**
+   * The model replied without a Cypher query, e.g. with an apology or a clarifying question,
+   * kept in `error.response.modelReply` when available
+   */
+  NoQueryGenerated = 'NO_QUERY_GENERATED',
+  /** FalkorDB rejected or failed to run the generated query */
+  QueryExecution = 'QUERY_EXECUTION',
+  /** FalkorDB aborted the generated query for running longer than `queryTimeoutMs` */
+  QueryTimeout = 'QUERY_TIMEOUT',
+  /** The model failed to produce the natural-language answer */
+  AnswerGeneration = 'ANSWER_GENERATION',
+  /** The provider's model list could not be retrieved */
+  ModelDiscovery = 'MODEL_DISCOVERY',
+  /** The generated query was refused by read-only mode */
+  ReadOnlyViolation = 'READ_ONLY_VIOLATION',
+  /** The call exceeded the configured `timeoutMs` */
+  Timeout = 'TIMEOUT',
+  /** The call was cancelled through its `AbortSignal` */
+  Aborted = 'ABORTED',
+  /** An argument passed to the method is invalid */
+  InvalidArgument = 'INVALID_ARGUMENT',
+  /** The client was closed with `close()` */
+  ClientClosed = 'CLIENT_CLOSED'
+}
+
+/** A log event from the client */
+export interface LogEvent {
+  level: LogLevel
+  message: string
+  /**
+   * The pipeline stage the event comes from; unset for events outside a stage, such as a
+   * call failing
+   */
+  stage?: PipelineStage
+  /** When the event happened, in milliseconds since the Unix epoch (like `Date.now()`) */
+  timestamp: number
+  /**
+   * The `requestId` of the call the event comes from; unset for events of methods that do
+   * not resolve to a response, such as `discoverSchema`
+   */
+  requestId?: string
+}
+
+/** Severity of a log event */
+export declare enum LogLevel {
+  /** Detail useful when debugging, such as schema discovery finishing */
+  Debug = 'DEBUG',
+  /** Normal progress: the model called, the query generated, the rows returned */
+  Info = 'INFO',
+  /** Something went wrong but the call carries on, such as a query sent back for self-healing */
+  Warn = 'WARN',
+  /** The call failed */
+  Error = 'ERROR'
 }
 
 /** A chat message in the conversation */
 export interface Message {
-  /** Role of the message sender: "user", "assistant", or "system" */
+  /** Role of the message sender: "user", "assistant", or "system"; other roles are rejected */
   role: string
   /** Content of the message */
   content: string
+  /**
+   * Optional Cypher query the turn of an `assistant` message ran, e.g. the previous
+   * response's `cypherQuery`, sent to the model with the message so a follow-up question can
+   * build on it
+   */
+  cypherQuery?: string
+  /**
+   * Optional result of that query, e.g. the previous response's `cypherResult`, sent to the
+   * model with the message so a follow-up question can refer to its rows. A result over 4,000
+   * characters is cut to its first lines, with a note of how many were left out
+   */
+  cypherResult?: string
+}
+
+/**
+ * A snapshot of a client's usage counters, as returned by `metrics()`
+ *
+ * Counts cover the calls that run the text-to-cypher pipeline: `textToCypher` and the methods
+ * built on it, each batch question counting once. They start at zero when the client is created.
+ */
+export interface Metrics {
+  /** Pipeline calls that finished, whether they resolved or rejected */
+  calls: number
+  /** Calls that resolved, including partial responses */
+  successes: number
+  /** Calls that rejected */
+  failures: number
+  /**
+   * Failures by stage. Timeouts, aborts, connection errors, and invalid arguments are not tied
+   * to a stage and count only toward `failures`.
+   */
+  failuresByStage: StageFailures
+  /** Schemas taken from the schema cache; stays at zero unless `schemaCacheTtlMs` is set */
+  schemaCacheHits: number
+  /** Schemas discovered because the schema cache held none for the graph */
+  schemaCacheMisses: number
+  /**
+   * `textToCypher` responses taken from the result cache; stays at zero unless
+   * `resultCacheTtlMs` is set
+   */
+  resultCacheHits: number
+  /** `textToCypher` calls that ran the pipeline because the result cache held no response */
+  resultCacheMisses: number
+  /** Mean duration of the counted calls in milliseconds; 0 before the first call finishes */
+  averageLatencyMs: number
+}
+
+/** An option `validateOptions` found invalid */
+export interface OptionError {
+  /** The option, as named in `ClientOptions`, e.g. `poolSize` */
+  field: string
+  /** Why it is invalid, as the constructor's error message puts it */
+  message: string
+}
+
+/** Outcome of checking client options with `validateOptions` */
+export interface OptionsValidation {
+  /** Whether `new TextToCypher(...)` would accept the options */
+  valid: boolean
+  /** Every invalid option, in the order the constructor checks them; empty when valid */
+  errors: Array<OptionError>
+}
+
+/** A stage of the text-to-cypher pipeline, as reported to progress callbacks */
+export declare enum PipelineStage {
+  /** Discovering the graph schema (skipped when a schema is supplied) */
+  SchemaDiscovery = 'SCHEMA_DISCOVERY',
+  /** Generating the Cypher query */
+  QueryGeneration = 'QUERY_GENERATION',
+  /** Executing the query, including self-healing retries */
+  QueryExecution = 'QUERY_EXECUTION',
+  /** Generating the natural-language answer */
+  AnswerGeneration = 'ANSWER_GENERATION'
+}
+
+/** A pipeline stage starting or completing */
+export interface ProgressEvent {
+  stage: PipelineStage
+  phase: StagePhase
+  /** When the event happened, in milliseconds since the Unix epoch (like `Date.now()`) */
+  timestamp: number
+}
+
+/** Rows of a result set sharing one value of the `groupBy` column */
+export interface ResultGroup {
+  /** The column's value, as it appears in `resultSet` */
+  value: any
+  /** Rows holding the value */
+  count: number
+}
+
+/** A page of rows from a query run by `textToCypherPaged` */
+export interface ResultPage {
+  /** The page's rows */
+  resultSet: CypherResultSet
+  /** Token for `nextPage` to fetch the rows after this page; unset on the last page */
+  cursor?: string
+}
+
+/**
+ * Retries by failure category, replacing `maxRetries`. A category left out is not retried, and
+ * neither is a failure outside every category, such as a rejected API key or invalid Cypher.
+ */
+export interface RetryPolicy {
+  /** HTTP 429 responses */
+  rateLimit?: RetryRule
+  /** HTTP 502, 503, and 504 responses */
+  serverError?: RetryRule
+  /**
+   * LLM and FalkorDB requests that timed out in transport. A query FalkorDB aborts for running
+   * past `queryTimeoutMs` is never retried.
+   */
+  timeout?: RetryRule
+  /** Connections reset or closed during an LLM call or a query */
+  connection?: RetryRule
+  /** Query generation whose reply held no valid query; each retry asks the model again */
+  generation?: RetryRule
+}
+
+/** Retries of one failure category, as set in a `retryPolicy` */
+export interface RetryRule {
+  /** How many times a failure of the category is retried within a call */
+  retries: number
+  /**
+   * Delay, in milliseconds, before the first retry; each further retry doubles it (capped at
+   * 10s) and applies random jitter. Defaults to `retryBaseDelayMs`.
+   */
+  delayMs?: number
+}
+
+/**
+ * Sets the options every client created afterwards falls back to for the ones it is not given,
+ * replacing the defaults of any earlier call; `setDefaultOptions({})` clears them. Clients
+ * created before the call keep their options. Beyond their types, the defaults are checked like
+ * any other option when a client is created, so an invalid one makes the constructor throw.
+ *
+ * # Example
+ *
+ * ```javascript
+ * setDefaultOptions({ provider: 'anthropic', apiKeyEnv: 'ANTHROPIC_API_KEY', timeoutMs: 30000 });
+ *
+ * // Inherits the provider, API key, and timeout
+ * const client = new TextToCypher({
+ *   model: 'claude-sonnet-4-5',
+ *   falkordbConnection: 'falkor://localhost:6379',
+ * });
+ * ```
+ */
+export declare function setDefaultOptions(options: DefaultOptions): void
+
+/** Whether a progress event marks the start or the end of a stage */
+export declare enum StagePhase {
+  Started = 'STARTED',
+  /** The stage succeeded; a failed stage rejects the call instead */
+  Completed = 'COMPLETED'
+}
+
+/** A graph's node labels and relationship types, with their properties */
+export interface Schema {
+  /** One entry per node label */
+  entities: Array<SchemaEntity>
+  /** One entry per relationship type and pair of endpoint labels it connects */
+  relations: Array<SchemaRelation>
+}
+
+/** A property of a node label or relationship type */
+export interface SchemaAttribute {
+  name: string
+  /**
+   * Value type: `String`, `Number`, `Integer`, `Float`, `Boolean`, `DateTime`, `List`, `Map`,
+   * `Vector`, or `Point`
+   */
+  type: string
+  /** Whether every value is distinct */
+  unique: boolean
+  /** Whether every sampled node or relationship has the property */
+  required: boolean
+  /** A few sampled values, as strings */
+  examples?: Array<string>
+}
+
+/** A node label and the properties its nodes carry */
+export interface SchemaEntity {
+  label: string
+  attributes: Array<SchemaAttribute>
+  /** Free-text description of the label, when the schema has one */
+  description?: string
+  /** Properties of a few of the label's nodes, when the schema was discovered with `sampleRows` */
+  samples?: Array<Record<string, any>>
+}
+
+/** A relationship type between nodes of two labels, and the properties its edges carry */
+export interface SchemaRelation {
+  /** The relationship type */
+  label: string
+  /** Label of the nodes the relationships start from */
+  source: string
+  /** Label of the nodes the relationships point to */
+  target: string
+  attributes: Array<SchemaAttribute>
+}
+
+/** Failed pipeline calls by the stage that failed */
+export interface StageFailures {
+  /** Schema discovery failed, the graph did not exist, or the schema was too large */
+  schemaDiscovery: number
+  /** The model produced no usable query, or a write query under `readOnly` */
+  queryGeneration: number
+  /** FalkorDB rejected the query or it ran out of time */
+  queryExecution: number
+  /** The answer could not be generated, including partial responses that resolved without one */
+  answerGeneration: number
+}
+
+/**
+ * How long each pipeline stage took, in milliseconds
+ *
+ * Durations come from a monotonic clock, so they are unaffected by system clock changes. A stage
+ * that did not run is omitted; a stage that failed reports the time spent until it failed.
+ */
+export interface StageTimings {
+  /** Discovering the graph schema; omitted when a schema was supplied */
+  schemaDiscoveryMs?: number
+  /** Generating the Cypher query */
+  queryGenerationMs?: number
+  /** Executing the query, including self-healing retries */
+  queryExecutionMs?: number
+  /** Generating the natural-language answer */
+  answerGenerationMs?: number
+}
+
+/** Per-call options for `textToCypher` */
+export interface TextToCypherCallOptions {
+  /** Optional `AbortSignal`; aborting it cancels the call, which rejects with an `ABORTED` error */
+  signal?: AbortSignal
+  /** Optional cap on the rows kept from the executed query, overriding the client's `maxRows` */
+  maxRows?: number
+  /** Optional model to use for this call instead of the client's `model` */
+  model?: string
+  /**
+   * Optional callback invoked with a `ProgressEvent` as each stage starts and completes.
+   * Best-effort: it is not awaited, and an exception it throws is ignored
+   */
+  onProgress?: ((arg: ProgressEvent) => void) | undefined | null
+  /**
+   * Optional; `false` skips answer generation, so the call resolves once the query has run,
+   * without an `answer`. Defaults to `true`
+   */
+  generateAnswer?: boolean
+  /**
+   * Optional names of the result columns the answer is generated from; the response still
+   * holds every column. Naming a column the query did not return rejects with
+   * `INVALID_ARGUMENT`. Defaults to every column
+   */
+  answerColumns?: Array<string>
+  /**
+   * Optional API key to send this call's LLM requests with instead of the client's `apiKey`,
+   * e.g. the calling tenant's own key
+   */
+  apiKey?: string
+  /**
+   * Optional format of `cypherResult`: `"text"` (the default, the text the answer is
+   * generated from), `"json"` (an array of row objects keyed by column), `"csv"` (a header
+   * row, then one line per row), or `"ndjson"` (one row object per line). Nested values such
+   * as nodes are JSON-encoded within a CSV cell
+   */
+  resultFormat?: string
+  /**
+   * Optional identifier for the call, e.g. the request id of the application request it
+   * serves: up to 128 letters, digits, `-`, `_`, `.`, or `:`. It is appended to each query the
+   * call runs as a `/* trace_id: ... *\/` comment, so FalkorDB's logs and slow log can be
+   * matched to the call, and returned in `traceId`. Defaults to the call's `requestId`, a
+   * random UUID
+   */
+  traceId?: string
+  /**
+   * Optional name of a result column to count the returned rows by, client-side, into
+   * `groups`, e.g. for a quick histogram without aggregating in the Cypher. Naming a column
+   * the query did not return rejects with `INVALID_ARGUMENT`
+   */
+  groupBy?: string
 }
 
 /** Response from text-to-cypher operations */
 export interface TextToCypherResponse {
-  /** Status of the operation: "success" or "error" */
+  /**
+   * Status of the operation: "success", "partial" when the query ran but the answer could not
+   * be generated, or "error"
+   */
   status: string
-  /** The discovered graph schema (JSON string) */
+  /**
+   * The discovered graph schema (JSON string), also set on the error response of a call that
+   * failed after discovery
+   */
   schema?: string
   /** The generated Cypher query */
   cypherQuery?: string
-  /** The result from executing the Cypher query */
+  /** The result from executing the Cypher query, as text */
   cypherResult?: string
+  /** The same result as columns and rows of JSON values */
+  resultSet?: CypherResultSet
+  /**
+   * Rows of `resultSet` counted by their value in the `groupBy` column, most frequent value
+   * first; set only when the call passes `groupBy`. Counts cover the rows kept, so a truncated
+   * result is counted over its first `maxRows` rows
+   */
+  groups?: Array<ResultGroup>
   /** Natural language answer generated from the results */
   answer?: string
+  /**
+   * The answer as JSON matching the client's `answerSchema`; set only when the option is on,
+   * the provider supports structured output, and the model's reply matched the schema
+   */
+  answerJson?: any
   /**
    * Model self-reported confidence (0-100) that the answer is correct given the data.
    * Omitted when the model does not report a value.
@@ -237,11 +1968,117 @@ export interface TextToCypherResponse {
   confidence?: number
   /** Error message if status is "error" */
   error?: string
+  /**
+   * Why the answer could not be generated when status is "partial"; `cypherQuery`,
+   * `cypherResult`, and `resultSet` still hold the query and its rows
+   */
+  answerError?: string
+  /**
+   * True when the query returned more rows than `maxRows` and only the first `maxRows` were
+   * kept in `cypherResult` and `resultSet`
+   */
+  truncated: boolean
+  /**
+   * Number of rows in `resultSet`; set once the query has run, so 0 means it ran and matched
+   * nothing, while a failed query leaves it unset
+   */
+  rowCount?: number
   /**
    * Aggregated token usage across all LLM calls made while serving the request.
-   * Omitted when no tokens were consumed (e.g. failures before any LLM call).
+   * Omitted when no tokens were consumed (e.g. failures before any LLM call) or the provider
+   * does not report usage.
    */
   tokenUsage?: TokenUsage
+  /**
+   * Model the LLM calls were sent to, as resolved from the `model` option (e.g. `gpt-4o-mini`
+   * for `openai::gpt-4o-mini`); with `answerModel` set, the model that generated the query.
+   * Omitted when the model could not be resolved.
+   */
+  modelUsed?: string
+  /**
+   * How long each pipeline stage took, for the stages that ran before the call finished or
+   * failed. Omitted when the call failed before the pipeline started, e.g. on a timeout.
+   */
+  timings?: StageTimings
+  /** FalkorDB's execution plan for `cypherQuery`, one operation per line; set by `explainQuery` */
+  queryPlan?: string
+  /**
+   * Values of the parameters `cypherQuery` references as `$name`, when the model parameterized
+   * it. They are passed to FalkorDB separately when the query runs.
+   */
+  parameters?: Record<string, any>
+  /**
+   * The prompt sent to the model to generate `cypherQuery`, system prompt included, one
+   * `[role]`-headed section per message; set only when the client's `debug` option is on
+   */
+  prompt?: string
+  /**
+   * The node labels `schemaTopK` kept in `prompt`, in schema order; set only when both
+   * `schemaTopK` and `debug` are on
+   */
+  schemaLabels?: Array<string>
+  /**
+   * The model's own rating, from 0 to 1, of how likely `cypherQuery` is to answer the question
+   * correctly; set only when the client's `withConfidence` option is on. A heuristic: models
+   * are often confident about wrong queries, so use it to flag queries for review rather than
+   * to prove them right
+   */
+  queryConfidence?: number
+  /**
+   * FalkorDB's error message for `cypherQuery` when FalkorDB rejected it (a type mismatch, an
+   * unknown function, ...), less the reply's leading error code, which the FalkorDB client
+   * drops (`mismatch: ...` of `Type mismatch: ...`); set on `error.response` of
+   * `QUERY_EXECUTION` rejections
+   */
+  executionError?: string
+  /**
+   * The model's reply to query generation when it held no Cypher query (an apology, or a
+   * clarifying question worth showing the user); set on `error.response` of
+   * `NO_QUERY_GENERATED` rejections, unless the reply was empty
+   */
+  modelReply?: string
+  /**
+   * How many times self-healing regenerated `cypherQuery` after FalkorDB rejected it; set
+   * once the query has been executed, also on `error.response` of `QUERY_EXECUTION`
+   * rejections
+   */
+  selfHealAttempts?: number
+  /**
+   * Token for `nextPage` to fetch the rows after `resultSet`; set by `textToCypherPaged` when
+   * the query returned more rows than the page holds
+   */
+  cursor?: string
+  /**
+   * Identifier of the call, appended to each query it runs as a `/* trace_id: ... *\/`
+   * comment so it shows up in FalkorDB's logs and slow log; the call's `traceId`, or its
+   * `requestId`
+   */
+  traceId?: string
+  /**
+   * Identifier of the call for correlating it with your own logs and traces: the call's
+   * `traceId` when one is given, and otherwise a random UUID. Every log event the call emits
+   * carries it as `requestId`, as does the error a failed call rejects with
+   */
+  requestId?: string
+  /**
+   * Problems spotted in `cypherQuery` that did not stop it from running, such as a node label
+   * or relationship type missing from the schema, which makes a query silently match nothing
+   */
+  warnings: Array<string>
+  /**
+   * What `cypherQuery` does to the graph, from its clauses: "read" when it has no write clause
+   * (`CREATE`, `MERGE`, `DELETE`, `SET`, `REMOVE`, `DROP`), "write" when it writes without
+   * matching existing data, or "mixed" when it both `MATCH`es and writes. Informational: only
+   * `readOnly` mode refuses queries that write
+   */
+  queryKind?: string
+  /** Code of the failure when status is "error", as on the rejection's `error.code` */
+  errorCode?: ErrorCode
+  /**
+   * Pipeline stage that failed when status is "error", if the failure belongs to one; omitted
+   * for failures such as timeouts, aborts, and connection errors
+   */
+  errorStage?: PipelineStage
 }
 
 /**
@@ -258,6 +2095,16 @@ export interface TokenUsage {
   completionTokens: number
   /** Total tokens consumed across all LLM calls */
   totalTokens: number
+  /**
+   * Input tokens the provider read from its prompt cache, included in `promptTokens`. Unset
+   * when the provider reported no cache reads.
+   */
+  cachedPromptTokens?: number
+  /**
+   * Input tokens the provider wrote to its prompt cache, included in `promptTokens`. Unset
+   * when the provider reported no cache writes.
+   */
+  cacheCreationTokens?: number
 }
 
 /** A user-defined function to surface to the model. */
@@ -588,3 +588,8 @@ if (!nativeBinding) {
 
 module.exports = nativeBinding
 module.exports.TextToCypher = nativeBinding.TextToCypher
+module.exports.ErrorCode = nativeBinding.ErrorCode
+module.exports.PipelineStage = nativeBinding.PipelineStage
+module.exports.StagePhase = nativeBinding.StagePhase
+module.exports.LogLevel = nativeBinding.LogLevel
+module.exports.setDefaultOptions = nativeBinding.setDefaultOptions
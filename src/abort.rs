@@ -0,0 +1,45 @@
+//! Cancellation of in-flight calls from a JS `AbortSignal`
+//!
+//! napi's own `AbortSignal` can only cancel libuv work that has not started yet, so it cannot stop
+//! an async method mid-flight. [`Signal`] instead hooks the JS signal's abort callback up to a
+//! [`futures::future::AbortHandle`], which drops the method's future on the runtime as soon as the
+//! signal fires.
+
+use crate::error::{ClientError, ErrorCode};
+use futures::future::{AbortHandle, AbortRegistration, Abortable};
+use napi::bindgen_prelude::*;
+use napi::sys;
+use std::future::Future;
+
+/// An `AbortSignal` passed from JS, converted into a handle the runtime can observe.
+pub struct Signal {
+    registration: AbortRegistration,
+}
+
+impl Signal {
+    /// Drives `future` to completion unless the signal fires first.
+    ///
+    /// On abort the future is dropped where it stands, which closes any FalkorDB connection or LLM
+    /// request it had open; both are per-call, so nothing shared is left half-used.
+    pub async fn guard<F: Future>(self, future: F) -> std::result::Result<F::Output, ClientError> {
+        Abortable::new(future, self.registration)
+            .await
+            .map_err(|_| ClientError::new(ErrorCode::Aborted, "Operation aborted"))
+    }
+}
+
+impl FromNapiValue for Signal {
+    unsafe fn from_napi_value(env: sys::napi_env, napi_val: sys::napi_value) -> Result<Self> {
+        let (handle, registration) = AbortHandle::new_pair();
+
+        // A signal that already fired will never call `onabort` again.
+        let aborted: Option<bool> = Object::from_raw(env, napi_val).get("aborted")?;
+        if aborted.unwrap_or(false) {
+            handle.abort();
+        } else {
+            AbortSignal::from_napi_value(env, napi_val)?.on_abort(move || handle.abort());
+        }
+
+        Ok(Self { registration })
+    }
+}
@@ -0,0 +1,128 @@
+//! Structured answers
+//!
+//! An answer meant for a program (a UI card, a form) is awkward to pull out of prose. With
+//! `answerSchema` set, answer generation asks providers that can constrain a reply to a JSON
+//! schema (the same ones as [`crate::structured_output`]) for an answer matching the caller's
+//! schema. [`AnswerSchema::parse`] checks the reply against the schema before it is handed out as
+//! `answerJson`; other providers, and replies that do not match, keep the free-text answer.
+//!
+//! The check covers the keywords strict structured output uses (`type`, `properties`,
+//! `required`, `additionalProperties`, `items`, `enum`, `const`, `anyOf`) and ignores the rest,
+//! such as `description` and `format`.
+
+use genai::chat::JsonSpec;
+use serde_json::{Map, Value};
+
+/// Sent as a system message ahead of the answer request, whose question template otherwise asks
+/// for prose followed by a confidence line.
+pub(crate) const PROMPT: &str = "Reply with a JSON object matching the given schema that holds \
+    the answer, without Markdown, commentary, or a CONFIDENCE line.";
+
+/// The schema answers are constrained to.
+#[derive(Debug, Clone)]
+pub(crate) struct AnswerSchema {
+    schema: Value,
+}
+
+impl AnswerSchema {
+    /// Checks that `schema` describes an object, as providers require of a structured reply's
+    /// root, returning why not otherwise.
+    pub(crate) fn new(schema: Map<String, Value>) -> Result<Self, String> {
+        if schema.get("type").and_then(Value::as_str) != Some("object") {
+            return Err("the root must be an object schema (\"type\": \"object\")".to_string());
+        }
+        Ok(Self {
+            schema: Value::Object(schema),
+        })
+    }
+
+    pub(crate) fn response_format(&self) -> JsonSpec {
+        JsonSpec::new("answer", self.schema.clone())
+    }
+
+    /// The reply as JSON, when it is a JSON object (optionally in a ```json fence) matching the
+    /// schema.
+    pub(crate) fn parse(&self, reply: &str) -> Option<Value> {
+        let reply = reply.trim();
+        let reply = reply
+            .strip_prefix("```json")
+            .and_then(|rest| rest.strip_suffix("```"))
+            .unwrap_or(reply);
+        let value = serde_json::from_str(reply).ok()?;
+        matches(&self.schema, &value).then_some(value)
+    }
+}
+
+/// Whether `value` matches `schema`.
+fn matches(schema: &Value, value: &Value) -> bool {
+    let Some(schema) = schema.as_object() else {
+        // `true` accepts anything and `false` nothing; other values are not schemas.
+        return schema.as_bool().unwrap_or(true);
+    };
+    if let Some(types) = schema.get("type") {
+        let types = match types {
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            types => types.as_str().into_iter().collect::<Vec<_>>(),
+        };
+        if !types.iter().any(|&name| has_type(value, name)) {
+            return false;
+        }
+    }
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            return false;
+        }
+    }
+    if schema
+        .get("const")
+        .is_some_and(|constant| constant != value)
+    {
+        return false;
+    }
+    if let Some(options) = schema.get("anyOf").and_then(Value::as_array) {
+        if !options.iter().any(|option| matches(option, value)) {
+            return false;
+        }
+    }
+    match value {
+        Value::Object(object) => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            let required = schema.get("required").and_then(Value::as_array);
+            if required
+                .into_iter()
+                .flatten()
+                .any(|name| name.as_str().is_some_and(|name| !object.contains_key(name)))
+            {
+                return false;
+            }
+            object.iter().all(|(name, item)| {
+                match properties.and_then(|properties| properties.get(name)) {
+                    Some(property) => matches(property, item),
+                    None => schema
+                        .get("additionalProperties")
+                        .is_none_or(|additional| matches(additional, item)),
+                }
+            })
+        }
+        Value::Array(items) => schema
+            .get("items")
+            .is_none_or(|schema| items.iter().all(|item| matches(schema, item))),
+        _ => true,
+    }
+}
+
+/// Whether `value` is of the JSON schema type `name`.
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
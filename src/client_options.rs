@@ -0,0 +1,300 @@
+//! Client option checks
+//!
+//! `new TextToCypher(...)` throws on the first invalid option it meets, which suits application
+//! code but not config-validation tooling, which wants every problem in a config at once and no
+//! client. [`check`] holds the constructor's checks, run on their own: it reports each failing
+//! option with the message the constructor throws for it, without connecting to anything or
+//! keeping state. The constructor runs it first and throws the first error.
+
+use crate::answer_format::AnswerSchema;
+use crate::connection::{self, ConnectionOptions};
+use crate::pipeline::ApiKey;
+use crate::prompt_template::PromptTemplate;
+use crate::{
+    extra_headers, provider_adapter, user_agent, validate_examples, validate_llm_endpoint,
+    validate_max_rows, validate_max_tokens, ClientOptions,
+};
+use napi_derive::napi;
+use std::num::NonZeroU8;
+
+/// An option `validateOptions` found invalid
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct OptionError {
+    /// The option, as named in `ClientOptions`, e.g. `poolSize`
+    pub field: String,
+    /// Why it is invalid, as the constructor's error message puts it
+    pub message: String,
+}
+
+/// Outcome of checking client options with `validateOptions`
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct OptionsValidation {
+    /// Whether `new TextToCypher(...)` would accept the options
+    pub valid: bool,
+    /// Every invalid option, in the order the constructor checks them; empty when valid
+    pub errors: Vec<OptionError>,
+}
+
+/// Every invalid option in `options`, in the order the constructor checks them.
+pub(crate) fn check(options: &ClientOptions) -> Vec<OptionError> {
+    let mut errors = Vec::new();
+    let mut report = |field: &str, result: Result<(), String>| {
+        if let Err(message) = result {
+            errors.push(OptionError {
+                field: field.to_string(),
+                message,
+            });
+        }
+    };
+    let connection_options = connection_options(options);
+
+    report(
+        "provider",
+        fails(
+            options
+                .provider
+                .as_deref()
+                .map(provider_adapter)
+                .transpose(),
+        ),
+    );
+    report(
+        "falkordbConnection",
+        fails(connection::assemble(
+            &options.falkordb_connection,
+            "falkordbConnection",
+            &connection_options,
+        )),
+    );
+    report(
+        "falkordbFallbacks",
+        fails(falkordb_fallbacks(options, &connection_options)),
+    );
+    let api_key_field = match (&options.api_key, &options.api_key_env) {
+        (None, Some(_)) => "apiKeyEnv",
+        _ => "apiKey",
+    };
+    report(
+        api_key_field,
+        fails(api_key(
+            options.api_key.clone(),
+            options.api_key_env.clone(),
+        )),
+    );
+    report(
+        "llmEndpoint",
+        fails(validate_llm_endpoint(options.llm_endpoint.as_deref())),
+    );
+    let headers = options
+        .extra_headers
+        .clone()
+        .map(|headers| extra_headers(headers, options.allow_reserved_headers.unwrap_or(false)))
+        .transpose();
+    report("extraHeaders", fails(headers.as_ref()));
+    report(
+        "userAgent",
+        fails(user_agent(
+            options.user_agent.clone(),
+            &headers.ok().flatten().unwrap_or_default(),
+        )),
+    );
+    report(
+        "selfHealMaxAttempts",
+        at_least_one("selfHealMaxAttempts", options.self_heal_max_attempts),
+    );
+    report(
+        "examples",
+        fails(
+            options
+                .examples
+                .as_deref()
+                .map(validate_examples)
+                .transpose(),
+        ),
+    );
+    report(
+        "promptTemplate",
+        fails(
+            options
+                .prompt_template
+                .clone()
+                .map(PromptTemplate::parse)
+                .transpose(),
+        ),
+    );
+    if options.retry_policy.is_some() && options.max_retries.is_some() {
+        report(
+            "retryPolicy",
+            Err("Invalid retryPolicy: cannot be combined with maxRetries".to_string()),
+        );
+    }
+    report(
+        "poolSize",
+        fails(options.pool_size.map(pool_size).transpose()),
+    );
+    report(
+        "connectionIdleTimeoutMs",
+        at_least_one(
+            "connectionIdleTimeoutMs",
+            options.connection_idle_timeout_ms,
+        ),
+    );
+    report(
+        "connectionKeepaliveMs",
+        at_least_one("connectionKeepaliveMs", options.connection_keepalive_ms),
+    );
+    report(
+        "queryTimeoutMs",
+        at_least_one("queryTimeoutMs", options.query_timeout_ms),
+    );
+    report("maxRows", fails(validate_max_rows(options.max_rows)));
+    report(
+        "enforceLimit",
+        at_least_one("enforceLimit", options.enforce_limit),
+    );
+    report(
+        "resultCacheSize",
+        at_least_one("resultCacheSize", options.result_cache_size),
+    );
+    if let Some(temperature) = options.temperature.filter(|t| !(0.0..=2.0).contains(t)) {
+        report(
+            "temperature",
+            Err(format!(
+                "Invalid temperature: {temperature}. Must be between 0.0 and 2.0"
+            )),
+        );
+    }
+    if let Some(top_p) = options.top_p.filter(|p| !(0.0..=1.0).contains(p)) {
+        report(
+            "topP",
+            Err(format!(
+                "Invalid topP: {top_p}. Must be between 0.0 and 1.0"
+            )),
+        );
+    }
+    report("seed", fails(options.seed.map(seed).transpose()));
+    report(
+        "maxTokens",
+        fails(validate_max_tokens("maxTokens", options.max_tokens)),
+    );
+    report(
+        "answerMaxTokens",
+        fails(validate_max_tokens(
+            "answerMaxTokens",
+            options.answer_max_tokens,
+        )),
+    );
+    report(
+        "answerSchema",
+        options
+            .answer_schema
+            .clone()
+            .map(AnswerSchema::new)
+            .transpose()
+            .map(drop)
+            .map_err(|e| format!("Invalid answerSchema: {e}")),
+    );
+    report(
+        "schemaTopK",
+        at_least_one("schemaTopK", options.schema_top_k),
+    );
+    report(
+        "maxSchemaBytes",
+        at_least_one("maxSchemaBytes", options.max_schema_bytes),
+    );
+    report(
+        "sampleRows",
+        at_least_one("sampleRows", options.sample_rows),
+    );
+    if options
+        .redact_properties
+        .iter()
+        .flatten()
+        .any(|name| name.trim().is_empty())
+    {
+        report(
+            "redactProperties",
+            Err("Invalid redactProperties: names must not be blank".to_string()),
+        );
+    }
+    errors
+}
+
+/// `result` without its value, and with its error as a message.
+fn fails<T, E: ToString>(result: Result<T, E>) -> Result<(), String> {
+    result.map(drop).map_err(|e| e.to_string())
+}
+
+/// Fails unless `value`, when set, is at least 1.
+fn at_least_one(option: &str, value: Option<u32>) -> Result<(), String> {
+    match value {
+        Some(0) => Err(format!("Invalid {option}: 0. Must be at least 1")),
+        _ => Ok(()),
+    }
+}
+
+/// The connection settings given next to `falkordbConnection`.
+pub(crate) fn connection_options(options: &ClientOptions) -> ConnectionOptions<'_> {
+    ConnectionOptions {
+        username: options.falkordb_username.as_deref(),
+        password: options.falkordb_password.as_deref(),
+        database: options.falkordb_database,
+        tls: options.tls.unwrap_or(false),
+        tls_insecure: options.tls_insecure.unwrap_or(false),
+    }
+}
+
+/// `falkordbFallbacks` with the connection settings applied to each.
+pub(crate) fn falkordb_fallbacks(
+    options: &ClientOptions,
+    connection_options: &ConnectionOptions,
+) -> Result<Vec<String>, String> {
+    options
+        .falkordb_fallbacks
+        .iter()
+        .flatten()
+        .map(|fallback| {
+            if fallback.trim().is_empty() {
+                return Err("Invalid falkordbFallbacks: entries must not be blank".to_string());
+            }
+            connection::assemble(
+                fallback.trim(),
+                "falkordbFallbacks entry",
+                connection_options,
+            )
+        })
+        .collect()
+}
+
+/// The API key from `apiKey` or the variable `apiKeyEnv` names; exactly one must be set.
+pub(crate) fn api_key(
+    api_key: Option<String>,
+    api_key_env: Option<String>,
+) -> Result<ApiKey, String> {
+    match (api_key, api_key_env) {
+        (Some(_), Some(_)) => {
+            Err("Invalid options: set either apiKey or apiKeyEnv, not both".to_string())
+        }
+        (Some(api_key), None) => Ok(ApiKey::Fixed(api_key)),
+        (None, Some(name)) if name.trim().is_empty() => {
+            Err("Invalid apiKeyEnv: must not be empty".to_string())
+        }
+        (None, Some(name)) => Ok(ApiKey::Env(name.trim().to_string())),
+        (None, None) => Err("Missing API key: set apiKey or apiKeyEnv".to_string()),
+    }
+}
+
+/// `poolSize` as a connection count, which must be between 1 and 255.
+pub(crate) fn pool_size(pool_size: u32) -> Result<NonZeroU8, String> {
+    u8::try_from(pool_size)
+        .ok()
+        .and_then(NonZeroU8::new)
+        .ok_or_else(|| format!("Invalid poolSize: {pool_size}. Must be between 1 and 255"))
+}
+
+/// `seed`, which must not be negative.
+pub(crate) fn seed(seed: i64) -> Result<u64, String> {
+    u64::try_from(seed).map_err(|_| format!("Invalid seed: {seed}. Must be 0 or greater"))
+}
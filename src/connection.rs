@@ -0,0 +1,104 @@
+//! FalkorDB connection string assembly
+//!
+//! Credentials, the database, and TLS can be configured next to `falkordbConnection` instead of
+//! inside it. Everything downstream (the library's UDF discovery included) only takes a connection
+//! string, so the separate options are folded into the URL once, when the client is created, for
+//! `falkordbConnection` and each of `falkordbFallbacks` alike. The result is checked to be a string
+//! the FalkorDB client can parse, so a malformed one is rejected then rather than on first use.
+
+use falkordb::FalkorConnectionInfo;
+use url::Url;
+
+/// Scheme assumed for connection strings without one, matching the FalkorDB client.
+const DEFAULT_SCHEME: &str = "falkor://";
+
+/// Plain schemes and their TLS counterparts.
+const TLS_SCHEMES: &[(&str, &str)] = &[("falkor", "falkors"), ("redis", "rediss")];
+
+/// Connection options passed separately from the connection string.
+#[derive(Debug, Default)]
+pub(crate) struct ConnectionOptions<'a> {
+    pub(crate) username: Option<&'a str>,
+    pub(crate) password: Option<&'a str>,
+    pub(crate) database: Option<u32>,
+    /// Connect over TLS even though the string uses a plain scheme.
+    pub(crate) tls: bool,
+    /// Skip certificate verification on TLS connections.
+    pub(crate) tls_insecure: bool,
+}
+
+/// Returns `connection` with `options` applied. Each credential or database option that is set
+/// replaces the corresponding part already embedded in the string; with no options set the string
+/// is returned unchanged. `option` names where the string came from, for error messages.
+pub(crate) fn assemble(
+    connection: &str,
+    option: &str,
+    options: &ConnectionOptions,
+) -> Result<String, String> {
+    let ConnectionOptions {
+        username,
+        password,
+        database,
+        tls,
+        tls_insecure,
+    } = *options;
+    if username.is_none() && password.is_none() && database.is_none() && !tls && !tls_insecure {
+        return parsed(connection.to_string(), option);
+    }
+
+    let mut url = if connection.contains("://") {
+        Url::parse(connection)
+    } else {
+        Url::parse(&format!("{DEFAULT_SCHEME}{connection}"))
+    }
+    .map_err(|e| format!("Invalid {option}: {e}"))?;
+
+    if !url.has_host() {
+        return Err(format!(
+            "Invalid {option}: '{}' URLs cannot carry credentials, a database, or TLS settings",
+            url.scheme()
+        ));
+    }
+
+    // Setters only fail for URLs without a host, which were rejected above.
+    if let Some(username) = username {
+        let _ = url.set_username(username);
+    }
+    if let Some(password) = password {
+        let _ = url.set_password(Some(password));
+    }
+    if let Some(database) = database {
+        url.set_path(&format!("/{database}"));
+    }
+
+    if tls {
+        let secure = TLS_SCHEMES
+            .iter()
+            .find(|(plain, secure)| url.scheme() == *plain || url.scheme() == *secure)
+            .map(|(_, secure)| *secure)
+            .ok_or_else(|| format!("TLS is not supported for '{}' URLs", url.scheme()))?;
+        let _ = url.set_scheme(secure);
+    }
+    if tls_insecure {
+        if !TLS_SCHEMES
+            .iter()
+            .any(|(_, secure)| url.scheme() == *secure)
+        {
+            return Err(
+                "tlsInsecure requires a TLS connection: set tls or use a falkors:// URL"
+                    .to_string(),
+            );
+        }
+        // The redis client reads this fragment as "accept any certificate".
+        url.set_fragment(Some("insecure"));
+    }
+
+    parsed(url.into(), option)
+}
+
+/// `connection`, when the FalkorDB client can parse it.
+fn parsed(connection: String, option: &str) -> Result<String, String> {
+    FalkorConnectionInfo::try_from(connection.as_str())
+        .map_err(|e| format!("Invalid {option}: {e}"))?;
+    Ok(connection)
+}
@@ -0,0 +1,62 @@
+//! Conversation context
+//!
+//! A follow-up such as "now filter those to only 2020" refers to the rows an earlier turn
+//! returned, which the model never saw: it only wrote that turn's query. An assistant message
+//! passed to `textToCypherWithMessages` can carry its turn's `cypherQuery` and `cypherResult`,
+//! and [`turn_context`] folds them into the message the model reads, so the next query can build
+//! on the earlier one and its rows. Results longer than [`MAX_RESULT_CHARS`] are cut down by
+//! [`summarize_result`] to keep every later request of the conversation small.
+
+/// Most characters of an earlier turn's result sent to the model.
+pub(crate) const MAX_RESULT_CHARS: usize = 4000;
+
+/// The content of an assistant message, followed by the query its turn ran and what the query
+/// returned, when the message carries them.
+pub(crate) fn turn_context(
+    content: &str,
+    cypher_query: Option<&str>,
+    cypher_result: Option<&str>,
+) -> String {
+    let mut context = content.to_string();
+    if let Some(query) = cypher_query {
+        context.push_str(&format!(
+            "\n\nThe Cypher query for this answer was:\n{query}"
+        ));
+    }
+    if let Some(result) = cypher_result {
+        let returned = match cypher_query {
+            Some(_) => "It returned",
+            None => "The query for this answer returned",
+        };
+        context.push_str(&format!("\n\n{returned}:\n{}", summarize_result(result)));
+    }
+    context.trim_start().to_string()
+}
+
+/// `result` as it is sent to the model: whole when it fits in [`MAX_RESULT_CHARS`], and otherwise
+/// its first lines (one per row in the text rendering) up to the limit, followed by how many
+/// lines were left out. A result whose first line alone is over the limit is cut mid-line.
+pub(crate) fn summarize_result(result: &str) -> String {
+    let result = result.trim_end();
+    if result.chars().count() <= MAX_RESULT_CHARS {
+        return result.to_string();
+    }
+    let lines = result.lines().collect::<Vec<_>>();
+    let mut chars = 0;
+    let kept = lines
+        .iter()
+        .take_while(|line| {
+            chars += line.chars().count() + 1;
+            chars <= MAX_RESULT_CHARS
+        })
+        .count();
+    if kept == 0 {
+        let cut = result.chars().take(MAX_RESULT_CHARS).collect::<String>();
+        return format!("{cut}… (cut short at {MAX_RESULT_CHARS} characters)");
+    }
+    format!(
+        "{}\n… ({} more lines not shown)",
+        lines[..kept].join("\n"),
+        lines.len() - kept
+    )
+}
@@ -0,0 +1,539 @@
+//! Lightweight lexical inspection of generated Cypher
+//!
+//! This is not a parser: it tokenizes just enough (string literals, comments, backtick-quoted
+//! identifiers) to find clause keywords without being fooled by property names, labels, map keys,
+//! or text inside literals. It also picks out the node labels and relationship types a query
+//! names. The only thing it reads in full is a `CYPHER name=value ...` parameter header, whose
+//! values are plain literals.
+
+use serde_json::{Map, Number, Value};
+
+/// Clause keywords that modify the graph.
+const WRITE_CLAUSES: &[&str] = &["CREATE", "MERGE", "DELETE", "SET", "REMOVE", "DROP"];
+
+/// Returns the first write clause (`CREATE`, `MERGE`, `DELETE`, `SET`, `REMOVE`, `DROP`) found in
+/// `query`, if any.
+pub(crate) fn find_write_clause(query: &str) -> Option<&'static str> {
+    keywords(query).find_map(|word| {
+        WRITE_CLAUSES
+            .iter()
+            .copied()
+            .find(|clause| word.eq_ignore_ascii_case(clause))
+    })
+}
+
+/// Whether `query`, as extracted from a model's reply, can be a query rather than prose the
+/// extraction kept from a reply without one, e.g. `with that.` from "I can't help with that.": a
+/// query has a parenthesis (a pattern or a function call) or a `RETURN`.
+pub(crate) fn holds_query(query: &str) -> bool {
+    query.contains('(')
+        || query
+            .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .any(|word| word.eq_ignore_ascii_case("RETURN"))
+}
+
+/// Classifies `query` by the clauses it uses: `"write"` when it has a write clause but does not
+/// `MATCH` existing data, `"mixed"` when it does both, and `"read"` when it writes nothing.
+pub(crate) fn query_kind(query: &str) -> &'static str {
+    let mut writes = false;
+    let mut matches = false;
+    for word in keywords(query) {
+        writes |= WRITE_CLAUSES
+            .iter()
+            .any(|clause| word.eq_ignore_ascii_case(clause));
+        matches |= word.eq_ignore_ascii_case("MATCH");
+    }
+    match (writes, matches) {
+        (false, _) => "read",
+        (true, false) => "write",
+        (true, true) => "mixed",
+    }
+}
+
+/// Iterates over the bare words of `query` that can be clause keywords, skipping literals,
+/// comments, quoted identifiers, and words used as property keys, labels, map keys, or parameters.
+fn keywords(query: &str) -> impl Iterator<Item = &str> {
+    scan(query).keywords.into_iter().map(|keyword| keyword.word)
+}
+
+/// A word [`keywords`] yields, with the number of brackets it is nested in.
+struct Keyword<'a> {
+    word: &'a str,
+    depth: usize,
+}
+
+struct Scan<'a> {
+    keywords: Vec<Keyword<'a>>,
+    /// Offset just past the last character that is neither whitespace nor part of a comment.
+    end: usize,
+}
+
+fn scan(query: &str) -> Scan<'_> {
+    let bytes = query.as_bytes();
+    let mut keywords = Vec::new();
+    let mut depth = 0usize;
+    let mut end = 0;
+    let mut i = 0;
+    // Last significant character before the current position (whitespace excluded).
+    let mut previous = b' ';
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        match c {
+            b'\'' | b'"' | b'`' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != c {
+                    if bytes[i] == b'\\' && c != b'`' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i += 1;
+                previous = c;
+                end = i.min(bytes.len());
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i < bytes.len() && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' => {
+                let start = i;
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+                let next = bytes[i..]
+                    .iter()
+                    .copied()
+                    .find(|b| !b.is_ascii_whitespace());
+                if !matches!(previous, b'.' | b':' | b'$') && next != Some(b':') {
+                    keywords.push(Keyword {
+                        word: &query[start..i],
+                        depth,
+                    });
+                }
+                previous = b'a';
+                end = i;
+            }
+            c if c.is_ascii_whitespace() => i += 1,
+            _ => {
+                match c {
+                    b'(' | b'[' | b'{' => depth += 1,
+                    b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                previous = c;
+                i += 1;
+                end = i;
+            }
+        }
+    }
+
+    Scan { keywords, end }
+}
+
+/// Appends `LIMIT limit` to a read query whose final `RETURN` has no `LIMIT` of its own, so the
+/// rows it returns are bounded, dropping a trailing semicolon. `None` when the query writes, does
+/// not end in a `RETURN` (and so returns no rows), combines results with `UNION`, where the
+/// `LIMIT` would only bound the last part, or already limits its rows.
+pub(crate) fn add_limit(query: &str, limit: u32) -> Option<String> {
+    append_to_return(query, &format!("LIMIT {limit}"), &["LIMIT"])
+}
+
+/// Appends `SKIP skip LIMIT limit` to a read query, so FalkorDB returns only that window of its
+/// rows. `None` in the cases [`add_limit`] gives up on, and when the final `RETURN` already
+/// skips rows.
+pub(crate) fn add_page(query: &str, skip: u32, limit: u32) -> Option<String> {
+    append_to_return(
+        query,
+        &format!("SKIP {skip} LIMIT {limit}"),
+        &["SKIP", "LIMIT"],
+    )
+}
+
+/// Appends `clause` to the final `RETURN` of a read query that has none of `existing` after it.
+fn append_to_return(query: &str, clause: &str, existing: &[&str]) -> Option<String> {
+    if find_write_clause(query).is_some() {
+        return None;
+    }
+    let Scan { keywords, end } = scan(query);
+    let clauses = keywords
+        .iter()
+        .filter(|keyword| keyword.depth == 0)
+        .map(|keyword| keyword.word)
+        .collect::<Vec<_>>();
+    let is = |keyword: &'static str| move |word: &&str| word.eq_ignore_ascii_case(keyword);
+    if clauses.iter().any(is("UNION")) {
+        return None;
+    }
+    let last_return = clauses.iter().rposition(is("RETURN"))?;
+    if clauses[last_return..].iter().any(|word| {
+        existing
+            .iter()
+            .any(|keyword| word.eq_ignore_ascii_case(keyword))
+    }) {
+        return None;
+    }
+
+    let body = query[..end].strip_suffix(';').unwrap_or(&query[..end]);
+    Some(format!("{} {clause}{}", body.trim_end(), &query[end..]))
+}
+
+/// Node labels and relationship types named by a query, each listed once in order of appearance.
+#[derive(Debug, Default)]
+pub(crate) struct References<'a> {
+    pub(crate) labels: Vec<&'a str>,
+    pub(crate) relationship_types: Vec<&'a str>,
+}
+
+/// Collects the node labels (`(n:Person)`, `WHERE n:Person`) and relationship types
+/// (`-[:KNOWS]->`, `-[:KNOWS|:FOLLOWS]-`) named in `query`, backtick-quoted ones included.
+///
+/// A colon directly inside a map (`{name: 'Alice'}`) separates a key from its value, so it names
+/// nothing; that also skips patterns written directly inside a `CALL { ... }` subquery.
+pub(crate) fn references(query: &str) -> References<'_> {
+    let bytes = query.as_bytes();
+    let mut references = References::default();
+    // Open brackets, innermost last, with `-` standing for the `[` of a relationship pattern.
+    let mut brackets = Vec::new();
+    let mut i = 0;
+    // Last significant character before the current position (whitespace excluded).
+    let mut previous = b' ';
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        match c {
+            b'\'' | b'"' | b'`' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != c {
+                    if bytes[i] == b'\\' && c != b'`' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i += 1;
+                previous = c;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i < bytes.len() && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            b'(' | b'{' => {
+                brackets.push(c);
+                previous = c;
+                i += 1;
+            }
+            b'[' => {
+                brackets.push(if previous == b'-' { b'-' } else { c });
+                previous = c;
+                i += 1;
+            }
+            b')' | b'}' | b']' => {
+                brackets.pop();
+                previous = c;
+                i += 1;
+            }
+            b':' if brackets.last() != Some(&b'{') => {
+                let relationship = brackets.last() == Some(&b'-');
+                let mut cursor = Cursor {
+                    text: query,
+                    position: i + 1,
+                };
+                while let Some(name) = cursor.name() {
+                    let names = if relationship {
+                        &mut references.relationship_types
+                    } else {
+                        &mut references.labels
+                    };
+                    if !names.contains(&name) {
+                        names.push(name);
+                    }
+                    // Only relationship patterns accept alternatives: `[:KNOWS|FOLLOWS]`.
+                    if !(relationship && cursor.eat(b'|')) {
+                        break;
+                    }
+                    cursor.eat(b':');
+                }
+                i = cursor.position;
+                previous = b'a';
+            }
+            c if c.is_ascii_whitespace() => i += 1,
+            _ => {
+                previous = c;
+                i += 1;
+            }
+        }
+    }
+
+    references
+}
+
+/// Splits a query that starts with a `CYPHER name=value ...` header into the header's parameters
+/// and the query after it.
+///
+/// Returns `None` when there is no header, or when a value is anything but a literal (string,
+/// number, boolean, null, list, or map), such as a function call; such a query is best run as
+/// written.
+pub(crate) fn split_parameters(query: &str) -> Option<(Map<String, Value>, &str)> {
+    let mut cursor = Cursor {
+        text: query,
+        position: 0,
+    };
+    if !cursor.identifier()?.eq_ignore_ascii_case("CYPHER") {
+        return None;
+    }
+
+    let mut parameters = Map::new();
+    loop {
+        let start = cursor.position;
+        match cursor.identifier() {
+            Some(name) if cursor.eat(b'=') => {
+                parameters.insert(name.to_string(), cursor.literal()?);
+            }
+            _ => {
+                cursor.position = start;
+                break;
+            }
+        }
+    }
+
+    let body = cursor.rest().trim_start();
+    (!parameters.is_empty() && !body.is_empty()).then_some((parameters, body))
+}
+
+/// Puts `parameters` in a `CYPHER name=value ...` header ahead of `body`, the reverse of
+/// [`split_parameters`]; `body` comes back as it is when there are no parameters.
+pub(crate) fn join_parameters(parameters: &Map<String, Value>, body: &str) -> String {
+    if parameters.is_empty() {
+        return body.to_string();
+    }
+    let header = parameters
+        .iter()
+        .map(|(name, value)| format!("{name}={}", literal(value)))
+        .collect::<Vec<_>>();
+    format!("CYPHER {} {body}", header.join(" "))
+}
+
+/// Whether `name` can be written bare as a parameter name or map key.
+pub(crate) fn is_identifier(name: &str) -> bool {
+    name.bytes().next().is_some_and(|b| !b.is_ascii_digit())
+        && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+/// Writes a JSON value as the Cypher literal [`Cursor::literal`] reads back.
+fn literal(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => {
+            let mut quoted = String::with_capacity(s.len() + 2);
+            quoted.push('\'');
+            for c in s.chars() {
+                match c {
+                    '\\' => quoted.push_str("\\\\"),
+                    '\'' => quoted.push_str("\\'"),
+                    '\n' => quoted.push_str("\\n"),
+                    '\r' => quoted.push_str("\\r"),
+                    '\t' => quoted.push_str("\\t"),
+                    c => quoted.push(c),
+                }
+            }
+            quoted.push('\'');
+            quoted
+        }
+        Value::Array(items) => {
+            let items = items.iter().map(literal).collect::<Vec<_>>();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Object(entries) => {
+            let entries = entries
+                .iter()
+                .map(|(key, value)| {
+                    if is_identifier(key) {
+                        format!("{key}: {}", literal(value))
+                    } else {
+                        format!("`{key}`: {}", literal(value))
+                    }
+                })
+                .collect::<Vec<_>>();
+            format!("{{{}}}", entries.join(", "))
+        }
+    }
+}
+
+/// Reads Cypher literals from `text`, starting at `position`.
+struct Cursor<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.position..]
+    }
+
+    /// Skips whitespace, then consumes `byte` if it comes next.
+    fn eat(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+        let found = self.rest().as_bytes().first() == Some(&byte);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn identifier(&mut self) -> Option<&'a str> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let length = rest
+            .bytes()
+            .take_while(|b| b.is_ascii_alphanumeric() || *b == b'_')
+            .count();
+        if length == 0 || rest.as_bytes()[0].is_ascii_digit() {
+            return None;
+        }
+        self.position += length;
+        Some(&rest[..length])
+    }
+
+    /// Reads a label or relationship type: an identifier, or a backtick-quoted name.
+    fn name(&mut self) -> Option<&'a str> {
+        if !self.eat(b'`') {
+            return self.identifier();
+        }
+        let rest = self.rest();
+        let length = rest.find('`')?;
+        self.position += length + 1;
+        Some(&rest[..length])
+    }
+
+    fn literal(&mut self) -> Option<Value> {
+        self.skip_whitespace();
+        match *self.rest().as_bytes().first()? {
+            quote @ (b'\'' | b'"') => {
+                self.position += 1;
+                self.string(quote).map(Value::String)
+            }
+            b'[' => {
+                self.position += 1;
+                let mut items = Vec::new();
+                if !self.eat(b']') {
+                    loop {
+                        items.push(self.literal()?);
+                        if self.eat(b']') {
+                            break;
+                        }
+                        if !self.eat(b',') {
+                            return None;
+                        }
+                    }
+                }
+                Some(Value::Array(items))
+            }
+            b'{' => {
+                self.position += 1;
+                let mut entries = Map::new();
+                if !self.eat(b'}') {
+                    loop {
+                        let key = if self.eat(b'`') {
+                            self.string(b'`')?
+                        } else {
+                            self.identifier()?.to_string()
+                        };
+                        if !self.eat(b':') {
+                            return None;
+                        }
+                        entries.insert(key, self.literal()?);
+                        if self.eat(b'}') {
+                            break;
+                        }
+                        if !self.eat(b',') {
+                            return None;
+                        }
+                    }
+                }
+                Some(Value::Object(entries))
+            }
+            b'-' | b'.' | b'0'..=b'9' => self.number(),
+            _ => match self.identifier()?.to_ascii_lowercase().as_str() {
+                "true" => Some(Value::Bool(true)),
+                "false" => Some(Value::Bool(false)),
+                "null" => Some(Value::Null),
+                _ => None,
+            },
+        }
+    }
+
+    /// Reads the rest of a literal opened by `quote`, resolving backslash escapes.
+    fn string(&mut self, quote: u8) -> Option<String> {
+        let mut value = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((index, c)) = chars.next() {
+            match c {
+                c if c == char::from(quote) => {
+                    self.position += index + 1;
+                    return Some(value);
+                }
+                '\\' if quote != b'`' => value.push(match chars.next()?.1 {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    'b' => '\u{8}',
+                    'f' => '\u{c}',
+                    'u' => {
+                        let hex: String = (0..4)
+                            .filter_map(|_| chars.next())
+                            .map(|(_, c)| c)
+                            .collect();
+                        char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?
+                    }
+                    escaped => escaped,
+                }),
+                c => value.push(c),
+            }
+        }
+        None
+    }
+
+    fn number(&mut self) -> Option<Value> {
+        let rest = self.rest();
+        let length = rest
+            .bytes()
+            .enumerate()
+            .take_while(|&(index, b)| {
+                b.is_ascii_digit()
+                    || b == b'.'
+                    || matches!(b, b'e' | b'E')
+                    || (matches!(b, b'-' | b'+')
+                        && (index == 0 || matches!(rest.as_bytes()[index - 1], b'e' | b'E')))
+            })
+            .count();
+        let literal = &rest[..length];
+        self.position += length;
+        match literal.parse::<i64>() {
+            Ok(integer) => Some(Value::from(integer)),
+            Err(_) => Number::from_f64(literal.parse().ok()?).map(Value::Number),
+        }
+    }
+}
@@ -0,0 +1,306 @@
+//! Process-wide default options
+//!
+//! Applications that create many clients (one per tenant, say) tend to repeat the same options:
+//! the provider, the endpoint, timeouts. `setDefaultOptions` stores them once for the process,
+//! and [`apply`] fills every option a later `new TextToCypher(...)` leaves unset from them, before
+//! the options are read. An option is taken from, in order, the constructor's options, the
+//! defaults, and the built-in default. `model` and `falkordbConnection` name what a client talks
+//! to and have no process-wide default; `onLog` and `rowFilter` have none either. `apiKey` and
+//! `apiKeyEnv` count as one option, so a client giving either ignores both defaults; so do
+//! `maxRetries` and `retryPolicy`.
+
+use crate::retry::RetryPolicy;
+use crate::{ClientOptions, CypherExample, UdfLibraryInput};
+use napi_derive::napi;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// The defaults set by the last `setDefaultOptions` call.
+static DEFAULTS: Mutex<Option<DefaultOptions>> = Mutex::new(None);
+
+/// Options `new TextToCypher(...)` falls back to for the ones it is not given: every
+/// `ClientOptions` field but `model`, `falkordbConnection`, `onLog`, and `rowFilter`.
+// A field added to `ClientOptions` belongs here too.
+#[napi(object, object_to_js = false)]
+#[derive(Clone)]
+pub struct DefaultOptions {
+    /// Default for `provider`
+    pub provider: Option<String>,
+    /// Default for `answerModel`
+    pub answer_model: Option<String>,
+    /// Default for `apiKey`
+    pub api_key: Option<String>,
+    /// Default for `apiKeyEnv`
+    pub api_key_env: Option<String>,
+    /// Default for `falkordbFallbacks`
+    pub falkordb_fallbacks: Option<Vec<String>>,
+    /// Default for `falkordbUsername`
+    pub falkordb_username: Option<String>,
+    /// Default for `falkordbPassword`
+    pub falkordb_password: Option<String>,
+    /// Default for `falkordbDatabase`
+    pub falkordb_database: Option<u32>,
+    /// Default for `tls`
+    pub tls: Option<bool>,
+    /// Default for `tlsInsecure`
+    pub tls_insecure: Option<bool>,
+    /// Default for `llmEndpoint`
+    pub llm_endpoint: Option<String>,
+    /// Default for `discoverUdfs`
+    pub discover_udfs: Option<bool>,
+    /// Default for `udfs`
+    pub udfs: Option<Vec<UdfLibraryInput>>,
+    /// Default for `timeoutMs`
+    pub timeout_ms: Option<u32>,
+    /// Default for `queryTimeoutMs`
+    pub query_timeout_ms: Option<u32>,
+    /// Default for `temperature`
+    pub temperature: Option<f64>,
+    /// Default for `topP`
+    pub top_p: Option<f64>,
+    /// Default for `seed`
+    pub seed: Option<i64>,
+    /// Default for `maxTokens`
+    pub max_tokens: Option<u32>,
+    /// Default for `answerMaxTokens`
+    pub answer_max_tokens: Option<u32>,
+    /// Default for `answerLanguage`
+    pub answer_language: Option<String>,
+    /// Default for `answerSchema`
+    pub answer_schema: Option<serde_json::Map<String, serde_json::Value>>,
+    /// Default for `readOnly`
+    pub read_only: Option<bool>,
+    /// Default for `systemPrompt`
+    pub system_prompt: Option<String>,
+    /// Default for `cypherDialect`
+    pub cypher_dialect: Option<String>,
+    /// Default for `examples`
+    pub examples: Option<Vec<CypherExample>>,
+    /// Default for `promptTemplate`
+    pub prompt_template: Option<String>,
+    /// Default for `maxRetries`
+    pub max_retries: Option<u32>,
+    /// Default for `retryBaseDelayMs`
+    pub retry_base_delay_ms: Option<u32>,
+    /// Default for `retryPolicy`
+    pub retry_policy: Option<RetryPolicy>,
+    /// Default for `connectRetries`
+    pub connect_retries: Option<u32>,
+    /// Default for `connectRetryDelayMs`
+    pub connect_retry_delay_ms: Option<u32>,
+    /// Default for `maxRows`
+    pub max_rows: Option<u32>,
+    /// Default for `enforceLimit`
+    pub enforce_limit: Option<u32>,
+    /// Default for `schemaCacheTtlMs`
+    pub schema_cache_ttl_ms: Option<u32>,
+    /// Default for `resultCacheTtlMs`
+    pub result_cache_ttl_ms: Option<u32>,
+    /// Default for `resultCacheSize`
+    pub result_cache_size: Option<u32>,
+    /// Default for `extraHeaders`
+    pub extra_headers: Option<HashMap<String, String>>,
+    /// Default for `allowReservedHeaders`
+    pub allow_reserved_headers: Option<bool>,
+    /// Default for `userAgent`
+    pub user_agent: Option<String>,
+    /// Default for `poolSize`
+    pub pool_size: Option<u32>,
+    /// Default for `connectionIdleTimeoutMs`
+    pub connection_idle_timeout_ms: Option<u32>,
+    /// Default for `connectionKeepaliveMs`
+    pub connection_keepalive_ms: Option<u32>,
+    /// Default for `debug`
+    pub debug: Option<bool>,
+    /// Default for `strict`
+    pub strict: Option<bool>,
+    /// Default for `schemaTopK`
+    pub schema_top_k: Option<u32>,
+    /// Default for `maxSchemaBytes`
+    pub max_schema_bytes: Option<u32>,
+    /// Default for `sampleRows`
+    pub sample_rows: Option<u32>,
+    /// Default for `redactProperties`
+    pub redact_properties: Option<Vec<String>>,
+    /// Default for `skipDiscovery`
+    pub skip_discovery: Option<bool>,
+    /// Default for `withConfidence`
+    pub with_confidence: Option<bool>,
+    /// Default for `structuredOutput`
+    pub structured_output: Option<bool>,
+    /// Default for `promptCaching`
+    pub prompt_caching: Option<bool>,
+    /// Default for `selfHeal`
+    pub self_heal: Option<bool>,
+    /// Default for `selfHealMaxAttempts`
+    pub self_heal_max_attempts: Option<u32>,
+    /// Default for `noQueryRetry`
+    pub no_query_retry: Option<bool>,
+    /// Default for `resolveErrors`
+    pub resolve_errors: Option<bool>,
+}
+
+/// Sets the options every client created afterwards falls back to for the ones it is not given,
+/// replacing the defaults of any earlier call; `setDefaultOptions({})` clears them. Clients
+/// created before the call keep their options. Beyond their types, the defaults are checked like
+/// any other option when a client is created, so an invalid one makes the constructor throw.
+///
+/// # Example
+///
+/// ```javascript
+/// setDefaultOptions({ provider: 'anthropic', apiKeyEnv: 'ANTHROPIC_API_KEY', timeoutMs: 30000 });
+///
+/// // Inherits the provider, API key, and timeout
+/// const client = new TextToCypher({
+///   model: 'claude-sonnet-4-5',
+///   falkordbConnection: 'falkor://localhost:6379',
+/// });
+/// ```
+#[napi]
+pub fn set_default_options(options: DefaultOptions) {
+    *DEFAULTS.lock().unwrap() = Some(options);
+}
+
+/// `options` with each field it leaves unset taken from the process-wide defaults, if any.
+pub(crate) fn apply(mut options: ClientOptions) -> ClientOptions {
+    let Some(defaults) = DEFAULTS.lock().unwrap().clone() else {
+        return options;
+    };
+    // Destructured so a field missing here fails to compile.
+    let DefaultOptions {
+        provider,
+        answer_model,
+        api_key,
+        api_key_env,
+        falkordb_fallbacks,
+        falkordb_username,
+        falkordb_password,
+        falkordb_database,
+        tls,
+        tls_insecure,
+        llm_endpoint,
+        discover_udfs,
+        udfs,
+        timeout_ms,
+        query_timeout_ms,
+        temperature,
+        top_p,
+        seed,
+        max_tokens,
+        answer_max_tokens,
+        answer_language,
+        answer_schema,
+        read_only,
+        system_prompt,
+        cypher_dialect,
+        examples,
+        prompt_template,
+        max_retries,
+        retry_base_delay_ms,
+        retry_policy,
+        connect_retries,
+        connect_retry_delay_ms,
+        max_rows,
+        enforce_limit,
+        schema_cache_ttl_ms,
+        result_cache_ttl_ms,
+        result_cache_size,
+        extra_headers,
+        allow_reserved_headers,
+        user_agent,
+        pool_size,
+        connection_idle_timeout_ms,
+        connection_keepalive_ms,
+        debug,
+        strict,
+        schema_top_k,
+        max_schema_bytes,
+        sample_rows,
+        redact_properties,
+        skip_discovery,
+        with_confidence,
+        structured_output,
+        prompt_caching,
+        self_heal,
+        self_heal_max_attempts,
+        no_query_retry,
+        resolve_errors,
+    } = defaults;
+    // The two ways of giving the API key are one option: a client setting either keeps both.
+    let (api_key, api_key_env) = match (&options.api_key, &options.api_key_env) {
+        (None, None) => (api_key, api_key_env),
+        _ => (None, None),
+    };
+    // So are the two ways of setting retries, which cannot be combined.
+    let (max_retries, retry_policy) = match (&options.max_retries, &options.retry_policy) {
+        (None, None) => (max_retries, retry_policy),
+        _ => (None, None),
+    };
+    macro_rules! fall_back {
+        ($($field:ident),+) => {
+            $(if options.$field.is_none() {
+                options.$field = $field;
+            })+
+        };
+    }
+    fall_back!(
+        provider,
+        answer_model,
+        api_key,
+        api_key_env,
+        falkordb_fallbacks,
+        falkordb_username,
+        falkordb_password,
+        falkordb_database,
+        tls,
+        tls_insecure,
+        llm_endpoint,
+        discover_udfs,
+        udfs,
+        timeout_ms,
+        query_timeout_ms,
+        temperature,
+        top_p,
+        seed,
+        max_tokens,
+        answer_max_tokens,
+        answer_language,
+        answer_schema,
+        read_only,
+        system_prompt,
+        cypher_dialect,
+        examples,
+        prompt_template,
+        max_retries,
+        retry_base_delay_ms,
+        retry_policy,
+        connect_retries,
+        connect_retry_delay_ms,
+        max_rows,
+        enforce_limit,
+        schema_cache_ttl_ms,
+        result_cache_ttl_ms,
+        result_cache_size,
+        extra_headers,
+        allow_reserved_headers,
+        user_agent,
+        pool_size,
+        connection_idle_timeout_ms,
+        connection_keepalive_ms,
+        debug,
+        strict,
+        schema_top_k,
+        max_schema_bytes,
+        sample_rows,
+        redact_properties,
+        skip_discovery,
+        with_confidence,
+        structured_output,
+        prompt_caching,
+        self_heal,
+        self_heal_max_attempts,
+        no_query_retry,
+        resolve_errors
+    );
+    options
+}
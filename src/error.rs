@@ -0,0 +1,273 @@
+//! Structured errors surfaced to JS
+//!
+//! Every failure of an async method rejects with an `Error` whose `code` is a stable
+//! [`ErrorCode`] string, so callers can branch on the kind of failure instead of matching
+//! messages.
+//!
+//! An async napi method can only reject with a plain `Error` built off the JS thread, so extra
+//! properties cannot be attached there. Instead, methods resolve to an [`Outcome`], whose
+//! conversion to JS runs on the JS thread: a failed outcome builds the `Error` object, attaches its
+//! details, and hands it back to napi as the rejection value.
+//!
+//! The attached details, and the message, are enumerable, so `JSON.stringify` keeps them when an
+//! error is logged or forwarded to another process.
+
+use crate::progress::PipelineStage;
+use crate::retry::FailureCategory;
+use crate::TextToCypherResponse;
+use napi::bindgen_prelude::*;
+use napi::sys;
+use napi_derive::napi;
+use std::fmt;
+
+/// Stable error codes exposed as `error.code` on rejected promises
+#[napi(string_enum = "UPPER_SNAKE")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// FalkorDB could not be reached or the connection string is invalid
+    Connection,
+    /// The graph does not exist in FalkorDB
+    GraphNotFound,
+    /// The graph schema could not be discovered
+    SchemaDiscovery,
+    /// The schema is larger than `maxSchemaBytes`, even after pruning
+    SchemaTooLarge,
+    /// The model failed to produce a valid Cypher query
+    QueryGeneration,
+    /// The model replied without a Cypher query, e.g. with an apology or a clarifying question,
+    /// kept in `error.response.modelReply` when available
+    NoQueryGenerated,
+    /// FalkorDB rejected or failed to run the generated query
+    QueryExecution,
+    /// FalkorDB aborted the generated query for running longer than `queryTimeoutMs`
+    QueryTimeout,
+    /// The model failed to produce the natural-language answer
+    AnswerGeneration,
+    /// The provider's model list could not be retrieved
+    ModelDiscovery,
+    /// The generated query was refused by read-only mode
+    ReadOnlyViolation,
+    /// The call exceeded the configured `timeoutMs`
+    Timeout,
+    /// The call was cancelled through its `AbortSignal`
+    Aborted,
+    /// An argument passed to the method is invalid
+    InvalidArgument,
+    /// The client was closed with `close()`
+    ClientClosed,
+}
+
+impl ErrorCode {
+    /// The pipeline stage failures with this code come from, when a single stage reports it.
+    pub fn stage(self) -> Option<PipelineStage> {
+        match self {
+            Self::GraphNotFound | Self::SchemaDiscovery | Self::SchemaTooLarge => {
+                Some(PipelineStage::SchemaDiscovery)
+            }
+            Self::QueryGeneration | Self::NoQueryGenerated | Self::ReadOnlyViolation => {
+                Some(PipelineStage::QueryGeneration)
+            }
+            Self::QueryExecution | Self::QueryTimeout => Some(PipelineStage::QueryExecution),
+            Self::AnswerGeneration => Some(PipelineStage::AnswerGeneration),
+            Self::Connection
+            | Self::ModelDiscovery
+            | Self::Timeout
+            | Self::Aborted
+            | Self::InvalidArgument
+            | Self::ClientClosed => None,
+        }
+    }
+}
+
+/// A failure with its code, message, and the partial response produced before it.
+pub struct ClientError {
+    code: ErrorCode,
+    message: String,
+    response: Option<Box<TextToCypherResponse>>,
+    /// FalkorDB's own error message, when FalkorDB rejected a query.
+    server_message: Option<String>,
+    /// Identifier of the call that failed, exposed to JS as `error.requestId`.
+    request_id: Option<String>,
+    /// The kind of transient failure this is, as the typed error it was built from says.
+    failure_category: Option<FailureCategory>,
+}
+
+impl ClientError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            response: None,
+            server_message: None,
+            request_id: None,
+            failure_category: None,
+        }
+    }
+
+    pub fn code(&self) -> &ErrorCode {
+        &self.code
+    }
+
+    /// Prefixes the message with the name of the operation that failed.
+    pub fn context(mut self, context: &str) -> Self {
+        self.message = format!("{}: {}", context, self.message);
+        self
+    }
+
+    /// FalkorDB's own error message, when FalkorDB rejected a query.
+    pub fn server_message(&self) -> Option<&str> {
+        self.server_message.as_deref()
+    }
+
+    /// Records FalkorDB's own error message for a query it rejected.
+    pub fn with_server_message(mut self, server_message: String) -> Self {
+        self.server_message = Some(server_message);
+        self
+    }
+
+    /// The kind of transient failure this is, when it was built from an error typed as one.
+    pub(crate) fn failure_category(&self) -> Option<FailureCategory> {
+        self.failure_category
+    }
+
+    /// Records the kind of transient failure this is, read off the error it was built from.
+    pub(crate) fn with_failure_category(mut self, category: Option<FailureCategory>) -> Self {
+        self.failure_category = category;
+        self
+    }
+
+    /// Records the identifier of the call that failed, also in its response.
+    pub fn with_request_id(mut self, request_id: String) -> Self {
+        if let Some(response) = self.response.as_mut() {
+            response
+                .request_id
+                .get_or_insert_with(|| request_id.clone());
+        }
+        self.request_id = Some(request_id);
+        self
+    }
+
+    /// Appends `suffix` to the message.
+    pub fn suffix(mut self, suffix: &str) -> Self {
+        self.message.push_str(suffix);
+        self
+    }
+
+    /// Whether the failure comes from the call's work rather than from how it was made: every
+    /// code but `INVALID_ARGUMENT` and `CLIENT_CLOSED`.
+    pub fn is_recoverable(&self) -> bool {
+        !matches!(
+            self.code,
+            ErrorCode::InvalidArgument | ErrorCode::ClientClosed
+        )
+    }
+
+    /// The partial response as an error-status response, or a bare one carrying just the message
+    /// when the failure happened before a response existed.
+    pub fn into_response(self) -> TextToCypherResponse {
+        let message = self.message;
+        match self.response {
+            Some(response) => *response,
+            None => TextToCypherResponse {
+                status: "error".to_string(),
+                schema: None,
+                cypher_query: None,
+                cypher_result: None,
+                result_set: None,
+                groups: None,
+                answer: None,
+                answer_json: None,
+                confidence: None,
+                error: Some(message),
+                answer_error: None,
+                truncated: false,
+                row_count: None,
+                token_usage: None,
+                model_used: None,
+                timings: None,
+                query_plan: None,
+                parameters: None,
+                prompt: None,
+                schema_labels: None,
+                query_confidence: None,
+                execution_error: None,
+                model_reply: None,
+                self_heal_attempts: None,
+                cursor: None,
+                trace_id: None,
+                request_id: self.request_id,
+                warnings: Vec::new(),
+                query_kind: None,
+                error_code: Some(self.code),
+                error_stage: self.code.stage(),
+            },
+        }
+    }
+
+    /// Attaches the partial response, exposed to JS as `error.response`, recording the failure's
+    /// code and stage in it.
+    pub fn with_response(mut self, mut response: TextToCypherResponse) -> Self {
+        response.error_code = Some(self.code);
+        response.error_stage = self.code.stage();
+        self.response = Some(Box::new(response));
+        self
+    }
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// The settled value of an async method: resolves with `Ok`, rejects with `Err`.
+pub struct Outcome<T>(std::result::Result<T, ClientError>);
+
+impl<T> From<std::result::Result<T, ClientError>> for Outcome<T> {
+    fn from(result: std::result::Result<T, ClientError>) -> Self {
+        Self(result)
+    }
+}
+
+impl<T> From<ClientError> for Outcome<T> {
+    fn from(error: ClientError) -> Self {
+        Self(Err(error))
+    }
+}
+
+impl<T: ToNapiValue> ToNapiValue for Outcome<T> {
+    unsafe fn to_napi_value(env: sys::napi_env, val: Self) -> Result<sys::napi_value> {
+        match val.0 {
+            Ok(value) => T::to_napi_value(env, value),
+            Err(error) => {
+                let value = JsError::from(Error::from_reason(error.message)).into_value(env);
+                let mut object = Object::from_raw(env, value);
+                // `Error` makes its own message non-enumerable, which `JSON.stringify` skips.
+                let message: Unknown = object.get_named_property("message")?;
+                object.define_properties(&[Property::new()
+                    .with_utf8_name("message")?
+                    .with_value(&message)
+                    .with_property_attributes(
+                        PropertyAttributes::Writable
+                            | PropertyAttributes::Enumerable
+                            | PropertyAttributes::Configurable,
+                    )])?;
+                object.set("code", error.code)?;
+                if let Some(request_id) = &error.request_id {
+                    object.set("requestId", request_id)?;
+                }
+                if let Some(stage) = error.code.stage() {
+                    object.set("stage", stage)?;
+                }
+                if let Some(response) = error.response {
+                    if let Some(cypher_query) = &response.cypher_query {
+                        object.set("cypherQuery", cypher_query)?;
+                    }
+                    object.set("response", *response)?;
+                }
+                // Returning the error object as an `Err` makes napi reject the promise with it.
+                Err(Error::from(Unknown::from_raw_unchecked(env, value)))
+            }
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Per-graph generation hints
+//!
+//! Operators often know things about a graph that its schema cannot say, such as the scale a
+//! property is measured on. [`GraphHints`] keeps such notes for each graph in memory on the
+//! client, and every query-generation prompt for that graph carries them, so callers benefit
+//! without passing them along.
+
+use crate::error::{ClientError, ErrorCode};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Hints by graph name.
+#[derive(Debug, Default)]
+pub(crate) struct GraphHints {
+    hints: Mutex<HashMap<String, Vec<String>>>,
+}
+
+impl GraphHints {
+    /// Replaces the hints of `graph_name`, trimmed; an empty list removes them.
+    pub(crate) fn set(&self, graph_name: &str, hints: Vec<String>) -> Result<(), ClientError> {
+        let hints: Vec<String> = hints.iter().map(|hint| hint.trim().to_string()).collect();
+        if hints.iter().any(String::is_empty) {
+            return Err(ClientError::new(
+                ErrorCode::InvalidArgument,
+                "Invalid hints: must not contain blank entries",
+            ));
+        }
+        let mut entries = self.hints.lock().unwrap();
+        if hints.is_empty() {
+            entries.remove(graph_name);
+        } else {
+            entries.insert(graph_name.to_string(), hints);
+        }
+        Ok(())
+    }
+
+    /// The hints of `graph_name`; empty when it has none.
+    pub(crate) fn get(&self, graph_name: &str) -> Vec<String> {
+        let entries = self.hints.lock().unwrap();
+        entries.get(graph_name).cloned().unwrap_or_default()
+    }
+
+    /// Drops the hints of `graph_name`, or of every graph when it is `None`.
+    pub(crate) fn clear(&self, graph_name: Option<&str>) {
+        let mut entries = self.hints.lock().unwrap();
+        match graph_name {
+            Some(graph_name) => {
+                entries.remove(graph_name);
+            }
+            None => entries.clear(),
+        }
+    }
+
+    /// The hints of `graph_name` as a prompt block, one per line; `None` when it has none.
+    pub(crate) fn prompt(&self, graph_name: &str) -> Option<String> {
+        let entries = self.hints.lock().unwrap();
+        let hints = entries.get(graph_name)?;
+        let lines: Vec<String> = hints.iter().map(|hint| format!("- {hint}")).collect();
+        Some(format!(
+            "Keep these notes about the graph in mind when writing the query:\n{}",
+            lines.join("\n")
+        ))
+    }
+}
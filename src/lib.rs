@@ -1,11 +1,62 @@
+#![recursion_limit = "256"]
 #![deny(clippy::all)]
 
+mod abort;
+mod answer_format;
+mod client_options;
+mod connection;
+mod conversation;
+mod cypher;
+mod defaults;
+mod error;
+mod graph_hints;
+mod log;
+mod metrics;
+mod paging;
+mod pipeline;
+mod progress;
+mod prompt_cache;
+mod prompt_template;
+mod redaction;
+mod result_cache;
+mod result_set;
+mod retry;
+mod schema;
+mod schema_cache;
+mod structured_output;
+
+use abort::Signal;
+use answer_format::AnswerSchema;
+pub use client_options::{OptionError, OptionsValidation};
+pub use defaults::{set_default_options, DefaultOptions};
+use error::{ClientError, ErrorCode, Outcome};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use log::LogSink;
+pub use log::{LogEvent, LogLevel};
+pub use metrics::{Metrics, StageFailures};
 use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
 use napi_derive::napi;
+use paging::PageCursor;
+pub use paging::ResultPage;
+use pipeline::{Pipeline, ProgressSink, RowFilter, RunOptions, DEFAULT_USER_AGENT};
+pub use progress::{PipelineStage, ProgressEvent, StagePhase, StageTimings};
+use prompt_template::PromptTemplate;
+use redaction::Redaction;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT};
+use result_cache::{ResultCache, ResultKey};
+use result_set::ResultFormat;
+pub use result_set::{CypherResultSet, ResultGroup};
+pub use retry::{RetryPolicy, RetryRule};
+pub use schema::{Schema, SchemaAttribute, SchemaEntity, SchemaRelation};
+use schema_cache::SchemaCache;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
 use text_to_cypher::{
-    AdapterKind, ChatMessage, ChatRequest, ChatRole, TextToCypherClient, UdfCatalog, UdfFunction,
-    UdfLibrary,
+    AdapterKind, ChatMessage, ChatRequest, ChatRole, UdfCatalog, UdfFunction, UdfLibrary, UdfSource,
 };
 
 /// A user-defined function to surface to the model.
@@ -31,16 +82,50 @@ pub struct UdfLibraryInput {
 }
 
 /// Options for creating a TextToCypher client
-#[napi(object)]
-#[derive(Debug, Clone)]
+#[napi(object, object_to_js = false)]
 pub struct ClientOptions {
     /// The AI model to use (e.g., "gpt-4o-mini", "anthropic:claude-3")
     pub model: String,
-    /// API key for the AI service
-    pub api_key: String,
+    /// Optional provider serving `model`: "openai", "anthropic", "gemini" (or "google"), or
+    /// "ollama", case-insensitive. Takes precedence over a `provider:` prefix on `model`, which is
+    /// still accepted when this is omitted. Unknown providers make the constructor throw.
+    pub provider: Option<String>,
+    /// Optional model for phrasing the natural-language answer, e.g. a cheaper model than the
+    /// one generating Cypher. Accepts the same forms as `model`, a bare name going to `provider`.
+    /// Only `textToCypher` and its variants generate answers. Defaults to `model`, or to a call's
+    /// `model` override.
+    pub answer_model: Option<String>,
+    /// API key for the AI service. Required unless `apiKeyEnv` is set.
+    pub api_key: Option<String>,
+    /// Optional name of an environment variable holding the API key, in place of `apiKey`. The
+    /// variable is read at the start of every call, so a rotated key takes effect without
+    /// creating a new client; a call made while it is unset rejects with `INVALID_ARGUMENT`.
+    pub api_key_env: Option<String>,
     /// FalkorDB connection string (e.g., "falkor://localhost:6379")
     pub falkordb_connection: String,
-    /// Optional LLM provider endpoint/base URL override
+    /// Optional FalkorDB connection strings to fail over to, in order, when `falkordbConnection`
+    /// cannot be reached, e.g. a read replica. The client opens its connection pool on the first
+    /// endpoint that accepts a connection and stays on it. Meant for read queries: a replica
+    /// refuses writes. The credential, database, and TLS options apply to every endpoint.
+    pub falkordb_fallbacks: Option<Vec<String>>,
+    /// Optional FalkorDB username. Takes precedence over a username embedded in
+    /// `falkordbConnection`.
+    pub falkordb_username: Option<String>,
+    /// Optional FalkorDB password. Takes precedence over a password embedded in
+    /// `falkordbConnection`.
+    pub falkordb_password: Option<String>,
+    /// Optional database number to select after connecting. Takes precedence over a database
+    /// embedded in `falkordbConnection` (e.g. the `2` in `falkor://localhost:6379/2`).
+    pub falkordb_database: Option<u32>,
+    /// When true, connect to FalkorDB over TLS, as a `falkors://` URL would. Certificates are
+    /// verified against the system trust store. Off by default.
+    pub tls: Option<bool>,
+    /// When true, accept any TLS certificate, e.g. a self-signed one in development. Requires a
+    /// TLS connection (`tls` or a `falkors://` URL). Never enable this in production.
+    pub tls_insecure: Option<bool>,
+    /// Optional LLM provider endpoint/base URL override, e.g. an OpenAI-compatible gateway. Must be
+    /// an http(s) URL; `model` and `apiKey` keep their meaning, with requests sent here instead of
+    /// to the provider's default endpoint.
     pub llm_endpoint: Option<String>,
     /// When true, discover the connected instance's user-defined functions (UDFs) and surface their
     /// `library.function` call targets to the model. Off by default. This is a client-level option,
@@ -51,16 +136,317 @@ pub struct ClientOptions {
     /// Use this when you already have the UDF list (e.g. from `GRAPH.UDF LIST`) to avoid an extra
     /// discovery round-trip.
     pub udfs: Option<Vec<UdfLibraryInput>>,
+    /// Optional upper bound, in milliseconds, on how long `textToCypher`, `answer`,
+    /// `textToCypherPaged`, `nextPage`, `textToCypherWithMessages`, `cypherOnly`, `explainQuery`,
+    /// `refineCypher`, `discoverSchema`, `discoverSchemaSync`, `discoverSchemaTyped`,
+    /// `describeSchema`, `estimateTokens`, `listGraphs`, `graphMetadata`, `validateCypher`,
+    /// `executeCypher`, and `ping` may run; `discoverSchemas` applies it to each graph. When
+    /// exceeded the promise rejects (`discoverSchemaSync` throws) with an "Operation timed out
+    /// after <N>ms" error. Omit for no limit.
+    pub timeout_ms: Option<u32>,
+    /// Optional limit, in milliseconds, on how long FalkorDB may run a generated query, enforced
+    /// by the server. A query that runs longer is aborted and the call rejects with a
+    /// `QUERY_TIMEOUT` error whose `error.response.cypherQuery` holds the query; it is neither
+    /// retried nor self-healed. Independent of `timeoutMs`, which bounds the whole call. Omit to
+    /// use the server's own query timeout.
+    pub query_timeout_ms: Option<u32>,
+    /// Optional sampling temperature (0.0-2.0) for every LLM call. Lower values make the
+    /// generated Cypher more deterministic. Omit to use the model provider's default.
+    pub temperature: Option<f64>,
+    /// Optional nucleus-sampling `top_p` (0.0-1.0) for every LLM call. Omit to use the model
+    /// provider's default.
+    pub top_p: Option<f64>,
+    /// Optional seed sent with every LLM call, so repeated calls with the same question and seed
+    /// tend to generate the same Cypher. Best-effort: only providers that accept a seed (OpenAI
+    /// and OpenAI-compatible APIs, Ollama) use it, others ignore it, and even those do not
+    /// guarantee identical output. Must be 0 or greater. Omit for no seed.
+    pub seed: Option<i64>,
+    /// Optional cap on the tokens the model may generate for a query. A query cut off by the cap
+    /// is incomplete, so it fails validation or execution like any other bad query. Omit to use
+    /// the model provider's default.
+    pub max_tokens: Option<u32>,
+    /// Optional cap on the tokens the model may generate for the natural-language answer. An
+    /// answer cut off by the cap is returned as far as it got, and its confidence is usually lost.
+    /// Defaults to `maxTokens`.
+    pub answer_max_tokens: Option<u32>,
+    /// Optional language for the natural-language answer, as a code ("es", "fr") or a free-form
+    /// description ("Brazilian Portuguese, informal"). The answer is written in it even when the
+    /// question is in another language; query generation is unaffected. Omit to let the model
+    /// answer in the question's language.
+    pub answer_language: Option<String>,
+    /// Optional JSON schema for the answer, for answers a program consumes (a UI card, a form).
+    /// Answer generation asks the model for a JSON object matching it, constrained by the
+    /// provider's structured output feature, and a reply that matches is returned as
+    /// `answerJson`, with `answer` holding the same JSON as text. Applies to OpenAI, Anthropic,
+    /// and Gemini models; other providers, and replies that do not match the schema, keep the
+    /// free-text answer and leave `answerJson` unset. The root must be an object schema
+    /// (`"type": "object"`). Omit for free-text answers.
+    pub answer_schema: Option<serde_json::Map<String, serde_json::Value>>,
+    /// When true, generated Cypher, and Cypher passed to `executeCypher`, containing write clauses
+    /// (CREATE, MERGE, DELETE, SET, REMOVE, DROP) is rejected before it reaches FalkorDB; the
+    /// rejection's `error.response.cypherQuery` holds the blocked query. Off by default. Does not
+    /// affect `cypherOnly`, which never executes.
+    pub read_only: Option<bool>,
+    /// Optional instructions for Cypher generation (e.g. domain naming conventions), sent as a
+    /// system message at the start of the conversation, right after the built-in schema prompt.
+    /// System messages passed to `textToCypherWithMessages` follow it, so they take precedence on
+    /// conflicts. Not used for answer generation. Omit to use the default prompt unchanged.
+    pub system_prompt: Option<String>,
+    /// Optional Cypher dialect generated queries must be compatible with, such as a FalkorDB
+    /// version ("FalkorDB 4.2") or a description of what is available ("openCypher 9, no `CALL {}`
+    /// subqueries, no APOC"). It is sent to the model as a constraint with every query-generation
+    /// request, self-healing included, ahead of `systemPrompt`. Omit to target the latest FalkorDB
+    /// dialect, which the built-in prompt is written for.
+    pub cypher_dialect: Option<String>,
+    /// Optional few-shot examples for query generation, sent ahead of the conversation as a user
+    /// question followed by an assistant reply with its Cypher. Every example is resent with every
+    /// query-generation request, so keep them short: a handful (3-5) covering the tricky parts of
+    /// the schema usually helps most, while dozens mostly add prompt tokens and latency.
+    pub examples: Option<Vec<CypherExample>>,
+    /// Optional template replacing the built-in query-generation prompt, e.g. read from a file
+    /// with `fs.readFileSync`. It is sent as the last user message, with no built-in system
+    /// prompt ahead of it, after filling in the placeholders `{{schema}}` and `{{question}}`
+    /// (both required) and `{{examples}}`, `{{skills}}`, and `{{udfs}}` (optional). Examples used
+    /// by the template are not also sent as conversation turns. The constructor throws on a
+    /// missing or unknown placeholder. Omit to use the built-in prompt.
+    pub prompt_template: Option<String>,
+    /// Optional number of times a failed LLM call or query execution is retried when the failure
+    /// is transient (rate limits, 502/503/504 responses, timeouts, dropped connections). Errors
+    /// such as invalid Cypher are never retried. A rate-limited reply's `Retry-After` (or
+    /// `retry-after-ms`) replaces the backoff delay; a wait over 60s fails the call instead.
+    /// Defaults to 0 (no retries).
+    pub max_retries: Option<u32>,
+    /// Optional delay, in milliseconds, before the first retry; each further retry doubles it
+    /// (capped at 10s) and applies random jitter. Defaults to 500.
+    pub retry_base_delay_ms: Option<u32>,
+    /// Optional retries by failure category, in place of `maxRetries`: `rateLimit`,
+    /// `serverError`, `timeout`, `connection`, and `generation` (a model reply holding no valid
+    /// query). Each category sets its own retry count and first delay (`retryBaseDelayMs` when
+    /// unset), and its retries are counted separately within a call. Categories left out are not
+    /// retried. Cannot be combined with `maxRetries`. Omit to retry per `maxRetries`.
+    pub retry_policy: Option<RetryPolicy>,
+    /// Optional number of times opening a FalkorDB connection is retried when no endpoint accepts
+    /// it, e.g. while a service starts before FalkorDB is ready. This only covers establishing a
+    /// connection (the first one, and a new one after the previous attempt failed); commands on
+    /// an open connection are retried per `maxRetries`. Only a connection that is refused,
+    /// dropped, or times out is retried, not a rejected password or TLS handshake. `timeoutMs`
+    /// still bounds the whole call. Defaults to 0 (no retries).
+    pub connect_retries: Option<u32>,
+    /// Optional delay, in milliseconds, before the first connection retry; each further retry
+    /// doubles it (capped at 10s) and applies random jitter. Defaults to 500.
+    pub connect_retry_delay_ms: Option<u32>,
+    /// Optional cap on the rows kept from an executed query; further rows are dropped, the answer
+    /// is generated from the rows kept, and the response's `truncated` flag is set. `textToCypher`
+    /// can override it per call. Omit to keep every row.
+    pub max_rows: Option<u32>,
+    /// Optional `LIMIT` appended to each generated query that reads rows and whose final `RETURN`
+    /// has no `LIMIT` of its own, so FalkorDB never returns more rows than that. Queries that
+    /// write, return no rows, or combine results with `UNION` are left as generated, and so is a
+    /// `LIMIT` the model already wrote, whatever its value. Unlike `maxRows`, which drops rows
+    /// after FalkorDB returned them, the limit bounds the work FalkorDB does and shows in
+    /// `cypherQuery`. Must be at least 1; omit to run queries as generated.
+    pub enforce_limit: Option<u32>,
+    /// Optional time, in milliseconds, a discovered schema is reused for further calls on the same
+    /// graph instead of being discovered again. Use `invalidateSchemaCache` after changing a
+    /// graph's schema. Omit or set to 0 to discover the schema on every call.
+    pub schema_cache_ttl_ms: Option<u32>,
+    /// Optional time, in milliseconds, a successful `textToCypher` response is reused for a
+    /// repeated call with the same graph, question (ignoring extra whitespace), model, schema,
+    /// and options, without calling the model or FalkorDB again. A cached response can be stale:
+    /// it holds the rows as they were when it was stored, so pick a TTL the data can tolerate and
+    /// use `clearResultCache` after writing to a graph. Omit or set to 0 to disable the cache.
+    pub result_cache_ttl_ms: Option<u32>,
+    /// Optional number of responses the result cache holds; once full, the least recently used
+    /// one is evicted. Must be at least 1; defaults to 100. Ignored unless `resultCacheTtlMs` is
+    /// set.
+    pub result_cache_size: Option<u32>,
+    /// Optional headers added to every request to the model provider (e.g. an org ID or routing
+    /// tag for an API gateway). Reserved headers (`Authorization`, `x-api-key`, `x-goog-api-key`,
+    /// `Content-Type`, `Content-Length`, `Host`) make the constructor throw unless
+    /// `allowReservedHeaders` is set. Headers never replace ones the provider integration sets
+    /// itself, such as the `apiKey` credentials.
+    pub extra_headers: Option<HashMap<String, String>>,
+    /// Accept reserved names in `extraHeaders`, e.g. an `Authorization` header for a gateway in
+    /// front of a provider that authenticates with `x-api-key`. Off by default.
+    pub allow_reserved_headers: Option<bool>,
+    /// Optional `User-Agent` sent with every request to the model provider, to identify the
+    /// application's traffic, e.g. for a provider's allowlist. It is also reported to FalkorDB as
+    /// the connections' library name (`CLIENT SETINFO LIB-NAME`, with whitespace replaced by
+    /// `_`), which `CLIENT LIST` shows. Takes precedence over a `User-Agent` in `extraHeaders`.
+    /// Defaults to `text-to-cypher-node/<version>`.
+    pub user_agent: Option<String>,
+    /// Optional size (1-255) of the FalkorDB connection pool the client opens on first use and
+    /// shares across calls; each command borrows a connection and returns it when done, and a
+    /// broken connection is replaced with a fresh one. Defaults to 8.
+    pub pool_size: Option<u32>,
+    /// Optional time, in milliseconds, after which an unused connection pool is closed and
+    /// replaced with a fresh one before the next command, instead of sending the command on
+    /// connections the server (or a proxy) may have dropped meanwhile. Set it below the server's
+    /// idle `timeout`. Omit to keep the pool open however long it sits unused.
+    pub connection_idle_timeout_ms: Option<u32>,
+    /// Optional idle time, in milliseconds, after which TCP keepalive probes are sent on FalkorDB
+    /// connections, so NATs and firewalls see traffic on them and a connection that died without
+    /// closing is noticed. Omit to send no probes (the system default).
+    pub connection_keepalive_ms: Option<u32>,
+    /// When true, responses carry the full prompt sent to the model for query generation in
+    /// `prompt`, for debugging wrong Cypher. Off by default because the prompt embeds the graph
+    /// schema, which should not end up in logs that record responses.
+    pub debug: Option<bool>,
+    /// When true, a generated query that names a node label or relationship type missing from
+    /// the schema is rejected with a `QUERY_GENERATION` error instead of being run with
+    /// `warnings`. Off by default.
+    pub strict: Option<bool>,
+    /// Optional number of node labels to keep in the query-generation prompt. When set, only the
+    /// labels whose names, property names, and relationship types best match the question, and
+    /// the relationship types between them, are sent to the model, which keeps prompts for graphs
+    /// with hundreds of labels small. `response.schema` still holds the full schema. Omit to send
+    /// the full schema (the default).
+    pub schema_top_k: Option<u32>,
+    /// Optional limit, in bytes, on the schema JSON sent to the model, so a graph too large for
+    /// the model's context fails clearly instead of with a provider error. A schema over the
+    /// limit rejects with `SCHEMA_TOO_LARGE`, unless `schemaTopK` is set, in which case the
+    /// schema is pruned to fewer labels until it fits. Must be at least 1; omit for no limit.
+    pub max_schema_bytes: Option<u32>,
+    /// Optional number of nodes per label whose properties schema discovery adds to the schema,
+    /// as each entity's `samples`, so the model sees real values (e.g. a status's spelling) when
+    /// mapping the question's terms. Long strings are truncated, long lists shortened, and
+    /// vectors left out. The samples are sent to the model with the schema, so only enable this
+    /// for data the model provider may see; each label costs one more query at discovery and
+    /// more prompt tokens. Must be at least 1; omit to send no data rows (the default).
+    pub sample_rows: Option<u32>,
+    /// Optional property names whose values are kept from the model and from debug output, e.g.
+    /// `["email", "ssn"]` for a graph holding personal data. They are masked as `[REDACTED]` in
+    /// the rows the answer is generated from, in nodes, relationships, paths, and maps, and in
+    /// columns named after them (`ssn` or `p.ssn`), but not in a column aliased to another name.
+    /// Schema discovery leaves their values out of attribute examples and `samples`, so neither
+    /// the query-generation prompt nor `prompt` shows them. Queries can still filter on and return
+    /// them: `cypherResult` and `resultSet` hold the values, as does the generated query when the
+    /// question names one. Names are case-sensitive; blank ones make the constructor throw.
+    pub redact_properties: Option<Vec<String>>,
+    /// When true, the client never discovers a schema from FalkorDB: every call that needs one
+    /// must be given it (the `schema` argument of `textToCypher`, `cypherOnly`, and the like),
+    /// and that schema is used as given. A call without one rejects with `INVALID_ARGUMENT`
+    /// before contacting FalkorDB, as do the `discoverSchema` methods and `describeSchema`. For
+    /// applications that keep their own schema. Off by default.
+    pub skip_discovery: Option<bool>,
+    /// When true, the model rates each generated query from 0 to 1 against the question and
+    /// schema in an extra LLM call, reported as `queryConfidence`. The rating is a heuristic, and
+    /// the extra call adds latency and tokens (counted in `tokenUsage`). A rating call that fails
+    /// leaves `queryConfidence` unset rather than failing the call. Off by default.
+    pub with_confidence: Option<bool>,
+    /// When true, query generation asks the model for a JSON object holding the query and its
+    /// parameters, constrained by the provider's structured output feature, instead of
+    /// extracting the query from free text. Applies to OpenAI, Anthropic, and Gemini models;
+    /// other providers, and replies that do not match the schema, use the text extraction.
+    /// Off by default.
+    pub structured_output: Option<bool>,
+    /// When true, the query-generation system prompt, which holds the schema and is the same for
+    /// every question about a graph, is marked cacheable for providers that cache a prompt only
+    /// when asked (Anthropic), so repeated calls are billed the cheaper cached rate for it.
+    /// OpenAI and Gemini cache long prompts on their own; other providers get the request
+    /// unchanged. Cache reads and writes show in `tokenUsage`. Off by default.
+    pub prompt_caching: Option<bool>,
+    /// When true (the default), a generated query FalkorDB rejects is regenerated with the error
+    /// as feedback and executed again, up to `selfHealMaxAttempts` times. Set to false to reject
+    /// with the first execution error instead.
+    pub self_heal: Option<bool>,
+    /// Optional number of times self-healing may regenerate a rejected query, each attempt seeing
+    /// every earlier failed query and its error; defaults to 1. A query FalkorDB aborts for
+    /// running past `queryTimeoutMs` is never self-healed.
+    pub self_heal_max_attempts: Option<u32>,
+    /// When true, a model reply holding no Cypher query, such as an apology or a clarifying
+    /// question, is followed up once with an instruction to reply with a query only, choosing the
+    /// question's most likely meaning over asking. A second reply without a query rejects with
+    /// `NO_QUERY_GENERATED`, as the first does when this is off. Off by default.
+    pub no_query_retry: Option<bool>,
+    /// When true, calls that resolve to a `TextToCypherResponse` (`textToCypher`, `cypherOnly`,
+    /// `executeCypher`, and the like) resolve a failure with its error-status response, whose
+    /// `errorCode` and `errorStage` say what failed, instead of rejecting. Only failures of the
+    /// call's own work resolve: an invalid argument (`INVALID_ARGUMENT`) or a closed client
+    /// (`CLIENT_CLOSED`) still rejects, as do the other methods. Off by default.
+    pub resolve_errors: Option<bool>,
+    /// Optional callback receiving structured log events (`{ level, message, stage, timestamp }`)
+    /// as a call progresses: the model called, the query generated, the rows returned, queries
+    /// sent back for self-healing, and failures. It is not awaited and an exception it throws is
+    /// ignored, so it never slows or breaks a call, and it does not keep the process alive.
+    pub on_log: Option<ThreadsafeFunction<LogEvent, (), LogEvent, Status, false, true>>,
+    /// Optional predicate called with each row a generated query returns, as an object keyed by
+    /// column name, returning whether to keep it. Rows it returns false for are left out of
+    /// `cypherResult`, `resultSet`, and the rows the answer is written from. Each row is a call
+    /// into JavaScript on the main thread, so it suits results capped by `maxRows` rather than
+    /// large ones; a throwing predicate fails the call with `QUERY_EXECUTION`.
+    pub row_filter: Option<ThreadsafeFunction<Row, bool, Row, Status, false, true>>,
+}
+
+/// A result row as `rowFilter` receives it, keyed by column name
+type Row = serde_json::Map<String, serde_json::Value>;
+
+/// A question paired with the Cypher that answers it, shown to the model as a worked example
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct CypherExample {
+    /// A question about the graph, phrased the way users ask
+    pub question: String,
+    /// The Cypher query that answers it
+    pub cypher: String,
 }
 
 /// A chat message in the conversation
 #[napi(object)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
-    /// Role of the message sender: "user", "assistant", or "system"
+    /// Role of the message sender: "user", "assistant", or "system"; other roles are rejected
     pub role: String,
     /// Content of the message
     pub content: String,
+    /// Optional Cypher query the turn of an `assistant` message ran, e.g. the previous
+    /// response's `cypherQuery`, sent to the model with the message so a follow-up question can
+    /// build on it
+    pub cypher_query: Option<String>,
+    /// Optional result of that query, e.g. the previous response's `cypherResult`, sent to the
+    /// model with the message so a follow-up question can refer to its rows. A result over 4,000
+    /// characters is cut to its first lines, with a note of how many were left out
+    pub cypher_result: Option<String>,
+}
+
+/// Per-call options for `textToCypher`
+#[napi(object, object_to_js = false)]
+#[derive(Default)]
+pub struct TextToCypherCallOptions {
+    /// Optional `AbortSignal`; aborting it cancels the call, which rejects with an `ABORTED` error
+    pub signal: Option<Signal>,
+    /// Optional cap on the rows kept from the executed query, overriding the client's `maxRows`
+    pub max_rows: Option<u32>,
+    /// Optional model to use for this call instead of the client's `model`
+    pub model: Option<String>,
+    /// Optional callback invoked with a `ProgressEvent` as each stage starts and completes.
+    /// Best-effort: it is not awaited, and an exception it throws is ignored
+    pub on_progress: Option<ThreadsafeFunction<ProgressEvent, (), ProgressEvent, Status, false>>,
+    /// Optional; `false` skips answer generation, so the call resolves once the query has run,
+    /// without an `answer`. Defaults to `true`
+    pub generate_answer: Option<bool>,
+    /// Optional names of the result columns the answer is generated from; the response still
+    /// holds every column. Naming a column the query did not return rejects with
+    /// `INVALID_ARGUMENT`. Defaults to every column
+    pub answer_columns: Option<Vec<String>>,
+    /// Optional API key to send this call's LLM requests with instead of the client's `apiKey`,
+    /// e.g. the calling tenant's own key
+    pub api_key: Option<String>,
+    /// Optional format of `cypherResult`: `"text"` (the default, the text the answer is
+    /// generated from), `"json"` (an array of row objects keyed by column), `"csv"` (a header
+    /// row, then one line per row), or `"ndjson"` (one row object per line). Nested values such
+    /// as nodes are JSON-encoded within a CSV cell
+    pub result_format: Option<String>,
+    /// Optional identifier for the call, e.g. the request id of the application request it
+    /// serves: up to 128 letters, digits, `-`, `_`, `.`, or `:`. It is appended to each query the
+    /// call runs as a `/* trace_id: ... */` comment, so FalkorDB's logs and slow log can be
+    /// matched to the call, and returned in `traceId`. Defaults to the call's `requestId`, a
+    /// random UUID
+    pub trace_id: Option<String>,
+    /// Optional name of a result column to count the returned rows by, client-side, into
+    /// `groups`, e.g. for a quick histogram without aggregating in the Cypher. Naming a column
+    /// the query did not return rejects with `INVALID_ARGUMENT`
+    pub group_by: Option<String>,
 }
 
 /// Aggregated token usage for a text-to-cypher request
@@ -77,6 +463,12 @@ pub struct TokenUsage {
     pub completion_tokens: f64,
     /// Total tokens consumed across all LLM calls
     pub total_tokens: f64,
+    /// Input tokens the provider read from its prompt cache, included in `promptTokens`. Unset
+    /// when the provider reported no cache reads.
+    pub cached_prompt_tokens: Option<f64>,
+    /// Input tokens the provider wrote to its prompt cache, included in `promptTokens`. Unset
+    /// when the provider reported no cache writes.
+    pub cache_creation_tokens: Option<f64>,
 }
 
 impl From<text_to_cypher::TokenUsage> for TokenUsage {
@@ -85,6 +477,8 @@ impl From<text_to_cypher::TokenUsage> for TokenUsage {
             prompt_tokens: usage.prompt_tokens as f64,
             completion_tokens: usage.completion_tokens as f64,
             total_tokens: usage.total_tokens as f64,
+            cached_prompt_tokens: None,
+            cache_creation_tokens: None,
         }
     }
 }
@@ -93,24 +487,105 @@ impl From<text_to_cypher::TokenUsage> for TokenUsage {
 #[napi(object)]
 #[derive(Debug, Clone)]
 pub struct TextToCypherResponse {
-    /// Status of the operation: "success" or "error"
+    /// Status of the operation: "success", "partial" when the query ran but the answer could not
+    /// be generated, or "error"
     pub status: String,
-    /// The discovered graph schema (JSON string)
+    /// The discovered graph schema (JSON string), also set on the error response of a call that
+    /// failed after discovery
     pub schema: Option<String>,
     /// The generated Cypher query
     pub cypher_query: Option<String>,
-    /// The result from executing the Cypher query
+    /// The result from executing the Cypher query, as text
     pub cypher_result: Option<String>,
+    /// The same result as columns and rows of JSON values
+    pub result_set: Option<CypherResultSet>,
+    /// Rows of `resultSet` counted by their value in the `groupBy` column, most frequent value
+    /// first; set only when the call passes `groupBy`. Counts cover the rows kept, so a truncated
+    /// result is counted over its first `maxRows` rows
+    pub groups: Option<Vec<ResultGroup>>,
     /// Natural language answer generated from the results
     pub answer: Option<String>,
+    /// The answer as JSON matching the client's `answerSchema`; set only when the option is on,
+    /// the provider supports structured output, and the model's reply matched the schema
+    pub answer_json: Option<serde_json::Value>,
     /// Model self-reported confidence (0-100) that the answer is correct given the data.
     /// Omitted when the model does not report a value.
     pub confidence: Option<u32>,
     /// Error message if status is "error"
     pub error: Option<String>,
+    /// Why the answer could not be generated when status is "partial"; `cypherQuery`,
+    /// `cypherResult`, and `resultSet` still hold the query and its rows
+    pub answer_error: Option<String>,
+    /// True when the query returned more rows than `maxRows` and only the first `maxRows` were
+    /// kept in `cypherResult` and `resultSet`
+    pub truncated: bool,
+    /// Number of rows in `resultSet`; set once the query has run, so 0 means it ran and matched
+    /// nothing, while a failed query leaves it unset
+    pub row_count: Option<u32>,
     /// Aggregated token usage across all LLM calls made while serving the request.
-    /// Omitted when no tokens were consumed (e.g. failures before any LLM call).
+    /// Omitted when no tokens were consumed (e.g. failures before any LLM call) or the provider
+    /// does not report usage.
     pub token_usage: Option<TokenUsage>,
+    /// Model the LLM calls were sent to, as resolved from the `model` option (e.g. `gpt-4o-mini`
+    /// for `openai::gpt-4o-mini`); with `answerModel` set, the model that generated the query.
+    /// Omitted when the model could not be resolved.
+    pub model_used: Option<String>,
+    /// How long each pipeline stage took, for the stages that ran before the call finished or
+    /// failed. Omitted when the call failed before the pipeline started, e.g. on a timeout.
+    pub timings: Option<StageTimings>,
+    /// FalkorDB's execution plan for `cypherQuery`, one operation per line; set by `explainQuery`
+    pub query_plan: Option<String>,
+    /// Values of the parameters `cypherQuery` references as `$name`, when the model parameterized
+    /// it. They are passed to FalkorDB separately when the query runs.
+    pub parameters: Option<serde_json::Map<String, serde_json::Value>>,
+    /// The prompt sent to the model to generate `cypherQuery`, system prompt included, one
+    /// `[role]`-headed section per message; set only when the client's `debug` option is on
+    pub prompt: Option<String>,
+    /// The node labels `schemaTopK` kept in `prompt`, in schema order; set only when both
+    /// `schemaTopK` and `debug` are on
+    pub schema_labels: Option<Vec<String>>,
+    /// The model's own rating, from 0 to 1, of how likely `cypherQuery` is to answer the question
+    /// correctly; set only when the client's `withConfidence` option is on. A heuristic: models
+    /// are often confident about wrong queries, so use it to flag queries for review rather than
+    /// to prove them right
+    pub query_confidence: Option<f64>,
+    /// FalkorDB's error message for `cypherQuery` when FalkorDB rejected it (a type mismatch, an
+    /// unknown function, ...), less the reply's leading error code, which the FalkorDB client
+    /// drops (`mismatch: ...` of `Type mismatch: ...`); set on `error.response` of
+    /// `QUERY_EXECUTION` rejections
+    pub execution_error: Option<String>,
+    /// The model's reply to query generation when it held no Cypher query (an apology, or a
+    /// clarifying question worth showing the user); set on `error.response` of
+    /// `NO_QUERY_GENERATED` rejections, unless the reply was empty
+    pub model_reply: Option<String>,
+    /// How many times self-healing regenerated `cypherQuery` after FalkorDB rejected it; set
+    /// once the query has been executed, also on `error.response` of `QUERY_EXECUTION`
+    /// rejections
+    pub self_heal_attempts: Option<u32>,
+    /// Token for `nextPage` to fetch the rows after `resultSet`; set by `textToCypherPaged` when
+    /// the query returned more rows than the page holds
+    pub cursor: Option<String>,
+    /// Identifier of the call, appended to each query it runs as a `/* trace_id: ... */`
+    /// comment so it shows up in FalkorDB's logs and slow log; the call's `traceId`, or its
+    /// `requestId`
+    pub trace_id: Option<String>,
+    /// Identifier of the call for correlating it with your own logs and traces: the call's
+    /// `traceId` when one is given, and otherwise a random UUID. Every log event the call emits
+    /// carries it as `requestId`, as does the error a failed call rejects with
+    pub request_id: Option<String>,
+    /// Problems spotted in `cypherQuery` that did not stop it from running, such as a node label
+    /// or relationship type missing from the schema, which makes a query silently match nothing
+    pub warnings: Vec<String>,
+    /// What `cypherQuery` does to the graph, from its clauses: "read" when it has no write clause
+    /// (`CREATE`, `MERGE`, `DELETE`, `SET`, `REMOVE`, `DROP`), "write" when it writes without
+    /// matching existing data, or "mixed" when it both `MATCH`es and writes. Informational: only
+    /// `readOnly` mode refuses queries that write
+    pub query_kind: Option<String>,
+    /// Code of the failure when status is "error", as on the rejection's `error.code`
+    pub error_code: Option<ErrorCode>,
+    /// Pipeline stage that failed when status is "error", if the failure belongs to one; omitted
+    /// for failures such as timeouts, aborts, and connection errors
+    pub error_stage: Option<PipelineStage>,
 }
 
 impl From<text_to_cypher::TextToCypherResponse> for TextToCypherResponse {
@@ -120,11 +595,316 @@ impl From<text_to_cypher::TextToCypherResponse> for TextToCypherResponse {
             schema: response.schema,
             cypher_query: response.cypher_query,
             cypher_result: response.cypher_result,
+            result_set: None,
+            groups: None,
             answer: response.answer,
+            answer_json: None,
             confidence: response.confidence.map(u32::from),
             error: response.error,
-            token_usage: response.token_usage.map(Into::into),
+            answer_error: None,
+            truncated: false,
+            row_count: None,
+            token_usage: response
+                .token_usage
+                .filter(|usage| usage.total_tokens > 0)
+                .map(Into::into),
+            model_used: None,
+            timings: None,
+            query_plan: None,
+            parameters: None,
+            prompt: None,
+            schema_labels: None,
+            query_confidence: None,
+            execution_error: None,
+            model_reply: None,
+            self_heal_attempts: None,
+            cursor: None,
+            trace_id: None,
+            request_id: None,
+            warnings: Vec::new(),
+            query_kind: None,
+            error_code: None,
+            error_stage: None,
+        }
+    }
+}
+
+/// Outcome of checking a Cypher query with `validateCypher`
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct CypherValidation {
+    /// Whether FalkorDB could parse and plan the query
+    pub valid: bool,
+    /// FalkorDB's error message when the query is not valid
+    pub error: Option<String>,
+}
+
+/// One graph's entry in the map `discoverSchemas` resolves to
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct GraphSchema {
+    /// The discovered schema as a JSON string, when discovery succeeded
+    pub schema: Option<String>,
+    /// Why discovery failed, when it did
+    pub error: Option<String>,
+}
+
+/// The labels and relationship types of a graph, as `graphMetadata` returns them
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct GraphMetadata {
+    /// The graph's node labels
+    pub labels: Vec<String>,
+    /// The graph's relationship types
+    pub relationship_types: Vec<String>,
+}
+
+/// One question's response, as `textToCypherBatchEach` delivers it
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct BatchResult {
+    /// Position of the question in the `questions` array
+    pub index: u32,
+    /// The question's response; an error-status one when it failed
+    pub response: TextToCypherResponse,
+}
+
+/// How many questions of a `textToCypherBatch` or `textToCypherBatchEach` call, or graphs of a
+/// `discoverSchemas` call, are in flight at once.
+const BATCH_CONCURRENCY: usize = 4;
+
+/// Checks that a caller-supplied schema is a JSON object, like the one `discoverSchema` returns.
+fn validate_schema(schema: &str) -> std::result::Result<(), ClientError> {
+    match serde_json::from_str::<serde_json::Value>(schema) {
+        Ok(serde_json::Value::Object(_)) => Ok(()),
+        Ok(_) => Err(ClientError::new(
+            ErrorCode::InvalidArgument,
+            "Invalid schema: expected a JSON object as returned by discoverSchema",
+        )),
+        Err(e) => Err(ClientError::new(
+            ErrorCode::InvalidArgument,
+            format!("Invalid schema: malformed JSON: {}", e),
+        )),
+    }
+}
+
+/// Checks that a row cap keeps at least one row.
+fn validate_max_rows(max_rows: Option<u32>) -> std::result::Result<(), ClientError> {
+    match max_rows {
+        Some(0) => Err(ClientError::new(
+            ErrorCode::InvalidArgument,
+            "Invalid maxRows: 0. Must be at least 1",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that a token cap, when set, allows at least one token.
+fn validate_max_tokens(
+    option: &str,
+    max_tokens: Option<u32>,
+) -> std::result::Result<(), ClientError> {
+    match max_tokens {
+        Some(0) => Err(ClientError::new(
+            ErrorCode::InvalidArgument,
+            format!("Invalid {option}: 0. Must be at least 1"),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that every few-shot example has both a question and a query.
+fn validate_examples(examples: &[CypherExample]) -> std::result::Result<(), ClientError> {
+    for (index, example) in examples.iter().enumerate() {
+        if example.question.trim().is_empty() || example.cypher.trim().is_empty() {
+            return Err(ClientError::new(
+                ErrorCode::InvalidArgument,
+                format!("Invalid examples: example {index} needs a non-empty question and cypher"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that an LLM endpoint override is an absolute http(s) URL; blank counts as unset.
+fn validate_llm_endpoint(endpoint: Option<&str>) -> std::result::Result<(), ClientError> {
+    let Some(endpoint) = endpoint
+        .map(str::trim)
+        .filter(|endpoint| !endpoint.is_empty())
+    else {
+        return Ok(());
+    };
+    let invalid = |reason: String| {
+        ClientError::new(
+            ErrorCode::InvalidArgument,
+            format!("Invalid llmEndpoint: {endpoint}: {reason}"),
+        )
+    };
+    let url = url::Url::parse(endpoint).map_err(|e| invalid(e.to_string()))?;
+    if !matches!(url.scheme(), "http" | "https") || !url.has_host() {
+        return Err(invalid("expected an http:// or https:// URL".to_string()));
+    }
+    Ok(())
+}
+
+/// Headers the provider integration owns: credentials and request framing.
+const RESERVED_HEADERS: [&str; 6] = [
+    "authorization",
+    "x-api-key",
+    "x-goog-api-key",
+    "content-type",
+    "content-length",
+    "host",
+];
+
+/// Checks `extraHeaders` and converts them for the HTTP client.
+fn extra_headers(
+    headers: HashMap<String, String>,
+    allow_reserved: bool,
+) -> std::result::Result<HeaderMap, ClientError> {
+    let invalid = |message: String| {
+        ClientError::new(
+            ErrorCode::InvalidArgument,
+            format!("Invalid extraHeaders: {message}"),
+        )
+    };
+    let mut map = HeaderMap::with_capacity(headers.len());
+    for (name, value) in headers {
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| invalid(format!("'{name}' is not a valid header name")))?;
+        if !allow_reserved && RESERVED_HEADERS.contains(&header_name.as_str()) {
+            return Err(invalid(format!(
+                "'{name}' is reserved; set allowReservedHeaders to send it"
+            )));
+        }
+        let header_value = HeaderValue::from_str(&value)
+            .map_err(|_| invalid(format!("the value of '{name}' is not a valid header value")))?;
+        map.insert(header_name, header_value);
+    }
+    Ok(map)
+}
+
+/// Resolves the `User-Agent`: `userAgent` when set, else one given in `extraHeaders`, else the
+/// default.
+fn user_agent(
+    user_agent: Option<String>,
+    extra_headers: &HeaderMap,
+) -> std::result::Result<String, ClientError> {
+    let Some(user_agent) = user_agent else {
+        return Ok(extra_headers
+            .get(USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or(DEFAULT_USER_AGENT)
+            .to_string());
+    };
+    let invalid = |message: &str| {
+        ClientError::new(
+            ErrorCode::InvalidArgument,
+            format!("Invalid userAgent: {message}"),
+        )
+    };
+    let user_agent = user_agent.trim();
+    if user_agent.is_empty() {
+        return Err(invalid("must not be empty"));
+    }
+    HeaderValue::from_str(user_agent).map_err(|_| invalid("not a valid header value"))?;
+    Ok(user_agent.to_string())
+}
+
+/// Checks and normalizes a per-call model override; blank names are rejected.
+fn model_override(
+    model: Option<String>,
+    provider: Option<AdapterKind>,
+) -> std::result::Result<Option<String>, ClientError> {
+    match model {
+        Some(model) if model.trim().is_empty() => Err(ClientError::new(
+            ErrorCode::InvalidArgument,
+            "Invalid model: must not be empty",
+        )),
+        model => Ok(model.map(|model| override_model_name(model.trim(), provider))),
+    }
+}
+
+/// Validates a per-call API key override.
+fn api_key_override(api_key: Option<String>) -> std::result::Result<Option<String>, ClientError> {
+    match api_key {
+        Some(api_key) if api_key.trim().is_empty() => Err(ClientError::new(
+            ErrorCode::InvalidArgument,
+            "Invalid apiKey: must not be empty",
+        )),
+        api_key => Ok(api_key),
+    }
+}
+
+/// Validates a per-call trace id: it goes into a query comment, so it is kept to characters that
+/// cannot end the comment.
+fn trace_id_override(trace_id: Option<String>) -> std::result::Result<Option<String>, ClientError> {
+    match trace_id {
+        Some(trace_id)
+            if trace_id.is_empty()
+                || trace_id.len() > 128
+                || !trace_id.bytes().all(|b| {
+                    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b':')
+                }) =>
+        {
+            Err(ClientError::new(
+                ErrorCode::InvalidArgument,
+                format!("Invalid traceId {trace_id:?}: use 1 to 128 letters, digits, or - _ . :"),
+            ))
         }
+        trace_id => Ok(trace_id),
+    }
+}
+
+/// Normalizes a model name that stands in for the client's `model`: a bare name goes to the
+/// client's `provider`; one with its own provider prefix keeps it.
+fn override_model_name(model: &str, provider: Option<AdapterKind>) -> String {
+    if model.contains("::") || strip_provider_prefix(model).is_some() {
+        normalize_model_name(model)
+    } else {
+        resolve_model(model, provider)
+    }
+}
+
+/// Provider names accepted by the `provider` option and `listModelsByProvider`.
+const PROVIDERS: [&str; 4] = ["openai", "anthropic", "gemini", "ollama"];
+
+/// The adapter for a provider name (case-insensitive); `google` is accepted for Gemini.
+fn provider_adapter(provider: &str) -> std::result::Result<AdapterKind, ClientError> {
+    match provider.trim().to_lowercase().as_str() {
+        "openai" => Ok(AdapterKind::OpenAI),
+        "anthropic" => Ok(AdapterKind::Anthropic),
+        "gemini" | "google" => Ok(AdapterKind::Gemini),
+        "ollama" => Ok(AdapterKind::Ollama),
+        _ => Err(ClientError::new(
+            ErrorCode::InvalidArgument,
+            format!(
+                "Unknown provider: '{}'. Supported providers are: {}",
+                provider,
+                PROVIDERS.join(", ")
+            ),
+        )),
+    }
+}
+
+/// `model` without a known `provider:` or `provider::` prefix, if it has one.
+fn strip_provider_prefix(model: &str) -> Option<&str> {
+    PROVIDERS.iter().find_map(|provider| {
+        let rest = model.strip_prefix(provider)?;
+        rest.strip_prefix("::").or_else(|| rest.strip_prefix(':'))
+    })
+}
+
+/// The genai model name for `model`: an explicit `provider` wins over any prefix on the name,
+/// otherwise the `provider:model` prefix form selects the provider.
+fn resolve_model(model: &str, provider: Option<AdapterKind>) -> String {
+    match provider {
+        Some(provider) => format!(
+            "{}::{}",
+            provider.as_lower_str(),
+            strip_provider_prefix(model).unwrap_or(model)
+        ),
+        None => normalize_model_name(model),
     }
 }
 
@@ -135,9 +915,8 @@ fn normalize_model_name(model: &str) -> String {
     }
     // Convert known single-colon provider prefixes to genai's "::" namespace format
     for prefix in &["openai:", "anthropic:", "gemini:", "ollama:"] {
-        if model.starts_with(prefix) {
+        if let Some(model_name) = model.strip_prefix(prefix) {
             let provider = &prefix[..prefix.len() - 1];
-            let model_name = &model[prefix.len()..];
             return format!("{}::{}", provider, model_name);
         }
     }
@@ -165,7 +944,105 @@ fn normalize_model_name(model: &str) -> String {
 /// ```
 #[napi]
 pub struct TextToCypher {
-    client: TextToCypherClient,
+    pipeline: Pipeline,
+    timeout_ms: Option<u32>,
+    /// Resolve recoverable failures of response-returning calls to error-status responses.
+    resolve_errors: bool,
+    /// The explicit `provider` option, applied to bare per-call model overrides.
+    provider: Option<AdapterKind>,
+}
+
+impl TextToCypher {
+    /// Awaits `future`, bounding it by the configured `timeout_ms` when one is set. Fails without
+    /// polling it once the client is closed.
+    async fn with_timeout<F: Future>(
+        &self,
+        future: F,
+    ) -> std::result::Result<F::Output, ClientError> {
+        self.pipeline.ensure_open()?;
+        match self.timeout_ms {
+            Some(ms) => tokio::time::timeout(Duration::from_millis(u64::from(ms)), future)
+                .await
+                .map_err(|_| {
+                    ClientError::new(
+                        ErrorCode::Timeout,
+                        format!("Operation timed out after {}ms", ms),
+                    )
+                }),
+            None => Ok(future.await),
+        }
+    }
+
+    /// The schema a batch of questions shares: the supplied one once validated, or the graph's
+    /// discovered one.
+    async fn batch_schema(
+        &self,
+        graph_name: &str,
+        schema: Option<String>,
+    ) -> std::result::Result<String, ClientError> {
+        self.pipeline.ensure_open()?;
+        match schema {
+            Some(schema) => validate_schema(&schema).map(|()| schema),
+            None => self
+                .with_timeout(self.pipeline.discover_schema(graph_name))
+                .await?
+                .map_err(|e| e.context("Schema discovery failed")),
+        }
+    }
+
+    /// Runs the pipeline under the timeout and `signal`, prefixing pipeline failures with
+    /// `context`. The call is identified by its trace id, or a new request id that then also
+    /// serves as its trace id.
+    async fn run(
+        &self,
+        graph_name: &str,
+        request: ChatRequest,
+        mut options: RunOptions<'_>,
+        signal: Option<Signal>,
+        context: &str,
+    ) -> std::result::Result<TextToCypherResponse, ClientError> {
+        let request_id = options
+            .trace_id
+            .get_or_insert_with(pipeline::new_request_id)
+            .clone();
+        let call = async {
+            let started = Instant::now();
+            let future = self.with_timeout(self.pipeline.run(graph_name, request, options));
+            let result = match signal {
+                Some(signal) => signal.guard(future).await.and_then(|result| result),
+                None => future.await,
+            };
+
+            let result = match result {
+                Ok(Ok(response)) => Ok(response),
+                Ok(Err(e)) => Err(e.context(context)),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = &result {
+                self.pipeline.log(LogLevel::Error, None, || e.to_string());
+            }
+            self.pipeline.metrics.record_call(&result, started);
+            result
+        };
+        log::with_request_id(request_id.clone(), call)
+            .await
+            .map_err(|e| e.with_request_id(request_id))
+    }
+
+    /// Settles a call that resolves to a response. With `resolveErrors`, a recoverable failure
+    /// resolves to its error-status response instead of rejecting, as a failed question in
+    /// `textToCypherBatch` does.
+    fn settle(
+        &self,
+        result: std::result::Result<TextToCypherResponse, ClientError>,
+    ) -> Outcome<TextToCypherResponse> {
+        match result {
+            Err(e) if self.resolve_errors && e.is_recoverable() => {
+                Outcome::from(Ok(e.into_response()))
+            }
+            result => result.into(),
+        }
+    }
 }
 
 #[napi]
@@ -187,12 +1064,159 @@ impl TextToCypher {
     /// ```
     #[napi(constructor)]
     pub fn new(options: ClientOptions) -> Result<Self> {
-        let model = normalize_model_name(&options.model);
-        let mut client =
-            TextToCypherClient::new(model, options.api_key, options.falkordb_connection);
-        if let Some(endpoint) = options.llm_endpoint {
-            client = client.with_llm_endpoint(endpoint);
+        let options = defaults::apply(options);
+        if let Some(error) = client_options::check(&options).into_iter().next() {
+            return Err(Error::from_reason(error.message));
+        }
+        // Conversions that can fail were checked above; their errors are passed on regardless.
+        let provider = options
+            .provider
+            .as_deref()
+            .map(provider_adapter)
+            .transpose()
+            .map_err(|e| Error::from_reason(e.to_string()))?;
+        let model = resolve_model(&options.model, provider);
+        let connection_options = client_options::connection_options(&options);
+        let falkordb_connection = connection::assemble(
+            &options.falkordb_connection,
+            "falkordbConnection",
+            &connection_options,
+        )
+        .map_err(Error::from_reason)?;
+        let falkordb_fallbacks = client_options::falkordb_fallbacks(&options, &connection_options)
+            .map_err(Error::from_reason)?;
+        let api_key = client_options::api_key(options.api_key, options.api_key_env)
+            .map_err(Error::from_reason)?;
+        let mut pipeline = Pipeline::new(model, api_key, falkordb_connection);
+        pipeline.falkordb_fallbacks = falkordb_fallbacks;
+        pipeline.answer_model = options
+            .answer_model
+            .filter(|model| !model.trim().is_empty())
+            .map(|model| override_model_name(model.trim(), provider));
+        pipeline.llm_endpoint = options.llm_endpoint;
+        if let Some(headers) = options.extra_headers {
+            pipeline.extra_headers =
+                extra_headers(headers, options.allow_reserved_headers.unwrap_or(false))
+                    .map_err(|e| Error::from_reason(e.to_string()))?;
+        }
+        pipeline.user_agent = user_agent(options.user_agent, &pipeline.extra_headers)
+            .map_err(|e| Error::from_reason(e.to_string()))?;
+        pipeline.read_only = options.read_only.unwrap_or(false);
+        pipeline.debug = options.debug.unwrap_or(false);
+        pipeline.strict = options.strict.unwrap_or(false);
+        pipeline.with_confidence = options.with_confidence.unwrap_or(false);
+        pipeline.structured_output = options.structured_output.unwrap_or(false);
+        pipeline.prompt_caching = options.prompt_caching.unwrap_or(false);
+        // Going through the return-value callback drops an exception the callback throws, as
+        // for `onProgress`.
+        pipeline.on_log = options.on_log.map(|on_log| -> LogSink {
+            Box::new(move |event| {
+                on_log.call_with_return_value(
+                    event,
+                    ThreadsafeFunctionCallMode::NonBlocking,
+                    |_, _| Ok(()),
+                );
+            })
+        });
+        pipeline.row_filter = options.row_filter.map(|row_filter| -> RowFilter {
+            let row_filter = std::sync::Arc::new(row_filter);
+            Box::new(move |row| {
+                let row_filter = row_filter.clone();
+                Box::pin(async move {
+                    row_filter
+                        .call_async_catch(row)
+                        .await
+                        .map_err(|e| e.reason.clone())
+                })
+            })
+        });
+        pipeline.self_heal_attempts = match options.self_heal {
+            Some(false) => 0,
+            _ => options.self_heal_max_attempts.unwrap_or(1),
+        };
+        pipeline.no_query_retry = options.no_query_retry.unwrap_or(false);
+        pipeline.system_prompt = options
+            .system_prompt
+            .filter(|prompt| !prompt.trim().is_empty());
+        pipeline.cypher_dialect = options
+            .cypher_dialect
+            .map(|dialect| dialect.trim().to_string())
+            .filter(|dialect| !dialect.is_empty());
+        if let Some(examples) = options.examples {
+            pipeline.examples = examples;
+        }
+        if let Some(template) = options.prompt_template {
+            pipeline.prompt_template = Some(
+                PromptTemplate::parse(template).map_err(|e| Error::from_reason(e.to_string()))?,
+            );
+        }
+        if let Some(max_retries) = options.max_retries {
+            pipeline.retry.max_retries = max_retries;
+        }
+        pipeline.retry.policy = options.retry_policy;
+        if let Some(ms) = options.retry_base_delay_ms {
+            pipeline.retry.base_delay = Duration::from_millis(u64::from(ms));
+        }
+        if let Some(connect_retries) = options.connect_retries {
+            pipeline.connect_retry.max_retries = connect_retries;
+        }
+        if let Some(ms) = options.connect_retry_delay_ms {
+            pipeline.connect_retry.base_delay = Duration::from_millis(u64::from(ms));
         }
+        if let Some(pool_size) = options.pool_size {
+            pipeline.pool_size =
+                client_options::pool_size(pool_size).map_err(Error::from_reason)?;
+        }
+        pipeline.connection_idle_timeout = options
+            .connection_idle_timeout_ms
+            .map(|ms| Duration::from_millis(u64::from(ms)));
+        pipeline.connection_keepalive = options
+            .connection_keepalive_ms
+            .map(|ms| Duration::from_millis(u64::from(ms)));
+        pipeline.query_timeout_ms = options.query_timeout_ms;
+        pipeline.max_rows = options.max_rows;
+        pipeline.enforce_limit = options.enforce_limit;
+        pipeline.schema_cache = options
+            .schema_cache_ttl_ms
+            .filter(|&ms| ms > 0)
+            .map(|ms| SchemaCache::new(Duration::from_millis(u64::from(ms))));
+        pipeline.result_cache = options.result_cache_ttl_ms.filter(|&ms| ms > 0).map(|ms| {
+            ResultCache::new(
+                Duration::from_millis(u64::from(ms)),
+                options
+                    .result_cache_size
+                    .unwrap_or(result_cache::DEFAULT_CAPACITY),
+            )
+        });
+
+        if let Some(temperature) = options.temperature {
+            pipeline.chat_options = pipeline.chat_options.with_temperature(temperature);
+        }
+        if let Some(top_p) = options.top_p {
+            pipeline.chat_options = pipeline.chat_options.with_top_p(top_p);
+        }
+        if let Some(seed) = options.seed {
+            let seed = client_options::seed(seed).map_err(Error::from_reason)?;
+            pipeline.chat_options = pipeline.chat_options.with_seed(seed);
+        }
+        if let Some(max_tokens) = options.max_tokens {
+            pipeline.chat_options = pipeline.chat_options.with_max_tokens(max_tokens);
+        }
+        pipeline.answer_max_tokens = options.answer_max_tokens;
+        pipeline.answer_language = options
+            .answer_language
+            .map(|language| language.trim().to_string())
+            .filter(|language| !language.is_empty());
+        pipeline.answer_schema = options
+            .answer_schema
+            .map(AnswerSchema::new)
+            .transpose()
+            .map_err(|e| Error::from_reason(format!("Invalid answerSchema: {e}")))?;
+        pipeline.schema_top_k = options.schema_top_k.map(|top_k| top_k as usize);
+        pipeline.max_schema_bytes = options.max_schema_bytes.map(|max_bytes| max_bytes as usize);
+        pipeline.sample_rows = options.sample_rows;
+        pipeline.redaction = Redaction::new(options.redact_properties.unwrap_or_default());
+        pipeline.skip_discovery = options.skip_discovery.unwrap_or(false);
 
         // UDF context: an explicit catalog wins; otherwise optionally discover from the instance.
         if let Some(libraries) = options.udfs {
@@ -213,12 +1237,47 @@ impl TextToCypher {
                     })
                     .collect(),
             );
-            client = client.with_udfs(catalog);
+            pipeline.udf_source = UdfSource::Provided(catalog);
         } else if options.discover_udfs.unwrap_or(false) {
-            client = client.with_discovered_udfs();
+            pipeline.udf_source = UdfSource::Discover;
         }
 
-        Ok(Self { client })
+        Ok(Self {
+            pipeline,
+            timeout_ms: options.timeout_ms,
+            resolve_errors: options.resolve_errors.unwrap_or(false),
+            provider,
+        })
+    }
+
+    /// Checks client options without creating a client
+    ///
+    /// Runs the constructor's checks on `options`, after filling unset ones from
+    /// `setDefaultOptions` as the constructor does, and reports every invalid option instead of
+    /// throwing on the first. Nothing is connected to and no client is created.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Client configuration to check
+    ///
+    /// # Returns
+    ///
+    /// `{ valid, errors }`, where each error names its option in `field` and holds the
+    /// constructor's message for it in `message`
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// const { valid, errors } = TextToCypher.validateOptions(config);
+    /// for (const { field, message } of errors) console.error(`${field}: ${message}`);
+    /// ```
+    #[napi]
+    pub fn validate_options(options: ClientOptions) -> OptionsValidation {
+        let errors = client_options::check(&defaults::apply(options));
+        OptionsValidation {
+            valid: errors.is_empty(),
+            errors,
+        }
     }
 
     /// Converts natural language text to Cypher and executes the query
@@ -233,6 +1292,11 @@ impl TextToCypher {
     ///
     /// * `graph_name` - Name of the graph to query
     /// * `question` - Natural language question or request
+    /// * `schema` - Optional pre-discovered schema (the JSON string `discoverSchema` returns) to
+    ///   use instead of discovering it again
+    /// * `options` - Optional per-call options: an abort `signal`, `maxRows`, `model`,
+    ///   `onProgress`, `generateAnswer`, `answerColumns`, `apiKey`, `resultFormat`, `traceId`,
+    ///   and `groupBy` (see `TextToCypherCallOptions`)
     ///
     /// # Returns
     ///
@@ -247,13 +1311,152 @@ impl TextToCypher {
     /// );
     /// console.log('Query:', response.cypherQuery);
     /// console.log('Answer:', response.answer);
+    ///
+    /// // Reuse a cached schema to skip discovery
+    /// const schema = await client.discoverSchema('movies');
+    /// await client.textToCypher('movies', 'Find all actors', schema);
+    ///
+    /// // Cancel the call if it takes longer than 5 seconds
+    /// await client.textToCypher('movies', 'Find all actors', undefined, {
+    ///   signal: AbortSignal.timeout(5000),
+    /// });
+    ///
+    /// // Keep at most 100 rows
+    /// const top = await client.textToCypher('movies', 'List all actors', schema, {
+    ///   maxRows: 100,
+    /// });
+    /// console.log(top.truncated);
+    ///
+    /// // Send a hard question to a stronger model
+    /// await client.textToCypher('movies', 'Which directors ...', schema, { model: 'gpt-4o' });
+    ///
+    /// // Show which stage is running
+    /// await client.textToCypher('movies', 'Find all actors', undefined, {
+    ///   onProgress: (event) => console.log(event.stage, event.phase),
+    /// });
+    ///
+    /// // Only the rows, without a natural-language answer
+    /// const { resultSet } = await client.textToCypher('movies', 'List all actors', schema, {
+    ///   generateAnswer: false,
+    /// });
+    ///
+    /// // Answer from the titles alone, leaving out the long plot summaries
+    /// await client.textToCypher('movies', 'Return the title and plot of every movie', schema, {
+    ///   answerColumns: ['m.title'],
+    /// });
+    ///
+    /// // Bill the LLM calls to the tenant asking the question
+    /// await client.textToCypher('movies', 'Find all actors', schema, { apiKey: tenant.apiKey });
+    ///
+    /// // The rows as CSV, ready for an ETL job
+    /// const { cypherResult: csv } = await client.textToCypher('movies', 'List all actors',
+    ///   schema, { generateAnswer: false, resultFormat: 'csv' });
+    ///
+    /// // Tag the query with the id of the HTTP request being served
+    /// await client.textToCypher('movies', 'Find all actors', schema, { traceId: req.id });
+    ///
+    /// // How many of the listed movies came out each year
+    /// const { groups } = await client.textToCypher('movies', 'List movies and their release year',
+    ///   schema, { generateAnswer: false, groupBy: 'm.released' });
     /// ```
     #[napi]
     pub async fn text_to_cypher(
         &self,
         graph_name: String,
         question: String,
-    ) -> Result<TextToCypherResponse> {
+        schema: Option<String>,
+        options: Option<TextToCypherCallOptions>,
+    ) -> Outcome<TextToCypherResponse> {
+        let TextToCypherCallOptions {
+            signal,
+            max_rows,
+            model,
+            on_progress,
+            generate_answer,
+            answer_columns,
+            api_key,
+            result_format,
+            trace_id,
+            group_by,
+        } = options.unwrap_or_default();
+        if let Some(schema) = &schema {
+            if let Err(e) = validate_schema(schema) {
+                return e.into();
+            }
+        }
+        if let Err(e) = validate_max_rows(max_rows) {
+            return e.into();
+        }
+        if answer_columns.as_ref().is_some_and(|columns| {
+            columns.is_empty() || columns.iter().any(|column| column.trim().is_empty())
+        }) {
+            return ClientError::new(
+                ErrorCode::InvalidArgument,
+                "Invalid answerColumns: must name at least one column, and no blank ones",
+            )
+            .into();
+        }
+        if group_by
+            .as_ref()
+            .is_some_and(|column| column.trim().is_empty())
+        {
+            return ClientError::new(
+                ErrorCode::InvalidArgument,
+                "Invalid groupBy: must name a column",
+            )
+            .into();
+        }
+        let model = match model_override(model, self.provider) {
+            Ok(model) => model,
+            Err(e) => return e.into(),
+        };
+        let api_key = match api_key_override(api_key) {
+            Ok(api_key) => api_key,
+            Err(e) => return e.into(),
+        };
+        let result_format = match ResultFormat::parse(result_format.as_deref()) {
+            Ok(result_format) => result_format,
+            Err(e) => return e.into(),
+        };
+        let trace_id = match trace_id_override(trace_id) {
+            Ok(trace_id) => trace_id,
+            Err(e) => return e.into(),
+        };
+
+        let cache_key = self.pipeline.result_cache.as_ref().map(|_| ResultKey {
+            graph: graph_name.clone(),
+            question: ResultKey::normalize_question(&question),
+            model: model.clone().unwrap_or_else(|| self.pipeline.model.clone()),
+            schema: schema.clone(),
+            max_rows,
+            generate_answer: generate_answer.unwrap_or(true),
+            answer_columns: answer_columns.clone(),
+            result_format,
+            group_by: group_by.clone(),
+            api_key: api_key.as_deref().map(ResultKey::hash_api_key),
+        });
+        if let (Some(cache), Some(key)) = (&self.pipeline.result_cache, &cache_key) {
+            if let Err(e) = self.pipeline.ensure_open() {
+                return e.into();
+            }
+            let started = Instant::now();
+            let cached = cache.get(key);
+            self.pipeline.metrics.record_result_cache(cached.is_some());
+            if let Some(mut response) = cached {
+                let request_id = trace_id.unwrap_or_else(pipeline::new_request_id);
+                log::in_request(request_id.clone(), || {
+                    self.pipeline.log(LogLevel::Info, None, || {
+                        format!("Returning the cached response for {:?}", key.question)
+                    })
+                });
+                response.trace_id = Some(request_id.clone());
+                response.request_id = Some(request_id);
+                let result = Ok(response);
+                self.pipeline.metrics.record_call(&result, started);
+                return Outcome::from(result);
+            }
+        }
+
         let request = ChatRequest {
             messages: vec![ChatMessage {
                 role: ChatRole::User,
@@ -261,169 +1464,1511 @@ impl TextToCypher {
             }],
         };
 
-        match self.client.text_to_cypher(graph_name, request).await {
-            Ok(response) => Ok(response.into()),
-            Err(e) => Err(Error::from_reason(format!("Text-to-Cypher failed: {}", e))),
+        // Going through the return-value callback hands a thrown exception back here, where it
+        // is dropped, instead of raising it as an uncaught exception.
+        let progress_sink = on_progress.map(|on_progress| {
+            move |event: ProgressEvent| {
+                on_progress.call_with_return_value(
+                    event,
+                    ThreadsafeFunctionCallMode::NonBlocking,
+                    |_, _| Ok(()),
+                );
+            }
+        });
+        let options = RunOptions {
+            skip_answer: !generate_answer.unwrap_or(true),
+            schema,
+            max_rows,
+            answer_columns,
+            result_format,
+            group_by,
+            model,
+            api_key,
+            on_progress: progress_sink.as_ref().map(|sink| sink as ProgressSink),
+            trace_id,
+            ..Default::default()
+        };
+        let result = self
+            .run(
+                &graph_name,
+                request,
+                options,
+                signal,
+                "Text-to-Cypher failed",
+            )
+            .await;
+        // A partial response is left out, so the next call gets another try at the answer.
+        if let (Some(cache), Some(key), Ok(response)) =
+            (&self.pipeline.result_cache, cache_key, &result)
+        {
+            if response.answer_error.is_none() {
+                cache.insert(key, response.clone());
+            }
         }
+        self.settle(result)
     }
 
-    /// Converts natural language text to Cypher and executes the query with multiple messages
+    /// Answers a question about a graph, resolving to just the natural-language answer
     ///
-    /// This method allows for conversation history by accepting multiple messages.
+    /// Runs the same pipeline as `textToCypher` and hands back its `answer`, for callers that do
+    /// not need the query, the rows, or the other response fields.
     ///
     /// # Arguments
     ///
     /// * `graph_name` - Name of the graph to query
-    /// * `messages` - Array of conversation messages
+    /// * `question` - Natural language question
     ///
     /// # Returns
     ///
-    /// A promise that resolves to a TextToCypherResponse
+    /// A promise that resolves to the answer text. It rejects like `textToCypher` when a stage
+    /// fails, and with `ANSWER_GENERATION` when the answer could not be generated or the model
+    /// returned an empty one; the full response is attached as `error.response` either way.
     ///
     /// # Example
     ///
     /// ```javascript
-    /// const response = await client.textToCypherWithMessages('movies', [
-    ///   { role: 'user', content: 'Show me actors' },
-    ///   { role: 'assistant', content: 'Here are the actors...' },
-    ///   { role: 'user', content: 'Filter those who acted after 2020' }
-    /// ]);
+    /// const answer = await client.answer('movies', 'Who directed The Matrix?');
+    /// console.log(answer);
     /// ```
     #[napi]
-    pub async fn text_to_cypher_with_messages(
-        &self,
-        graph_name: String,
-        messages: Vec<Message>,
-    ) -> Result<TextToCypherResponse> {
-        let chat_messages: Result<Vec<ChatMessage>> = messages
-            .into_iter()
-            .map(|msg| {
-                let role = match msg.role.to_lowercase().as_str() {
-                    "user" => ChatRole::User,
-                    "assistant" => ChatRole::Assistant,
-                    "system" => ChatRole::System,
-                    _ => {
-                        return Err(Error::from_reason(format!(
-                            "Invalid message role: '{}'. Must be 'user', 'assistant', or 'system'",
-                            msg.role
-                        )))
-                    }
-                };
-                Ok(ChatMessage {
-                    role,
-                    content: msg.content,
-                })
-            })
-            .collect();
-
+    pub async fn answer(&self, graph_name: String, question: String) -> Outcome<String> {
         let request = ChatRequest {
-            messages: chat_messages?,
+            messages: vec![ChatMessage {
+                role: ChatRole::User,
+                content: question,
+            }],
+        };
+        let mut response = match self
+            .run(
+                &graph_name,
+                request,
+                RunOptions::default(),
+                None,
+                "Text-to-Cypher failed",
+            )
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => return e.into(),
         };
 
-        match self.client.text_to_cypher(graph_name, request).await {
-            Ok(response) => Ok(response.into()),
-            Err(e) => Err(Error::from_reason(format!("Text-to-Cypher failed: {}", e))),
+        match response.answer.take() {
+            Some(answer) if !answer.trim().is_empty() => Outcome::from(Ok(answer)),
+            _ => {
+                let message = response
+                    .answer_error
+                    .take()
+                    .unwrap_or_else(|| "The model returned an empty answer".to_string());
+                response.status = "error".to_string();
+                response.error = Some(message.clone());
+                ClientError::new(ErrorCode::AnswerGeneration, message)
+                    .context("Text-to-Cypher failed")
+                    .with_response(response)
+                    .into()
+            }
         }
     }
 
-    /// Generates a Cypher query without executing it
+    /// Converts natural language text to Cypher and returns the first page of its rows
     ///
-    /// Use this when you only want to generate the query for inspection or manual execution.
+    /// Runs the `textToCypher` pipeline without generating an answer, keeping the first
+    /// `page_size` rows in `resultSet`. When the query returned more, `cursor` is set; pass it to
+    /// `nextPage` for the following rows, without asking the model again.
+    ///
+    /// A cursor holds the graph name, the query, and where the next page starts, and nothing is
+    /// kept on the client or the server between pages, so cursors never expire and any client
+    /// connected to the same FalkorDB can use them. Each page runs the query again and keeps its
+    /// own rows: rows added or removed in between shift the pages, and the row order is only
+    /// stable when the query has an `ORDER BY`.
     ///
     /// # Arguments
     ///
-    /// * `graph_name` - Name of the graph to generate query for
-    /// * `question` - Natural language question or request
+    /// * `graph_name` - Name of the graph to query
+    /// * `question` - Natural language question
+    /// * `page_size` - Rows per page; must be at least 1
+    /// * `schema` - Optional pre-discovered schema (the JSON string `discoverSchema` returns) to
+    ///   use instead of discovering it again
     ///
     /// # Returns
     ///
-    /// A promise that resolves to a TextToCypherResponse with only the schema and query
+    /// A promise that resolves to a TextToCypherResponse with the query, the first page in
+    /// `resultSet`, and a `cursor` unless that page holds every row
     ///
     /// # Example
     ///
     /// ```javascript
-    /// const response = await client.cypherOnly('movies', 'Find all actors');
-    /// console.log('Generated query:', response.cypherQuery);
-    /// // You can now review, modify, or execute the query yourself
+    /// let page = await client.textToCypherPaged('movies', 'List all actors', 100);
+    /// const rows = [...page.resultSet.rows];
+    /// while (page.cursor) {
+    ///   page = await client.nextPage(page.cursor);
+    ///   rows.push(...page.resultSet.rows);
+    /// }
     /// ```
     #[napi]
-    pub async fn cypher_only(
+    pub async fn text_to_cypher_paged(
         &self,
         graph_name: String,
         question: String,
-    ) -> Result<TextToCypherResponse> {
+        page_size: u32,
+        schema: Option<String>,
+    ) -> Outcome<TextToCypherResponse> {
+        if page_size == 0 {
+            return ClientError::new(
+                ErrorCode::InvalidArgument,
+                "Invalid pageSize: 0. Must be at least 1",
+            )
+            .into();
+        }
+        if let Some(schema) = &schema {
+            if let Err(e) = validate_schema(schema) {
+                return e.into();
+            }
+        }
+
         let request = ChatRequest {
             messages: vec![ChatMessage {
                 role: ChatRole::User,
                 content: question,
             }],
         };
-
-        match self.client.cypher_only(graph_name, request).await {
-            Ok(response) => Ok(response.into()),
-            Err(e) => Err(Error::from_reason(format!(
-                "Cypher generation failed: {}",
-                e
-            ))),
-        }
+        let options = RunOptions {
+            skip_answer: true,
+            paged: true,
+            schema,
+            max_rows: Some(page_size),
+            ..Default::default()
+        };
+        let result = self
+            .run(&graph_name, request, options, None, "Text-to-Cypher failed")
+            .await;
+        self.settle(result)
     }
 
-    /// Discovers and returns the schema of a graph
+    /// Fetches the next page of rows of a query run by `textToCypherPaged`
+    ///
+    /// Runs the query the cursor holds again and keeps the page of rows it points to; the model is
+    /// not involved. See `textToCypherPaged` for how long cursors stay valid.
     ///
     /// # Arguments
     ///
-    /// * `graph_name` - Name of the graph to discover schema for
+    /// * `cursor` - The `cursor` of a `textToCypherPaged` response or of the previous page
     ///
     /// # Returns
     ///
-    /// A promise that resolves to a JSON string representing the graph schema
+    /// A promise that resolves to a ResultPage, whose `cursor` is unset on the last page. Rejects
+    /// with `INVALID_ARGUMENT` when `cursor` is not a cursor.
     ///
     /// # Example
     ///
     /// ```javascript
-    /// const schema = await client.discoverSchema('movies');
-    /// console.log('Schema:', JSON.parse(schema));
+    /// const first = await client.textToCypherPaged('movies', 'List all actors', 100);
+    /// if (first.cursor) {
+    ///   const second = await client.nextPage(first.cursor);
+    ///   console.log(second.resultSet.rows);
+    /// }
     /// ```
     #[napi]
-    pub async fn discover_schema(&self, graph_name: String) -> Result<String> {
-        match self.client.discover_schema(graph_name).await {
-            Ok(schema) => Ok(schema),
-            Err(e) => Err(Error::from_reason(format!(
-                "Schema discovery failed: {}",
-                e
-            ))),
+    pub async fn next_page(&self, cursor: String) -> Outcome<ResultPage> {
+        let cursor = match PageCursor::decode(&cursor) {
+            Ok(cursor) => cursor,
+            Err(e) => return e.context("Fetching the next page failed").into(),
+        };
+        match self.with_timeout(self.pipeline.fetch_page(&cursor)).await {
+            Ok(result) => result
+                .map_err(|e| e.context("Fetching the next page failed"))
+                .into(),
+            Err(e) => e.into(),
         }
     }
 
-    /// Lists all available AI models across all supported providers
+    /// Converts natural language text to Cypher and executes the query with multiple messages
     ///
-    /// This method queries all provider APIs (OpenAI, Anthropic, Gemini, Ollama) and
-    /// returns a combined list of available models with provider prefixes.
+    /// This method allows for conversation history by accepting multiple messages. Roles are
+    /// `user`, `assistant`, and `system` (case-insensitive); any other role rejects the call with
+    /// `INVALID_ARGUMENT` rather than being treated as a user message. System messages stay system
+    /// messages wherever they appear, for query generation and answer generation alike.
+    ///
+    /// An assistant message can carry its turn's `cypherQuery` and `cypherResult`, which are sent
+    /// to the model with it, so a follow-up such as "now only the ones from 2020" can refine the
+    /// earlier query and refer to its rows. Only the first 4,000 characters' worth of lines of a
+    /// result are sent; other roles carrying them reject with `INVALID_ARGUMENT`.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_name` - Name of the graph to query
+    /// * `messages` - Array of conversation messages
     ///
     /// # Returns
     ///
-    /// A promise that resolves to an array of model names with provider prefixes
+    /// A promise that resolves to a TextToCypherResponse
     ///
     /// # Example
     ///
     /// ```javascript
-    /// const allModels = await client.listModels();
-    /// console.log('All models:', allModels);
-    /// // Output: ['gpt-4o-mini', 'gpt-4o', 'anthropic:claude-sonnet-4-5', 'gemini:gemini-2.5-pro', ...]
+    /// const response = await client.textToCypherWithMessages('movies', [
+    ///   { role: 'user', content: 'Show me actors' },
+    ///   { role: 'assistant', content: 'Here are the actors...' },
+    ///   { role: 'user', content: 'Filter those who acted after 2020' }
+    /// ]);
+    ///
+    /// // Feeding the previous turn's query and rows back for a follow-up
+    /// const first = await client.textToCypher('movies', 'Which movies did Keanu Reeves act in?');
+    /// const followUp = await client.textToCypherWithMessages('movies', [
+    ///   { role: 'user', content: 'Which movies did Keanu Reeves act in?' },
+    ///   {
+    ///     role: 'assistant',
+    ///     content: first.answer ?? '',
+    ///     cypherQuery: first.cypherQuery,
+    ///     cypherResult: first.cypherResult,
+    ///   },
+    ///   { role: 'user', content: 'Now filter those to only 2020' },
+    /// ]);
     /// ```
     #[napi]
-    pub async fn list_models(&self) -> Result<Vec<String>> {
-        let all_provider_models = self
-            .client
-            .list_all_models()
-            .await
-            .map_err(|e| Error::from_reason(format!("Failed to list models: {}", e)))?;
-
-        let mut all_models = Vec::new();
-        for (adapter_kind, models) in all_provider_models {
-            let prefix = adapter_kind.as_lower_str();
-            for model in models {
-                if adapter_kind == AdapterKind::OpenAI {
+    pub async fn text_to_cypher_with_messages(
+        &self,
+        graph_name: String,
+        messages: Vec<Message>,
+    ) -> Outcome<TextToCypherResponse> {
+        let chat_messages: std::result::Result<Vec<ChatMessage>, ClientError> = messages
+            .into_iter()
+            .enumerate()
+            .map(|(index, msg)| {
+                let role = match msg.role.to_lowercase().as_str() {
+                    "user" => ChatRole::User,
+                    "assistant" => ChatRole::Assistant,
+                    "system" => ChatRole::System,
+                    _ => {
+                        let message = format!(
+                            "Invalid message role: '{}' (message {index}). Must be 'user', 'assistant', or 'system'",
+                            msg.role
+                        );
+                        return Err(ClientError::new(ErrorCode::InvalidArgument, message));
+                    }
+                };
+                if role != ChatRole::Assistant
+                    && (msg.cypher_query.is_some() || msg.cypher_result.is_some())
+                {
+                    let message = format!(
+                        "Invalid message: cypherQuery and cypherResult belong on assistant messages (message {index})"
+                    );
+                    return Err(ClientError::new(ErrorCode::InvalidArgument, message));
+                }
+                let content = match (&msg.cypher_query, &msg.cypher_result) {
+                    (None, None) => msg.content,
+                    (cypher_query, cypher_result) => conversation::turn_context(
+                        &msg.content,
+                        cypher_query.as_deref(),
+                        cypher_result.as_deref(),
+                    ),
+                };
+                Ok(ChatMessage { role, content })
+            })
+            .collect();
+
+        let request = match chat_messages {
+            Ok(messages) => ChatRequest { messages },
+            Err(e) => return e.into(),
+        };
+
+        let result = self
+            .run(
+                &graph_name,
+                request,
+                RunOptions::default(),
+                None,
+                "Text-to-Cypher failed",
+            )
+            .await;
+        self.settle(result)
+    }
+
+    /// Converts natural language text to Cypher, executes the query, and streams the answer
+    ///
+    /// Works like `textToCypher`, but `onChunk` is called with each partial piece of the
+    /// natural-language answer as the model generates it, which lets chat UIs render the answer
+    /// incrementally. Providers that cannot stream deliver the whole answer as a single chunk.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_name` - Name of the graph to query
+    /// * `question` - Natural language question or request
+    /// * `on_chunk` - Callback invoked with each partial chunk of the answer text
+    ///
+    /// # Returns
+    ///
+    /// A promise that resolves to the complete TextToCypherResponse once streaming finishes
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// const response = await client.textToCypherStream('movies', 'Who directed The Matrix?',
+    ///   (chunk) => process.stdout.write(chunk));
+    /// console.log('\nQuery:', response.cypherQuery);
+    /// ```
+    #[napi]
+    pub async fn text_to_cypher_stream(
+        &self,
+        graph_name: String,
+        question: String,
+        on_chunk: ThreadsafeFunction<String, (), String, Status, false>,
+    ) -> Outcome<TextToCypherResponse> {
+        let request = ChatRequest {
+            messages: vec![ChatMessage {
+                role: ChatRole::User,
+                content: question,
+            }],
+        };
+
+        let sink = |chunk: String| {
+            on_chunk.call(chunk, ThreadsafeFunctionCallMode::NonBlocking);
+        };
+        let options = RunOptions {
+            on_answer_chunk: Some(&sink),
+            ..Default::default()
+        };
+        let result = self
+            .run(&graph_name, request, options, None, "Text-to-Cypher failed")
+            .await;
+        self.settle(result)
+    }
+
+    /// Answers several questions against the same graph, discovering its schema only once
+    ///
+    /// Each question runs through the same steps as `textToCypher`, a few at a time so the model
+    /// provider is not flooded. A question that fails does not fail the batch: its slot holds an
+    /// error-status response (the same one a rejected `textToCypher` attaches as
+    /// `error.response`) while the other questions are still answered.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_name` - Name of the graph to query
+    /// * `questions` - Natural language questions or requests
+    /// * `schema` - Optional pre-discovered schema (the JSON string `discoverSchema` returns) to
+    ///   use instead of discovering it
+    /// * `on_progress` - Optional callback invoked with `(completed, total)` each time a question
+    ///   finishes, successfully or not. It is not awaited, and an exception it throws is ignored
+    ///
+    /// # Returns
+    ///
+    /// A promise that resolves to one TextToCypherResponse per question, in input order. It only
+    /// rejects when the schema cannot be discovered or the supplied one is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// const responses = await client.textToCypherBatch(
+    ///   'movies',
+    ///   ['How many movies are there?', 'Who directed The Matrix?'],
+    ///   undefined,
+    ///   (completed, total) => console.log(`${Math.round((100 * completed) / total)}%`)
+    /// );
+    /// for (const response of responses) {
+    ///   console.log(response.status === 'success' ? response.answer : response.error);
+    /// }
+    /// ```
+    #[napi]
+    #[allow(clippy::type_complexity)]
+    pub async fn text_to_cypher_batch(
+        &self,
+        graph_name: String,
+        questions: Vec<String>,
+        schema: Option<String>,
+        on_progress: Option<
+            ThreadsafeFunction<FnArgs<(u32, u32)>, (), FnArgs<(u32, u32)>, Status, false>,
+        >,
+    ) -> Outcome<Vec<TextToCypherResponse>> {
+        let schema = match self.batch_schema(&graph_name, schema).await {
+            Ok(schema) => schema,
+            Err(e) => return e.into(),
+        };
+
+        let total = u32::try_from(questions.len()).unwrap_or(u32::MAX);
+        let completed = AtomicU32::new(0);
+        // As for `textToCypher`'s `onProgress`, a thrown exception comes back here and is dropped.
+        let report = |completed: u32| {
+            if let Some(on_progress) = &on_progress {
+                on_progress.call_with_return_value(
+                    (completed, total).into(),
+                    ThreadsafeFunctionCallMode::NonBlocking,
+                    |_, _| Ok(()),
+                );
+            }
+        };
+        let responses = stream::iter(questions)
+            .map(|question| {
+                let request = ChatRequest {
+                    messages: vec![ChatMessage {
+                        role: ChatRole::User,
+                        content: question,
+                    }],
+                };
+                let options = RunOptions {
+                    schema: Some(schema.clone()),
+                    ..Default::default()
+                };
+                let run = self.run(&graph_name, request, options, None, "Text-to-Cypher failed");
+                async {
+                    let result = run.await;
+                    report(completed.fetch_add(1, Ordering::Relaxed) + 1);
+                    result
+                }
+            })
+            .buffered(BATCH_CONCURRENCY)
+            .map(|result| result.unwrap_or_else(ClientError::into_response))
+            .collect()
+            .await;
+
+        Outcome::from(Ok(responses))
+    }
+
+    /// Answers several questions against the same graph like `textToCypherBatch`, handing each
+    /// response to a callback as soon as it is ready
+    ///
+    /// Questions run a few at a time, as in `textToCypherBatch`, and `on_result` is called once
+    /// per question, in completion order, with the question's index in `questions` and its
+    /// response (an error-status one for a question that failed). Each call is awaited before the
+    /// next, so the promise settles only after every response was delivered. An exception thrown
+    /// by `on_result` is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_name` - Name of the graph to query
+    /// * `questions` - Natural language questions or requests
+    /// * `on_result` - Called with a BatchResult (`{ index, response }`) for each question
+    /// * `schema` - Optional pre-discovered schema (the JSON string `discoverSchema` returns) to
+    ///   use instead of discovering it
+    ///
+    /// # Returns
+    ///
+    /// A promise that resolves once every question was answered and delivered. It only rejects
+    /// when the schema cannot be discovered or the supplied one is invalid, before any question
+    /// runs.
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// await client.textToCypherBatchEach('movies', questions, ({ index, response }) => {
+    ///   socket.send(JSON.stringify({ question: questions[index], answer: response.answer }));
+    /// });
+    /// ```
+    #[napi]
+    pub async fn text_to_cypher_batch_each(
+        &self,
+        graph_name: String,
+        questions: Vec<String>,
+        on_result: ThreadsafeFunction<BatchResult, (), BatchResult, Status, false>,
+        schema: Option<String>,
+    ) -> Outcome<()> {
+        let schema = match self.batch_schema(&graph_name, schema).await {
+            Ok(schema) => schema,
+            Err(e) => return e.into(),
+        };
+
+        stream::iter(questions.into_iter().enumerate())
+            .map(|(index, question)| {
+                let request = ChatRequest {
+                    messages: vec![ChatMessage {
+                        role: ChatRole::User,
+                        content: question,
+                    }],
+                };
+                let options = RunOptions {
+                    schema: Some(schema.clone()),
+                    ..Default::default()
+                };
+                let run = self.run(&graph_name, request, options, None, "Text-to-Cypher failed");
+                async move { (index, run.await) }
+            })
+            .buffer_unordered(BATCH_CONCURRENCY)
+            .for_each(|(index, result)| {
+                let result = BatchResult {
+                    index: u32::try_from(index).unwrap_or(u32::MAX),
+                    response: result.unwrap_or_else(ClientError::into_response),
+                };
+                let on_result = &on_result;
+                async move {
+                    // The callback's own exceptions are not the batch's to report.
+                    let _ = on_result.call_async_catch(result).await;
+                }
+            })
+            .await;
+
+        Outcome::from(Ok(()))
+    }
+
+    /// Generates a Cypher query without executing it
+    ///
+    /// Use this when you only want to generate the query for inspection or manual execution.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_name` - Name of the graph to generate query for
+    /// * `question` - Natural language question or request
+    /// * `schema` - Optional pre-discovered schema (the JSON string `discoverSchema` returns) to
+    ///   use instead of discovering it again
+    /// * `signal` - Optional `AbortSignal`; aborting it cancels the call, which rejects with an
+    ///   `ABORTED` error
+    /// * `model` - Optional model to use for this call instead of the client's `model`
+    /// * `api_key` - Optional API key to send this call's LLM requests with instead of the
+    ///   client's `apiKey`
+    /// * `trace_id` - Optional identifier for the call, returned in `traceId`, as for
+    ///   `textToCypher`. No generated query runs, so none is tagged with it
+    ///
+    /// # Returns
+    ///
+    /// A promise that resolves to a TextToCypherResponse with only the schema and query
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// const response = await client.cypherOnly('movies', 'Find all actors');
+    /// console.log('Generated query:', response.cypherQuery);
+    /// // You can now review, modify, or execute the query yourself
+    /// ```
+    #[napi]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn cypher_only(
+        &self,
+        graph_name: String,
+        question: String,
+        schema: Option<String>,
+        signal: Option<Signal>,
+        model: Option<String>,
+        api_key: Option<String>,
+        trace_id: Option<String>,
+    ) -> Outcome<TextToCypherResponse> {
+        if let Some(schema) = &schema {
+            if let Err(e) = validate_schema(schema) {
+                return e.into();
+            }
+        }
+        let model = match model_override(model, self.provider) {
+            Ok(model) => model,
+            Err(e) => return e.into(),
+        };
+        let api_key = match api_key_override(api_key) {
+            Ok(api_key) => api_key,
+            Err(e) => return e.into(),
+        };
+        let trace_id = match trace_id_override(trace_id) {
+            Ok(trace_id) => trace_id,
+            Err(e) => return e.into(),
+        };
+
+        let request = ChatRequest {
+            messages: vec![ChatMessage {
+                role: ChatRole::User,
+                content: question,
+            }],
+        };
+
+        let options = RunOptions {
+            cypher_only: true,
+            schema,
+            model,
+            api_key,
+            trace_id,
+            ..Default::default()
+        };
+        let result = self
+            .run(
+                &graph_name,
+                request,
+                options,
+                signal,
+                "Cypher generation failed",
+            )
+            .await;
+        self.settle(result)
+    }
+
+    /// Generates a Cypher query without executing it, streaming the query as it is generated
+    ///
+    /// Works like `cypherOnly`, but `onChunk` is called with each partial piece of the query as the
+    /// model writes it, which lets editors show the query taking shape. The chunks are the model's
+    /// raw text: `cypherQuery` in the resolved response is the finished query, with any code fences
+    /// removed and `enforceLimit` applied. Providers that cannot stream, and `structuredOutput`,
+    /// deliver the finished query as a single chunk. A streamed reply is not retried.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_name` - Name of the graph to generate query for
+    /// * `question` - Natural language question or request
+    /// * `on_chunk` - Callback invoked with each partial chunk of the query text
+    ///
+    /// # Returns
+    ///
+    /// A promise that resolves to a TextToCypherResponse with only the schema and query once
+    /// streaming finishes
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// const response = await client.cypherOnlyStream('movies', 'Find all actors', (chunk) => {
+    ///   process.stdout.write(chunk);
+    /// });
+    /// console.log('\nQuery:', response.cypherQuery);
+    /// ```
+    #[napi]
+    pub async fn cypher_only_stream(
+        &self,
+        graph_name: String,
+        question: String,
+        on_chunk: ThreadsafeFunction<String, (), String, Status, false>,
+    ) -> Outcome<TextToCypherResponse> {
+        let request = ChatRequest {
+            messages: vec![ChatMessage {
+                role: ChatRole::User,
+                content: question,
+            }],
+        };
+
+        let sink = |chunk: String| {
+            on_chunk.call(chunk, ThreadsafeFunctionCallMode::NonBlocking);
+        };
+        let options = RunOptions {
+            cypher_only: true,
+            on_query_chunk: Some(&sink),
+            ..Default::default()
+        };
+        let result = self
+            .run(
+                &graph_name,
+                request,
+                options,
+                None,
+                "Cypher generation failed",
+            )
+            .await;
+        self.settle(result)
+    }
+
+    /// Generates a Cypher query from a supplied schema, without any FalkorDB connection
+    ///
+    /// For schemas maintained outside FalkorDB: nothing is discovered or executed and no
+    /// connection is opened, so it works where FalkorDB is unreachable. UDFs from the `udfs`
+    /// option are shown to the model; `discoverUdfs` is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema_json` - The schema as a JSON object in the format `discoverSchema` returns
+    /// * `question` - Natural language question or request
+    ///
+    /// # Returns
+    ///
+    /// A promise that resolves to a TextToCypherResponse with only the schema and query. It
+    /// rejects with `INVALID_ARGUMENT` when `schema_json` is not a JSON object.
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// const schema = JSON.stringify({
+    ///   entities: [{ label: 'Movie', attributes: [{ name: 'title', type: 'String' }] }],
+    ///   relations: [],
+    /// });
+    /// const { cypherQuery } = await client.cypherFromSchema(schema, 'List every movie title');
+    /// ```
+    #[napi]
+    pub async fn cypher_from_schema(
+        &self,
+        schema_json: String,
+        question: String,
+    ) -> Outcome<TextToCypherResponse> {
+        if let Err(e) = validate_schema(&schema_json) {
+            return e.into();
+        }
+
+        let request = ChatRequest {
+            messages: vec![ChatMessage {
+                role: ChatRole::User,
+                content: question,
+            }],
+        };
+
+        let options = RunOptions {
+            cypher_only: true,
+            detached: true,
+            schema: Some(schema_json),
+            ..Default::default()
+        };
+        let result = self
+            .run("", request, options, None, "Cypher generation failed")
+            .await;
+        self.settle(result)
+    }
+
+    /// Generates a Cypher query and returns FalkorDB's execution plan for it, without executing it
+    ///
+    /// Works like `cypherOnly`, then has FalkorDB plan the generated query with `GRAPH.EXPLAIN`,
+    /// which shows the operations (scans, index lookups, expansions) it would run.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_name` - Name of the graph to generate and plan the query for
+    /// * `question` - Natural language question or request
+    /// * `schema` - Optional pre-discovered schema (the JSON string `discoverSchema` returns) to
+    ///   use instead of discovering it again
+    /// * `signal` - Optional `AbortSignal`; aborting it cancels the call, which rejects with an
+    ///   `ABORTED` error
+    /// * `model` - Optional model to use for this call instead of the client's `model`
+    ///
+    /// # Returns
+    ///
+    /// A promise that resolves to a TextToCypherResponse with the schema, the query, and its
+    /// `queryPlan`. A query FalkorDB cannot plan rejects with a `QUERY_EXECUTION` error whose
+    /// `error.response.cypherQuery` holds it.
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// const response = await client.explainQuery('movies', 'Find all actors');
+    /// console.log(response.cypherQuery);
+    /// console.log(response.queryPlan);
+    /// ```
+    #[napi]
+    pub async fn explain_query(
+        &self,
+        graph_name: String,
+        question: String,
+        schema: Option<String>,
+        signal: Option<Signal>,
+        model: Option<String>,
+    ) -> Outcome<TextToCypherResponse> {
+        if let Some(schema) = &schema {
+            if let Err(e) = validate_schema(schema) {
+                return e.into();
+            }
+        }
+        let model = match model_override(model, self.provider) {
+            Ok(model) => model,
+            Err(e) => return e.into(),
+        };
+
+        let request = ChatRequest {
+            messages: vec![ChatMessage {
+                role: ChatRole::User,
+                content: question,
+            }],
+        };
+
+        let options = RunOptions {
+            cypher_only: true,
+            explain: true,
+            schema,
+            model,
+            ..Default::default()
+        };
+        let result = self
+            .run(
+                &graph_name,
+                request,
+                options,
+                signal,
+                "Query explanation failed",
+            )
+            .await;
+        self.settle(result)
+    }
+
+    /// Revises a generated Cypher query according to feedback, without executing it
+    ///
+    /// Sends `previous_query` back to the model as its own earlier reply, followed by `feedback`
+    /// as the user's correction, so a wrong query can be fixed conversationally. Works like
+    /// `cypherOnly` otherwise: the schema comes from `schema`, the schema cache, or discovery,
+    /// and the revised query is returned without running it.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_name` - Name of the graph the query is for
+    /// * `previous_query` - The query to revise, e.g. a previous response's `cypherQuery`
+    /// * `feedback` - What is wrong with the query, in natural language
+    /// * `schema` - Optional pre-discovered schema (the JSON string `discoverSchema` returns) to
+    ///   use instead of discovering it again
+    ///
+    /// # Returns
+    ///
+    /// A promise that resolves to a TextToCypherResponse with the schema and the revised query
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// const first = await client.cypherOnly('movies', 'Who acted in The Matrix?');
+    /// const revised = await client.refineCypher(
+    ///   'movies',
+    ///   first.cypherQuery,
+    ///   'Match the movie by title, not by id'
+    /// );
+    /// console.log('Revised query:', revised.cypherQuery);
+    /// ```
+    #[napi]
+    pub async fn refine_cypher(
+        &self,
+        graph_name: String,
+        previous_query: String,
+        feedback: String,
+        schema: Option<String>,
+    ) -> Outcome<TextToCypherResponse> {
+        if previous_query.trim().is_empty() || feedback.trim().is_empty() {
+            return ClientError::new(
+                ErrorCode::InvalidArgument,
+                "Invalid refinement: previousQuery and feedback must not be empty",
+            )
+            .into();
+        }
+        if let Some(schema) = &schema {
+            if let Err(e) = validate_schema(schema) {
+                return e.into();
+            }
+        }
+
+        let request = ChatRequest {
+            messages: vec![
+                ChatMessage {
+                    role: ChatRole::Assistant,
+                    content: previous_query,
+                },
+                ChatMessage {
+                    role: ChatRole::User,
+                    content: format!(
+                        "The previous query needs to be revised: {feedback}. Please generate a corrected Cypher query."
+                    ),
+                },
+            ],
+        };
+
+        let options = RunOptions {
+            cypher_only: true,
+            schema,
+            ..Default::default()
+        };
+        let result = self
+            .run(
+                &graph_name,
+                request,
+                options,
+                None,
+                "Cypher refinement failed",
+            )
+            .await;
+        self.settle(result)
+    }
+
+    /// Discovers and returns the schema of a graph
+    ///
+    /// With `schemaCacheTtlMs` set, a schema cached within the TTL is returned without querying
+    /// FalkorDB, and a freshly discovered one is cached.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_name` - Name of the graph to discover schema for
+    ///
+    /// # Returns
+    ///
+    /// A promise that resolves to a JSON string representing the graph schema
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// const schema = await client.discoverSchema('movies');
+    /// console.log('Schema:', JSON.parse(schema));
+    /// ```
+    #[napi]
+    pub async fn discover_schema(&self, graph_name: String) -> Outcome<String> {
+        match self
+            .with_timeout(self.pipeline.discover_schema(&graph_name))
+            .await
+        {
+            Ok(result) => result
+                .map_err(|e| e.context("Schema discovery failed"))
+                .into(),
+            Err(e) => e.into(),
+        }
+    }
+
+    /// Discovers the schema of a graph, blocking until it is available
+    ///
+    /// The same schema `discoverSchema` returns, but the call blocks the calling thread (and with
+    /// it the whole event loop) until schema discovery finishes. Meant for CLI tools and scripts
+    /// that want the schema without `await`; servers should use `discoverSchema` instead. Honours
+    /// `timeoutMs` and the schema cache like `discoverSchema`, and throws the same errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_name` - Name of the graph to discover schema for
+    ///
+    /// # Returns
+    ///
+    /// A JSON string representing the graph schema
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// const schema = client.discoverSchemaSync('movies');
+    /// console.log('Schema:', JSON.parse(schema));
+    /// ```
+    #[napi]
+    pub fn discover_schema_sync(&self, graph_name: String) -> Outcome<String> {
+        match block_on(self.with_timeout(self.pipeline.discover_schema(&graph_name))) {
+            Ok(result) => result
+                .map_err(|e| e.context("Schema discovery failed"))
+                .into(),
+            Err(e) => e.into(),
+        }
+    }
+
+    /// Discovers the schema of a graph as a typed object
+    ///
+    /// The same schema `discoverSchema` returns, already parsed: `entities` lists the node labels
+    /// and `relations` the relationship types with their endpoint labels, each with its
+    /// `attributes`. Uses the schema cache like `discoverSchema`.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_name` - Name of the graph to discover schema for
+    ///
+    /// # Returns
+    ///
+    /// A promise that resolves to the graph's Schema
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// const schema = await client.discoverSchemaTyped('movies');
+    /// for (const entity of schema.entities) {
+    ///   console.log(entity.label, entity.attributes.map((attribute) => attribute.name));
+    /// }
+    /// ```
+    #[napi]
+    pub async fn discover_schema_typed(&self, graph_name: String) -> Outcome<Schema> {
+        match self
+            .with_timeout(self.pipeline.discover_schema(&graph_name))
+            .await
+        {
+            Ok(result) => result
+                .and_then(|schema| Schema::from_json(&schema))
+                .map_err(|e| e.context("Schema discovery failed"))
+                .into(),
+            Err(e) => e.into(),
+        }
+    }
+
+    /// Discovers the schemas of several graphs at once
+    ///
+    /// Graphs are discovered a few at a time, each bounded by `timeoutMs` on its own. A graph whose
+    /// discovery fails does not fail the call: its entry holds the error while the other graphs
+    /// are still discovered. The schema cache is used as in `discoverSchema`.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_names` - Names of the graphs to discover schemas for
+    ///
+    /// # Returns
+    ///
+    /// A promise that resolves to an object keyed by graph name, holding either the schema JSON
+    /// string or the error message for each graph
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// const schemas = await client.discoverSchemas(['movies', 'social']);
+    /// for (const [graphName, { schema, error }] of Object.entries(schemas)) {
+    ///   console.log(graphName, schema ? JSON.parse(schema) : error);
+    /// }
+    /// ```
+    #[napi]
+    pub async fn discover_schemas(
+        &self,
+        graph_names: Vec<String>,
+    ) -> Outcome<HashMap<String, GraphSchema>> {
+        if let Err(e) = self.pipeline.ensure_open() {
+            return e.into();
+        }
+        let graph_names: HashSet<String> = graph_names.into_iter().collect();
+
+        let schemas = stream::iter(graph_names)
+            .map(|graph_name| async move {
+                let result = match self
+                    .with_timeout(self.pipeline.discover_schema(&graph_name))
+                    .await
+                {
+                    Ok(result) => result.map_err(|e| e.context("Schema discovery failed")),
+                    Err(e) => Err(e),
+                };
+                let entry = match result {
+                    Ok(schema) => GraphSchema {
+                        schema: Some(schema),
+                        error: None,
+                    },
+                    Err(e) => GraphSchema {
+                        schema: None,
+                        error: Some(e.to_string()),
+                    },
+                };
+                (graph_name, entry)
+            })
+            .buffer_unordered(BATCH_CONCURRENCY)
+            .collect()
+            .await;
+
+        Outcome::from(Ok(schemas))
+    }
+
+    /// Describes the schema of a graph in natural language
+    ///
+    /// Discovers the schema like `discoverSchema` and has the model summarize it in a few short
+    /// paragraphs: the node labels, how they are connected, and the properties most useful for
+    /// asking questions. The description is generated with `answerModel` when set, and otherwise
+    /// with `model`. With `schemaCacheTtlMs` set, it is cached along with the schema, so calls
+    /// within the TTL neither query FalkorDB nor call the model.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_name` - Name of the graph to describe
+    ///
+    /// # Returns
+    ///
+    /// A promise that resolves to the description. It rejects with `SCHEMA_DISCOVERY` when the
+    /// schema cannot be discovered, and with `ANSWER_GENERATION` when the model fails or replies
+    /// with nothing.
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// console.log(await client.describeSchema('movies'));
+    /// // "The graph holds movies and the people who made them. Person nodes connect to Movie ..."
+    /// ```
+    #[napi]
+    pub async fn describe_schema(&self, graph_name: String) -> Outcome<String> {
+        match self
+            .with_timeout(self.pipeline.describe_schema(&graph_name))
+            .await
+        {
+            Ok(result) => result
+                .map_err(|e| e.context("Schema description failed"))
+                .into(),
+            Err(e) => e.into(),
+        }
+    }
+
+    /// Estimates how many tokens the query-generation prompt for a question will use
+    ///
+    /// Builds the prompt `textToCypher` would send to `model` for `question` (system prompt,
+    /// schema as pruned by `schemaTopK`, UDF context, skills, and examples), without calling the
+    /// model, and counts its tokens approximately: about 4 characters per token, 3.5 for
+    /// Anthropic models, plus a few per message. It is an estimate, not the provider's tokenizer,
+    /// so expect it to be off by 10-20% (more for non-English text or code-heavy schemas), and it
+    /// covers only the input of query generation: the reply, the answer call, and self-healing
+    /// add more. The schema is taken from the schema cache or discovered, like `textToCypher`
+    /// does.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_name` - Name of the graph the question is about
+    /// * `question` - The question to estimate
+    ///
+    /// # Returns
+    ///
+    /// A promise that resolves to the estimated number of prompt tokens. It rejects like schema
+    /// discovery does, and with `SCHEMA_TOO_LARGE` when the schema is over `maxSchemaBytes`.
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// const tokens = await client.estimateTokens('movies', 'Who directed The Matrix?');
+    /// // Pick a cheaper model for small prompts, for instance.
+    /// ```
+    #[napi]
+    pub async fn estimate_tokens(&self, graph_name: String, question: String) -> Outcome<u32> {
+        match self
+            .with_timeout(self.pipeline.estimate_tokens(&graph_name, &question))
+            .await
+        {
+            Ok(result) => result
+                .map_err(|e| e.context("Token estimation failed"))
+                .into(),
+            Err(e) => e.into(),
+        }
+    }
+
+    /// Drops cached schemas so the next call discovers them again
+    ///
+    /// Call this after changing a graph's schema. Does nothing unless `schemaCacheTtlMs` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_name` - Graph whose cached schema to drop; omit to clear the whole cache
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// // After adding labels or properties to the movies graph
+    /// client.invalidateSchemaCache('movies');
+    /// ```
+    #[napi]
+    pub fn invalidate_schema_cache(&self, graph_name: Option<String>) {
+        if let Some(cache) = &self.pipeline.schema_cache {
+            cache.invalidate(graph_name.as_deref());
+        }
+    }
+
+    /// Drops every cached `textToCypher` response, so the next calls run the pipeline again
+    ///
+    /// Call this after writing to a graph whose answers are cached. Does nothing unless
+    /// `resultCacheTtlMs` is set.
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// // After loading new movies into the graph
+    /// client.clearResultCache();
+    /// ```
+    #[napi]
+    pub fn clear_result_cache(&self) {
+        if let Some(cache) = &self.pipeline.result_cache {
+            cache.clear();
+        }
+    }
+
+    /// Sets notes about a graph that every query generated for it should follow
+    ///
+    /// The hints are added to the query-generation prompt of every later call on `graph_name`,
+    /// for knowledge the schema cannot carry, such as the scale a property uses or which of two
+    /// similar relationship types to prefer. They are kept in memory on the client, replacing
+    /// any hints the graph had; an empty list removes them.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_name` - Name of the graph the hints apply to
+    /// * `hints` - One note per entry; a blank entry throws an `INVALID_ARGUMENT` error
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// client.setGraphHints('movies', ['The rating property is 0-10, not 0-100.']);
+    /// ```
+    #[napi]
+    pub fn set_graph_hints(&self, graph_name: String, hints: Vec<String>) -> Outcome<()> {
+        pipeline::check_graph_name(&graph_name)
+            .and_then(|()| self.pipeline.graph_hints.set(&graph_name, hints))
+            .into()
+    }
+
+    /// Returns the hints set for a graph with `setGraphHints`, or an empty array
+    #[napi]
+    pub fn get_graph_hints(&self, graph_name: String) -> Vec<String> {
+        self.pipeline.graph_hints.get(&graph_name)
+    }
+
+    /// Drops the hints of a graph, or of every graph when no name is given
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// client.clearGraphHints('movies'); // just the movies graph
+    /// client.clearGraphHints();         // every graph
+    /// ```
+    #[napi]
+    pub fn clear_graph_hints(&self, graph_name: Option<String>) {
+        self.pipeline.graph_hints.clear(graph_name.as_deref());
+    }
+
+    /// Returns a snapshot of the client's usage counters
+    ///
+    /// Counts the calls that ran the text-to-cypher pipeline since the client was created: how
+    /// many resolved and rejected, which stage failed, schema and result cache hits and misses,
+    /// and the mean call duration. Reading them is cheap and never waits on a running call.
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// const { calls, failures, averageLatencyMs } = client.metrics();
+    /// console.log(`${failures}/${calls} failed, ${averageLatencyMs.toFixed(0)} ms on average`);
+    /// ```
+    #[napi]
+    pub fn metrics(&self) -> Metrics {
+        self.pipeline.metrics.snapshot()
+    }
+
+    /// Checks that a Cypher query parses, without executing it
+    ///
+    /// FalkorDB plans the query with `GRAPH.EXPLAIN`, which never runs it, so write queries are
+    /// checked without changing the graph. Use it to vet a query from `cypherOnly` before running
+    /// it yourself.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_name` - Name of the graph to plan the query against
+    /// * `cypher_query` - The Cypher query to check
+    ///
+    /// # Returns
+    ///
+    /// A promise that resolves to a CypherValidation; an invalid query resolves with `valid: false`
+    /// and FalkorDB's error rather than rejecting. Rejects when FalkorDB cannot be reached.
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// const { cypherQuery } = await client.cypherOnly('movies', 'Find all actors');
+    /// const { valid, error } = await client.validateCypher('movies', cypherQuery);
+    /// if (!valid) console.error('Generated query does not parse:', error);
+    /// ```
+    #[napi]
+    pub async fn validate_cypher(
+        &self,
+        graph_name: String,
+        cypher_query: String,
+    ) -> Outcome<CypherValidation> {
+        match self
+            .with_timeout(self.pipeline.validate_query(&graph_name, &cypher_query))
+            .await
+        {
+            Ok(result) => result
+                .map(|error| CypherValidation {
+                    valid: error.is_none(),
+                    error,
+                })
+                .map_err(|e| e.context("Query validation failed"))
+                .into(),
+            Err(e) => e.into(),
+        }
+    }
+
+    /// Runs a Cypher query you supply, without generating one or an answer
+    ///
+    /// Use it to run a generated query after refining it by hand, over the client's own
+    /// connection. The query gets the same treatment as a generated one: read-only mode refuses
+    /// write clauses before the query reaches FalkorDB, `queryTimeoutMs` and `timeoutMs` bound it,
+    /// and `maxRows` caps the rows kept. With read-only mode off, a write query is run as one,
+    /// without retries, so it is never applied twice; call `clearResultCache` afterwards if
+    /// answers are cached.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_name` - Name of the graph to run the query against
+    /// * `cypher_query` - The Cypher query to run
+    ///
+    /// # Returns
+    ///
+    /// A promise that resolves to a TextToCypherResponse with `cypherQuery`, `cypherResult`,
+    /// `resultSet`, and `rowCount`, and no `schema` or `answer`. A query FalkorDB rejects rejects
+    /// with `QUERY_EXECUTION`, its `error.response.executionError` holding FalkorDB's message.
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// const { cypherQuery } = await client.cypherOnly('movies', 'Find all actors');
+    /// const edited = `${cypherQuery} ORDER BY a.name`;
+    /// const response = await client.executeCypher('movies', edited);
+    /// console.log(response.resultSet.rows);
+    /// ```
+    #[napi]
+    pub async fn execute_cypher(
+        &self,
+        graph_name: String,
+        cypher_query: String,
+    ) -> Outcome<TextToCypherResponse> {
+        let request_id = pipeline::new_request_id();
+        let executed = log::with_request_id(
+            request_id.clone(),
+            self.with_timeout(self.pipeline.execute_cypher(
+                &graph_name,
+                &cypher_query,
+                &request_id,
+            )),
+        )
+        .await;
+        let result = match executed {
+            Ok(result) => result.map_err(|e| e.context("Query execution failed")),
+            Err(e) => Err(e),
+        };
+        self.settle(result.map_err(|e| e.with_request_id(request_id)))
+    }
+
+    /// Checks that the configured FalkorDB connection works
+    ///
+    /// Connects with the client's connection settings and sends a `PING`, without touching any
+    /// graph, so it is cheap enough for readiness probes. Bounded by `timeoutMs` like the other
+    /// methods.
+    ///
+    /// # Returns
+    ///
+    /// A promise that resolves to `true` when FalkorDB answers, and rejects with a `CONNECTION`
+    /// error when it cannot be reached (or `TIMEOUT` when `timeoutMs` runs out first)
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// app.get('/ready', async (req, res) => {
+    ///   try {
+    ///     await client.ping();
+    ///     res.sendStatus(200);
+    ///   } catch {
+    ///     res.sendStatus(503);
+    ///   }
+    /// });
+    /// ```
+    #[napi]
+    pub async fn ping(&self) -> Outcome<bool> {
+        match self.with_timeout(self.pipeline.ping()).await {
+            Ok(result) => result
+                .map(|()| true)
+                .map_err(|e| e.context("Ping failed"))
+                .into(),
+            Err(e) => e.into(),
+        }
+    }
+
+    /// Prepares the client for its first calls
+    ///
+    /// Opens the FalkorDB connection pool, connecting all `poolSize` connections, and discovers
+    /// the schemas of `graphNames` into the schema cache, so the first calls on those graphs skip
+    /// both steps. Schemas already cached are kept. Connecting and each graph's discovery are
+    /// bounded by `timeoutMs` on their own.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_names` - Names of the graphs whose schemas to cache; may be empty to only connect
+    ///
+    /// # Returns
+    ///
+    /// A promise that resolves once the pool is open and every schema is cached. It rejects with a
+    /// `CONNECTION` error when FalkorDB cannot be reached, with the first graph's discovery error
+    /// (e.g. `GRAPH_NOT_FOUND`), and with `INVALID_ARGUMENT` when graphs are named but
+    /// `schemaCacheTtlMs` is unset, as there would be no cache to fill.
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// await client.warmup(['movies', 'social']);
+    /// server.listen(3000);
+    /// ```
+    #[napi]
+    pub async fn warmup(&self, graph_names: Vec<String>) -> Outcome<()> {
+        if !graph_names.is_empty() && self.pipeline.schema_cache.is_none() {
+            return ClientError::new(
+                ErrorCode::InvalidArgument,
+                "Invalid graphNames: caching schemas needs schemaCacheTtlMs",
+            )
+            .into();
+        }
+        let warm = async {
+            self.with_timeout(self.pipeline.open_pool_early()).await??;
+            let graph_names: HashSet<String> = graph_names.into_iter().collect();
+            stream::iter(graph_names)
+                .map(|graph_name| async move {
+                    self.with_timeout(self.pipeline.discover_schema(&graph_name))
+                        .await?
+                        .map(drop)
+                })
+                .buffer_unordered(BATCH_CONCURRENCY)
+                .try_collect::<Vec<()>>()
+                .await
+        };
+        warm.await
+            .map(drop)
+            .map_err(|e| e.context("Warmup failed"))
+            .into()
+    }
+
+    /// Lists the graphs on the connected FalkorDB instance
+    ///
+    /// Uses the same connection settings as the other methods.
+    ///
+    /// # Returns
+    ///
+    /// A promise that resolves to an array of graph names
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// const graphs = await client.listGraphs();
+    /// if (graphs.includes('movies')) {
+    ///   const schema = await client.discoverSchema('movies');
+    /// }
+    /// ```
+    #[napi]
+    pub async fn list_graphs(&self) -> Outcome<Vec<String>> {
+        match self.with_timeout(self.pipeline.list_graphs()).await {
+            Ok(result) => result
+                .map_err(|e| e.context("Listing graphs failed"))
+                .into(),
+            Err(e) => e.into(),
+        }
+    }
+
+    /// Lists the node labels and relationship types of a graph
+    ///
+    /// A lightweight alternative to `discoverSchema` for callers that only need the names, e.g.
+    /// for autocomplete or validation UIs: it asks FalkorDB's catalog (`db.labels()` and
+    /// `db.relationshipTypes()`) rather than sampling entities for their properties, and neither
+    /// uses nor fills the schema cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph_name` - Name of the graph
+    ///
+    /// # Returns
+    ///
+    /// A promise that resolves to the graph's GraphMetadata
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// const { labels, relationshipTypes } = await client.graphMetadata('movies');
+    /// console.log(labels); // ['Movie', 'Person']
+    /// console.log(relationshipTypes); // ['ACTED_IN', 'DIRECTED']
+    /// ```
+    #[napi]
+    pub async fn graph_metadata(&self, graph_name: String) -> Outcome<GraphMetadata> {
+        match self
+            .with_timeout(self.pipeline.graph_metadata(&graph_name))
+            .await
+        {
+            Ok(result) => result
+                .map_err(|e| e.context("Reading graph metadata failed"))
+                .into(),
+            Err(e) => e.into(),
+        }
+    }
+
+    /// Lists all available AI models across all supported providers
+    ///
+    /// This method queries all provider APIs (OpenAI, Anthropic, Gemini, Ollama) and
+    /// returns a combined list of available models with provider prefixes.
+    ///
+    /// # Returns
+    ///
+    /// A promise that resolves to an array of model names with provider prefixes
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// const allModels = await client.listModels();
+    /// console.log('All models:', allModels);
+    /// // Output: ['gpt-4o-mini', 'gpt-4o', 'anthropic:claude-sonnet-4-5', 'gemini:gemini-2.5-pro', ...]
+    /// ```
+    #[napi]
+    pub async fn list_models(&self) -> Outcome<Vec<String>> {
+        if let Err(e) = self.pipeline.ensure_open() {
+            return e.into();
+        }
+        let all_provider_models = match self.pipeline.list_all_models().await {
+            Ok(models) => models,
+            Err(e) => {
+                return ClientError::new(
+                    ErrorCode::ModelDiscovery,
+                    format!("Failed to list models: {}", e),
+                )
+                .into()
+            }
+        };
+
+        let mut all_models = Vec::new();
+        for (adapter_kind, models) in all_provider_models {
+            let prefix = adapter_kind.as_lower_str();
+            for model in models {
+                if adapter_kind == AdapterKind::OpenAI {
                     // OpenAI models don't need a namespace prefix
                     all_models.push(model);
                 } else {
@@ -432,14 +2977,15 @@ impl TextToCypher {
             }
         }
 
-        Ok(all_models)
+        Outcome::from(Ok(all_models))
     }
 
     /// Lists available AI models from a specific provider
     ///
     /// # Arguments
     ///
-    /// * `provider` - Provider name: "openai", "anthropic", "gemini", or "ollama" (case-insensitive)
+    /// * `provider` - Provider name: "openai", "anthropic", "gemini" (or "google"), or "ollama"
+    ///   (case-insensitive)
     ///
     /// # Note
     ///
@@ -460,23 +3006,45 @@ impl TextToCypher {
     /// // Output: ['gpt-4o-mini', 'gpt-4o', 'gpt-4-turbo', ...]
     /// ```
     #[napi]
-    pub async fn list_models_by_provider(&self, provider: String) -> Result<Vec<String>> {
-        let adapter_kind = match provider.to_lowercase().as_str() {
-            "openai" => AdapterKind::OpenAI,
-            "anthropic" => AdapterKind::Anthropic,
-            "gemini" => AdapterKind::Gemini,
-            "ollama" => AdapterKind::Ollama,
-            _ => {
-                return Err(Error::from_reason(format!(
-                    "Unknown provider: '{}'. Supported providers are: openai, anthropic, gemini, ollama",
-                    provider
-                )))
-            }
+    pub async fn list_models_by_provider(&self, provider: String) -> Outcome<Vec<String>> {
+        if let Err(e) = self.pipeline.ensure_open() {
+            return e.into();
+        }
+        let adapter_kind = match provider_adapter(&provider) {
+            Ok(adapter_kind) => adapter_kind,
+            Err(e) => return e.into(),
         };
 
-        match self.client.list_models(adapter_kind).await {
-            Ok(models) => Ok(models),
-            Err(e) => Err(Error::from_reason(format!("Failed to list models: {}", e))),
-        }
+        self.pipeline
+            .list_models(adapter_kind)
+            .await
+            .map_err(|e| {
+                ClientError::new(
+                    ErrorCode::ModelDiscovery,
+                    format!("Failed to list models: {}", e),
+                )
+            })
+            .into()
+    }
+
+    /// Closes the client
+    ///
+    /// Closes the FalkorDB connection pool. Calls already running finish first, and their
+    /// connections close as they finish; every later call rejects with a `CLIENT_CLOSED` error.
+    /// Closing again does nothing.
+    ///
+    /// # Example
+    ///
+    /// ```javascript
+    /// const client = new TextToCypher(options);
+    /// try {
+    ///   console.log(await client.textToCypher('movies', 'Who directed The Matrix?'));
+    /// } finally {
+    ///   await client.close();
+    /// }
+    /// ```
+    #[napi]
+    pub async fn close(&self) {
+        self.pipeline.close().await;
     }
 }
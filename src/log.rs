@@ -0,0 +1,77 @@
+//! Structured log events
+//!
+//! The pipeline reports what it is doing (the model it calls, the query it generated, the rows
+//! that came back, what went wrong) to the client's `onLog` callback, so applications can route
+//! it into their own logger. Nothing is written to stderr.
+//!
+//! Calls that resolve to a response run inside [`with_request_id`], so each event they emit
+//! carries the call's `requestId`, also found on the response and on errors.
+
+use crate::progress::{unix_millis, PipelineStage};
+use napi_derive::napi;
+use std::future::Future;
+
+tokio::task_local! {
+    /// Identifier of the call being served.
+    static REQUEST_ID: String;
+}
+
+/// Runs `future` as the call `request_id`, which its log events and responses carry.
+pub(crate) async fn with_request_id<F: Future>(request_id: String, future: F) -> F::Output {
+    REQUEST_ID.scope(request_id, future).await
+}
+
+/// Runs `f` as the call `request_id`, like [`with_request_id`] does a future.
+pub(crate) fn in_request<R>(request_id: String, f: impl FnOnce() -> R) -> R {
+    REQUEST_ID.sync_scope(request_id, f)
+}
+
+/// Identifier of the call being served, if any.
+pub(crate) fn request_id() -> Option<String> {
+    REQUEST_ID.try_with(String::clone).ok()
+}
+
+/// Severity of a log event
+#[napi(string_enum = "UPPER_SNAKE")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    /// Detail useful when debugging, such as schema discovery finishing
+    Debug,
+    /// Normal progress: the model called, the query generated, the rows returned
+    Info,
+    /// Something went wrong but the call carries on, such as a query sent back for self-healing
+    Warn,
+    /// The call failed
+    Error,
+}
+
+/// A log event from the client
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct LogEvent {
+    pub level: LogLevel,
+    pub message: String,
+    /// The pipeline stage the event comes from; unset for events outside a stage, such as a
+    /// call failing
+    pub stage: Option<PipelineStage>,
+    /// When the event happened, in milliseconds since the Unix epoch (like `Date.now()`)
+    pub timestamp: f64,
+    /// The `requestId` of the call the event comes from; unset for events of methods that do
+    /// not resolve to a response, such as `discoverSchema`
+    pub request_id: Option<String>,
+}
+
+/// Receives the client's log events.
+pub(crate) type LogSink = Box<dyn Fn(LogEvent) + Send + Sync>;
+
+impl LogEvent {
+    pub(crate) fn now(level: LogLevel, stage: Option<PipelineStage>, message: String) -> Self {
+        Self {
+            level,
+            message,
+            stage,
+            timestamp: unix_millis(),
+            request_id: request_id(),
+        }
+    }
+}
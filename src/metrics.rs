@@ -0,0 +1,159 @@
+//! Usage counters
+//!
+//! [`Counters`] tallies a client's pipeline calls, their failures by stage, cache lookups, and the
+//! time the calls took, for `metrics()` to report. Each counter is a relaxed atomic, so recording
+//! never blocks a call; a snapshot taken while calls finish may see one counter already updated
+//! and another not yet.
+
+use crate::error::{ClientError, ErrorCode};
+use crate::progress::PipelineStage;
+use crate::TextToCypherResponse;
+use napi_derive::napi;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+/// Failed pipeline calls by the stage that failed
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct StageFailures {
+    /// Schema discovery failed, the graph did not exist, or the schema was too large
+    pub schema_discovery: f64,
+    /// The model produced no usable query, or a write query under `readOnly`
+    pub query_generation: f64,
+    /// FalkorDB rejected the query or it ran out of time
+    pub query_execution: f64,
+    /// The answer could not be generated, including partial responses that resolved without one
+    pub answer_generation: f64,
+}
+
+/// A snapshot of a client's usage counters, as returned by `metrics()`
+///
+/// Counts cover the calls that run the text-to-cypher pipeline: `textToCypher` and the methods
+/// built on it, each batch question counting once. They start at zero when the client is created.
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    /// Pipeline calls that finished, whether they resolved or rejected
+    pub calls: f64,
+    /// Calls that resolved, including partial responses
+    pub successes: f64,
+    /// Calls that rejected
+    pub failures: f64,
+    /// Failures by stage. Timeouts, aborts, connection errors, and invalid arguments are not tied
+    /// to a stage and count only toward `failures`.
+    pub failures_by_stage: StageFailures,
+    /// Schemas taken from the schema cache; stays at zero unless `schemaCacheTtlMs` is set
+    pub schema_cache_hits: f64,
+    /// Schemas discovered because the schema cache held none for the graph
+    pub schema_cache_misses: f64,
+    /// `textToCypher` responses taken from the result cache; stays at zero unless
+    /// `resultCacheTtlMs` is set
+    pub result_cache_hits: f64,
+    /// `textToCypher` calls that ran the pipeline because the result cache held no response
+    pub result_cache_misses: f64,
+    /// Mean duration of the counted calls in milliseconds; 0 before the first call finishes
+    pub average_latency_ms: f64,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Counters {
+    calls: AtomicU64,
+    successes: AtomicU64,
+    failures: AtomicU64,
+    schema_discovery_failures: AtomicU64,
+    query_generation_failures: AtomicU64,
+    query_execution_failures: AtomicU64,
+    answer_generation_failures: AtomicU64,
+    schema_cache_hits: AtomicU64,
+    schema_cache_misses: AtomicU64,
+    result_cache_hits: AtomicU64,
+    result_cache_misses: AtomicU64,
+    /// Summed duration of every counted call.
+    latency_micros: AtomicU64,
+}
+
+fn bump(counter: &AtomicU64) {
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+fn read(counter: &AtomicU64) -> f64 {
+    counter.load(Ordering::Relaxed) as f64
+}
+
+impl Counters {
+    /// Counts a finished pipeline call that started at `started`.
+    pub(crate) fn record_call(
+        &self,
+        result: &Result<TextToCypherResponse, ClientError>,
+        started: Instant,
+    ) {
+        let elapsed = u64::try_from(started.elapsed().as_micros()).unwrap_or(u64::MAX);
+        self.latency_micros.fetch_add(elapsed, Ordering::Relaxed);
+        bump(&self.calls);
+        match result {
+            Ok(response) => {
+                bump(&self.successes);
+                if response.answer_error.is_some() {
+                    bump(&self.answer_generation_failures);
+                }
+            }
+            Err(e) => {
+                bump(&self.failures);
+                if let Some(counter) = self.stage_failures(*e.code()) {
+                    bump(counter);
+                }
+            }
+        }
+    }
+
+    /// The failure counter of the stage that reports `code`, if a single stage does.
+    fn stage_failures(&self, code: ErrorCode) -> Option<&AtomicU64> {
+        code.stage().map(|stage| match stage {
+            PipelineStage::SchemaDiscovery => &self.schema_discovery_failures,
+            PipelineStage::QueryGeneration => &self.query_generation_failures,
+            PipelineStage::QueryExecution => &self.query_execution_failures,
+            PipelineStage::AnswerGeneration => &self.answer_generation_failures,
+        })
+    }
+
+    pub(crate) fn record_schema_cache(&self, hit: bool) {
+        bump(if hit {
+            &self.schema_cache_hits
+        } else {
+            &self.schema_cache_misses
+        });
+    }
+
+    pub(crate) fn record_result_cache(&self, hit: bool) {
+        bump(if hit {
+            &self.result_cache_hits
+        } else {
+            &self.result_cache_misses
+        });
+    }
+
+    pub(crate) fn snapshot(&self) -> Metrics {
+        let calls = read(&self.calls);
+        let average_latency_ms = if calls > 0.0 {
+            read(&self.latency_micros) / calls / 1000.0
+        } else {
+            0.0
+        };
+        Metrics {
+            calls,
+            successes: read(&self.successes),
+            failures: read(&self.failures),
+            failures_by_stage: StageFailures {
+                schema_discovery: read(&self.schema_discovery_failures),
+                query_generation: read(&self.query_generation_failures),
+                query_execution: read(&self.query_execution_failures),
+                answer_generation: read(&self.answer_generation_failures),
+            },
+            schema_cache_hits: read(&self.schema_cache_hits),
+            schema_cache_misses: read(&self.schema_cache_misses),
+            result_cache_hits: read(&self.result_cache_hits),
+            result_cache_misses: read(&self.result_cache_misses),
+            average_latency_ms,
+        }
+    }
+}
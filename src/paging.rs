@@ -0,0 +1,66 @@
+//! Paging through query results
+//!
+//! `textToCypherPaged` returns the first rows of a generated query with a cursor, and `nextPage`
+//! turns a cursor into the following rows. The cursor is an opaque token holding everything
+//! needed to fetch the next page (the graph, the query, and where the page starts), so no state
+//! is kept between calls and pages are fetched without asking the model again.
+
+use crate::error::{ClientError, ErrorCode};
+use crate::CypherResultSet;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+
+/// A page of rows from a query run by `textToCypherPaged`
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct ResultPage {
+    /// The page's rows
+    pub result_set: CypherResultSet,
+    /// Token for `nextPage` to fetch the rows after this page; unset on the last page
+    pub cursor: Option<String>,
+}
+
+/// Where a page of a query's rows starts, as encoded in a cursor.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PageCursor {
+    pub(crate) graph: String,
+    /// The query as generated, including any `CYPHER` parameter header.
+    pub(crate) query: String,
+    /// Rows before the page.
+    pub(crate) offset: u32,
+    pub(crate) page_size: u32,
+}
+
+impl PageCursor {
+    /// The cursor for the page after this one.
+    pub(crate) fn next(&self) -> Self {
+        Self {
+            graph: self.graph.clone(),
+            query: self.query.clone(),
+            offset: self.offset.saturating_add(self.page_size),
+            page_size: self.page_size,
+        }
+    }
+
+    pub(crate) fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    /// Reads a token produced by [`PageCursor::encode`].
+    pub(crate) fn decode(token: &str) -> Result<Self, ClientError> {
+        URL_SAFE_NO_PAD
+            .decode(token.trim())
+            .ok()
+            .and_then(|json| serde_json::from_slice::<Self>(&json).ok())
+            .filter(|cursor| cursor.page_size > 0)
+            .ok_or_else(|| {
+                ClientError::new(
+                    ErrorCode::InvalidArgument,
+                    "Invalid cursor: expected a cursor returned by textToCypherPaged or nextPage",
+                )
+            })
+    }
+}
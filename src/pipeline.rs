@@ -0,0 +1,2977 @@
+//! Text-to-cypher request pipeline
+//!
+//! Mirrors `text_to_cypher::processor` stage by stage (schema discovery, query generation,
+//! execution with self-healing retries, and answer generation) on top of the library's public
+//! `core` building blocks, so the bindings can hook into individual stages.
+
+use crate::answer_format::{self, AnswerSchema};
+use crate::cypher;
+use crate::error::{ClientError, ErrorCode};
+use crate::graph_hints::GraphHints;
+use crate::log::{self, LogEvent, LogLevel, LogSink};
+use crate::metrics::Counters;
+use crate::paging::{PageCursor, ResultPage};
+use crate::progress::{PipelineStage, ProgressEvent, StagePhase, StageTimings};
+use crate::prompt_cache::{self, CacheUsage};
+use crate::prompt_template::{PromptTemplate, PromptValues};
+use crate::redaction::Redaction;
+use crate::result_cache::ResultCache;
+use crate::result_set::{CypherResultSet, ResultFormat, ResultGroup};
+use crate::retry::{FailureCategory, ProviderError, Retry};
+use crate::schema::{prune_schema, sample_row};
+use crate::schema_cache::SchemaCache;
+use crate::structured_output;
+use crate::{CypherExample, GraphMetadata};
+use falkordb::{
+    ConnectionStrategy, FalkorAsyncClient, FalkorClientBuilder, FalkorConnectionInfo,
+    FalkorDBError, FalkorValue, RetryPolicy,
+};
+use futures::future::BoxFuture;
+use futures::StreamExt;
+use genai::chat::{CacheControl, ChatOptions, ChatStreamEvent};
+use genai::resolver::{AuthData, AuthResolver, Endpoint, ServiceTargetResolver};
+use genai::{ModelIden, ServiceTarget, WebConfig};
+use redis::aio::MultiplexedConnection;
+use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::num::NonZeroU8;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use text_to_cypher::core::{
+    clean_generated_cypher_response, discover_udfs, list_adapter_models_with_endpoint,
+    list_all_models_with_endpoint, parse_answer_confidence,
+};
+use text_to_cypher::formatter::format_query_records;
+use text_to_cypher::schema::discovery::Schema;
+use text_to_cypher::skills;
+use text_to_cypher::template::TemplateEngine;
+use text_to_cypher::validator::CypherValidator;
+use text_to_cypher::{
+    AdapterKind, ChatMessage, ChatRequest, ChatRole, SkillCatalog, TextToCypherResponse,
+    TokenUsage, UdfError, UdfSource,
+};
+
+pub(crate) type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A model reply that holds no Cypher query, such as an apology or a clarifying question.
+#[derive(Debug)]
+pub(crate) struct NoQuery {
+    /// The reply, unless it was empty.
+    reply: Option<String>,
+}
+
+impl fmt::Display for NoQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("The model replied without a Cypher query")
+    }
+}
+
+impl std::error::Error for NoQuery {}
+
+/// A generated query that fails validation, e.g. on unbalanced parentheses.
+#[derive(Debug)]
+pub(crate) struct InvalidQuery {
+    errors: Vec<String>,
+}
+
+impl fmt::Display for InvalidQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Query validation failed: {}", self.errors.join("; "))
+    }
+}
+
+impl std::error::Error for InvalidQuery {}
+
+/// Receives each partial chunk of generated text (the answer, or the query) as it is generated.
+pub(crate) type ChunkSink<'a> = &'a (dyn Fn(String) + Send + Sync);
+
+/// Receives an event as each pipeline stage starts and completes.
+pub(crate) type ProgressSink<'a> = &'a (dyn Fn(ProgressEvent) + Send + Sync);
+
+/// Decides whether a result row, given as an object keyed by column name, is kept.
+pub(crate) type RowFilter =
+    Box<dyn Fn(Map<String, Value>) -> BoxFuture<'static, Result<bool, String>> + Send + Sync>;
+
+/// Trailing marker the answer prompt asks the model to emit (see `parse_answer_confidence`).
+const CONFIDENCE_MARKER: &str = "CONFIDENCE:";
+
+/// System prompt of the request that has the model rate a generated query (see `rate_query`).
+const QUERY_RATING_PROMPT: &str =
+    "You review Cypher queries generated for questions about a graph \
+    database. Given the graph schema, a question, and a Cypher query, rate how likely the query is \
+    to answer the question correctly against that schema, from 0 (certainly wrong) to 1 (certainly \
+    right). Reply with the number only.";
+
+/// System prompt of the request that has the model describe a graph schema (see
+/// `describe_schema`).
+const SCHEMA_DESCRIPTION_PROMPT: &str =
+    "You explain graph databases to people seeing them for the first \
+    time. Given a graph schema as JSON, describe the graph in a few short paragraphs of plain \
+    prose: the kinds of nodes it holds, how they are connected, and the properties that look most \
+    useful for asking questions. Name node labels and relationship types exactly as the schema \
+    writes them. Do not list every property, and do not write Cypher.";
+
+/// Follow-up sent with `no_query_retry` after a reply that held no query (see `generate_query`).
+const NO_QUERY_RETRY_PROMPT: &str =
+    "Reply with a single Cypher query that answers the question above, \
+    and nothing else: no explanation, apology, or clarifying question. If the question is \
+    ambiguous, write the query for its most likely meaning.";
+
+/// Connections in the FalkorDB pool unless `poolSize` is set; the library's default count.
+const DEFAULT_POOL_SIZE: NonZeroU8 = NonZeroU8::new(8).unwrap();
+
+/// How the client identifies itself unless `userAgent` is set.
+pub(crate) const DEFAULT_USER_AGENT: &str =
+    concat!("text-to-cypher-node/", env!("CARGO_PKG_VERSION"));
+
+/// Per-call settings for [`Pipeline::run`].
+#[derive(Default)]
+pub(crate) struct RunOptions<'a> {
+    /// Stop after query generation instead of executing the query and answering.
+    pub(crate) cypher_only: bool,
+    /// With `cypher_only`, also have FalkorDB plan the generated query and return the plan.
+    pub(crate) explain: bool,
+    /// With `cypher_only` and a supplied `schema`, generate without a graph: the graph name is
+    /// not checked and FalkorDB is never contacted, not even to discover UDFs.
+    pub(crate) detached: bool,
+    /// Stop after executing the query instead of generating the answer.
+    pub(crate) skip_answer: bool,
+    /// With `skip_answer`, return a cursor to the rows past `max_rows`.
+    pub(crate) paged: bool,
+    /// Pre-discovered schema JSON to use instead of discovering it from the graph.
+    pub(crate) schema: Option<String>,
+    /// Receives the answer as it is streamed.
+    pub(crate) on_answer_chunk: Option<ChunkSink<'a>>,
+    /// Receives the generated query as it is streamed.
+    pub(crate) on_query_chunk: Option<ChunkSink<'a>>,
+    /// Row cap for this call, overriding [`Pipeline::max_rows`].
+    pub(crate) max_rows: Option<u32>,
+    /// Result columns the answer is generated from; `None` uses them all.
+    pub(crate) answer_columns: Option<Vec<String>>,
+    /// How the response's `cypher_result` is rendered.
+    pub(crate) result_format: ResultFormat,
+    /// Result column whose values the rows are counted by.
+    pub(crate) group_by: Option<String>,
+    /// Model for this call, overriding [`Pipeline::model`].
+    pub(crate) model: Option<String>,
+    /// API key for this call's LLM requests, overriding [`Pipeline::api_key`].
+    pub(crate) api_key: Option<String>,
+    /// Receives progress events.
+    pub(crate) on_progress: Option<ProgressSink<'a>>,
+    /// Identifier tagged onto the queries this call runs; `None` generates one.
+    pub(crate) trace_id: Option<String>,
+}
+
+/// The LLM a run talks to.
+struct Llm<'a> {
+    client: genai::Client,
+    /// Model the calls are sent to: the call's override or the client's model.
+    model: &'a str,
+    /// Generate queries as structured output (see [`structured_output`]).
+    structured: bool,
+    /// Receives the generated query as it is streamed.
+    query_chunks: Option<ChunkSink<'a>>,
+    /// Mark the query-generation system prompt cacheable (see [`prompt_cache`]).
+    cache_prompt: bool,
+    /// Cache reads and writes of the calls, shared with the run's details.
+    cache_usage: Arc<CacheUsage>,
+}
+
+/// Rows of an executed query, as kept after applying the row cap.
+struct QueryOutput {
+    /// Rows formatted for the answer prompt.
+    text: String,
+    result_set: CypherResultSet,
+    /// The rows as FalkorDB returned them, for formatting a subset of their columns.
+    records: Vec<Vec<FalkorValue>>,
+    /// More rows existed than the cap allowed.
+    truncated: bool,
+}
+
+/// Where LLM requests get their API key.
+#[derive(Debug, Clone)]
+pub(crate) enum ApiKey {
+    /// The `apiKey` the client was created with.
+    Fixed(String),
+    /// The name of the environment variable (`apiKeyEnv`) holding the key, read on every call so
+    /// a rotated key is picked up by the existing client.
+    Env(String),
+}
+
+impl ApiKey {
+    /// The key to send with a call starting now.
+    pub(crate) fn current(&self) -> Result<String, ClientError> {
+        match self {
+            Self::Fixed(key) => Ok(key.clone()),
+            Self::Env(name) => std::env::var(name).map_err(|_| {
+                ClientError::new(
+                    ErrorCode::InvalidArgument,
+                    format!("The API key environment variable '{name}' (apiKeyEnv) is not set"),
+                )
+            }),
+        }
+    }
+}
+
+/// Client-level configuration shared by every request.
+pub(crate) struct Pipeline {
+    pub(crate) model: String,
+    /// Model for answer generation, when it differs from the query model.
+    pub(crate) answer_model: Option<String>,
+    pub(crate) api_key: ApiKey,
+    pub(crate) falkordb_connection: String,
+    /// Connection strings tried in order when `falkordb_connection` cannot be reached.
+    pub(crate) falkordb_fallbacks: Vec<String>,
+    pub(crate) llm_endpoint: Option<String>,
+    /// Headers added to every LLM request the provider integration does not set itself.
+    pub(crate) extra_headers: HeaderMap,
+    /// Sent as the `User-Agent` of LLM requests and as the library name of FalkorDB connections.
+    pub(crate) user_agent: String,
+    pub(crate) skill_catalog: Option<SkillCatalog>,
+    pub(crate) udf_source: UdfSource,
+    /// Default options (sampling parameters, ...) applied to every LLM call.
+    pub(crate) chat_options: ChatOptions,
+    /// Token cap for answer generation, replacing the one in `chat_options`.
+    pub(crate) answer_max_tokens: Option<u32>,
+    /// Language the answer is written in, whatever the question's; `None` leaves it to the model.
+    pub(crate) answer_language: Option<String>,
+    /// Schema answers are generated as structured output in (see [`answer_format`]).
+    pub(crate) answer_schema: Option<AnswerSchema>,
+    /// Refuse to execute generated queries that contain write clauses.
+    pub(crate) read_only: bool,
+    /// Report the query-generation prompt in responses.
+    pub(crate) debug: bool,
+    /// Refuse generated queries that name labels or relationship types missing from the schema.
+    pub(crate) strict: bool,
+    /// Node labels of the schema kept in the query-generation prompt; `None` keeps them all.
+    pub(crate) schema_top_k: Option<usize>,
+    /// Largest schema, in bytes, sent to the model; `None` sends any size.
+    pub(crate) max_schema_bytes: Option<usize>,
+    /// Nodes per label whose properties schema discovery adds to the schema; `None` adds none.
+    pub(crate) sample_rows: Option<u32>,
+    /// Properties whose values are kept out of the answer prompt and discovered schemas.
+    pub(crate) redaction: Redaction,
+    /// Never discover schemas: calls must supply the schema, which is used as given.
+    pub(crate) skip_discovery: bool,
+    /// Have the model rate each generated query in an extra call.
+    pub(crate) with_confidence: bool,
+    /// Ask providers that support it for the query as structured output.
+    pub(crate) structured_output: bool,
+    /// Mark the schema-bearing system prompt cacheable for providers that need the marker.
+    pub(crate) prompt_caching: bool,
+    /// Times a query FalkorDB rejects is regenerated with the error as feedback; 0 gives up on
+    /// the first failure.
+    pub(crate) self_heal_attempts: u32,
+    /// Ask once more, for a query only, when the model replies without one.
+    pub(crate) no_query_retry: bool,
+    /// Cypher dialect generated queries must keep to; `None` leaves it to the built-in prompt.
+    pub(crate) cypher_dialect: Option<String>,
+    /// Extra instructions sent as a system message with every query-generation request.
+    pub(crate) system_prompt: Option<String>,
+    /// Few-shot examples sent with every query-generation request.
+    pub(crate) examples: Vec<CypherExample>,
+    /// Replaces the library's query-generation prompt; `None` uses the built-in one.
+    pub(crate) prompt_template: Option<PromptTemplate>,
+    /// Retries applied to LLM calls and query execution that fail transiently.
+    pub(crate) retry: Retry,
+    /// Retries applied to opening a FalkorDB connection, e.g. while the server is still starting.
+    pub(crate) connect_retry: Retry,
+    /// Server-side limit on how long FalkorDB may run a generated query; `None` leaves it to the
+    /// server's configuration.
+    pub(crate) query_timeout_ms: Option<u32>,
+    /// Rows kept from an executed query unless a call sets its own cap; `None` keeps all.
+    pub(crate) max_rows: Option<u32>,
+    /// `LIMIT` appended to generated read queries whose final `RETURN` has none.
+    pub(crate) enforce_limit: Option<u32>,
+    /// Notes about particular graphs added to their query-generation prompts.
+    pub(crate) graph_hints: GraphHints,
+    /// Reuses discovered schemas for a while; `None` discovers on every call.
+    pub(crate) schema_cache: Option<SchemaCache>,
+    /// Reuses successful `textToCypher` responses for a while; `None` runs every call.
+    pub(crate) result_cache: Option<ResultCache>,
+    /// Connections in the FalkorDB pool shared by every call.
+    pub(crate) pool_size: NonZeroU8,
+    /// How long the pool may go unused before it is replaced on next use; `None` keeps it.
+    pub(crate) connection_idle_timeout: Option<Duration>,
+    /// Idle time before TCP keepalive probes start on FalkorDB connections; `None` sends none.
+    pub(crate) connection_keepalive: Option<Duration>,
+    /// Receives log events; `None` drops them.
+    pub(crate) on_log: Option<LogSink>,
+    /// Drops result rows before they are returned or answered from; `None` keeps them all.
+    pub(crate) row_filter: Option<RowFilter>,
+    /// Usage counters reported by `metrics()`.
+    pub(crate) metrics: Counters,
+    /// The pooled FalkorDB client, once opened, with when it was last handed out.
+    falkordb_client: tokio::sync::Mutex<Option<(FalkorAsyncClient, Instant)>>,
+    /// Index into [`Pipeline::endpoints`] of the endpoint the pool was last opened on.
+    endpoint: AtomicUsize,
+    /// Set by [`Pipeline::close`]; no FalkorDB connection is opened after it.
+    closed: AtomicBool,
+}
+
+impl Pipeline {
+    /// Sends a log event to `on_log`, building the message only when there is a receiver.
+    pub(crate) fn log(
+        &self,
+        level: LogLevel,
+        stage: Option<PipelineStage>,
+        message: impl FnOnce() -> String,
+    ) {
+        if let Some(on_log) = &self.on_log {
+            on_log(LogEvent::now(level, stage, message()));
+        }
+    }
+
+    /// Creates a pipeline with the built-in FalkorDB skills and no UDF context.
+    pub(crate) fn new(model: String, api_key: ApiKey, falkordb_connection: String) -> Self {
+        Self {
+            model,
+            answer_model: None,
+            api_key,
+            falkordb_connection,
+            falkordb_fallbacks: Vec::new(),
+            llm_endpoint: None,
+            extra_headers: HeaderMap::new(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            skill_catalog: Some(SkillCatalog::builtin()),
+            udf_source: UdfSource::Off,
+            chat_options: ChatOptions::default(),
+            answer_max_tokens: None,
+            answer_language: None,
+            answer_schema: None,
+            read_only: false,
+            debug: false,
+            strict: false,
+            schema_top_k: None,
+            max_schema_bytes: None,
+            sample_rows: None,
+            redaction: Redaction::default(),
+            skip_discovery: false,
+            with_confidence: false,
+            structured_output: false,
+            prompt_caching: false,
+            self_heal_attempts: 1,
+            no_query_retry: false,
+            system_prompt: None,
+            examples: Vec::new(),
+            prompt_template: None,
+            cypher_dialect: None,
+            retry: Retry::default(),
+            connect_retry: Retry::default(),
+            query_timeout_ms: None,
+            max_rows: None,
+            enforce_limit: None,
+            graph_hints: GraphHints::default(),
+            schema_cache: None,
+            result_cache: None,
+            pool_size: DEFAULT_POOL_SIZE,
+            connection_idle_timeout: None,
+            connection_keepalive: None,
+            on_log: None,
+            row_filter: None,
+            metrics: Counters::default(),
+            falkordb_client: tokio::sync::Mutex::new(None),
+            endpoint: AtomicUsize::new(0),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    /// Builds the LLM client the way `text_to_cypher::core::create_genai_client_with_endpoint`
+    /// does, with `chat_options` installed as the client-wide defaults and `extra_headers` and
+    /// `user_agent` as the HTTP client's default headers. An unset `apiKeyEnv` variable counts as
+    /// no key, as model listing works without one.
+    fn genai_client(&self) -> genai::Client {
+        let api_key = self.api_key.current().unwrap_or_default();
+        self.genai_client_with_options(self.chat_options.clone(), &api_key)
+    }
+
+    /// Like [`Pipeline::genai_client`], with `chat_options` and `api_key` in place of the
+    /// pipeline's.
+    fn genai_client_with_options(&self, chat_options: ChatOptions, api_key: &str) -> genai::Client {
+        let key = api_key.to_string();
+        let auth_resolver = AuthResolver::from_resolver_fn(
+            move |_: ModelIden| -> Result<Option<AuthData>, genai::resolver::Error> {
+                Ok(Some(AuthData::from_single(key.clone())))
+            },
+        );
+        let mut builder = genai::Client::builder()
+            .with_auth_resolver(auth_resolver)
+            .with_chat_options(chat_options);
+
+        // Default headers fill in what a request lacks, so the adapter's own headers always win.
+        let mut headers = self.extra_headers.clone();
+        if let Ok(user_agent) = HeaderValue::from_str(&self.user_agent) {
+            headers.insert(USER_AGENT, user_agent);
+        }
+        builder = builder.with_web_config(WebConfig::default().with_default_headers(headers));
+
+        if let Some(endpoint) = self
+            .llm_endpoint
+            .as_deref()
+            .and_then(normalize_llm_endpoint)
+        {
+            let service_target_resolver = ServiceTargetResolver::from_resolver_fn(
+                move |mut service_target: ServiceTarget| -> Result<ServiceTarget, genai::resolver::Error> {
+                    service_target.endpoint = Endpoint::from_owned(endpoint.clone());
+                    Ok(service_target)
+                },
+            );
+            builder = builder.with_service_target_resolver(service_target_resolver);
+        }
+
+        builder.build()
+    }
+
+    /// The FalkorDB connection strings, in the order they are tried.
+    fn endpoints(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.falkordb_connection.as_str())
+            .chain(self.falkordb_fallbacks.iter().map(String::as_str))
+    }
+
+    /// The endpoint the pool was last opened on, or `falkordb_connection` before that.
+    fn current_endpoint(&self) -> &str {
+        self.endpoints()
+            .nth(self.endpoint.load(Ordering::Acquire))
+            .unwrap_or(&self.falkordb_connection)
+    }
+
+    /// Runs `open` on the endpoint at index `first`, then on the others in order until one
+    /// succeeds, returning its result with the index of its endpoint. Fails with the last
+    /// endpoint's error once every endpoint has failed.
+    async fn first_reachable<'a, T, F, Fut>(
+        &'a self,
+        first: usize,
+        open: F,
+    ) -> Result<(usize, T), ClientError>
+    where
+        F: Fn(&'a str) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let endpoints: Vec<&str> = self.endpoints().collect();
+        let mut index = first;
+        for next in (0..endpoints.len()).filter(|&other| other != first) {
+            match open(endpoints[index]).await {
+                Ok(value) => return Ok((index, value)),
+                Err(e) => self.log(LogLevel::Warn, None, || {
+                    format!(
+                        "FalkorDB endpoint {} of {} is unreachable, trying the next one: {e}",
+                        index + 1,
+                        endpoints.len()
+                    )
+                }),
+            }
+            index = next;
+        }
+
+        match open(endpoints[index]).await {
+            Ok(value) => Ok((index, value)),
+            Err(e) if endpoints.len() > 1 => Err(e.suffix(&format!(
+                " (all {} FalkorDB endpoints failed)",
+                endpoints.len()
+            ))),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Parses `endpoint`, with `user_agent` as the library name connections report to the server
+    /// (`CLIENT SETINFO LIB-NAME`, shown by `CLIENT LIST`), whitespace replaced by `_`.
+    fn connection_info(&self, endpoint: &str) -> Result<FalkorConnectionInfo, ClientError> {
+        let FalkorConnectionInfo::Redis(info) = endpoint.try_into().map_err(|e| {
+            ClientError::new(
+                ErrorCode::Connection,
+                format!("Invalid connection info: {e}"),
+            )
+        })?;
+        let lib_name = self
+            .user_agent
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join("_");
+        let settings = info
+            .redis_settings()
+            .clone()
+            .set_lib_name(lib_name, env!("CARGO_PKG_VERSION"));
+        Ok(FalkorConnectionInfo::Redis(
+            info.set_redis_settings(settings),
+        ))
+    }
+
+    /// The shared FalkorDB client, opening its connection pool on first use. A failed attempt is
+    /// not cached, so the next call tries again; once the pipeline is closed, every call fails.
+    ///
+    /// The pool is opened on the first of [`Pipeline::endpoints`] that accepts a connection, and
+    /// stays on it. When none does, every endpoint is tried again after a backoff, as many times as
+    /// `connect_retry` allows.
+    ///
+    /// Each command borrows a pooled connection and returns it when done. A command that finds
+    /// its connection broken fails, and the client swaps the connection for a fresh one; reads
+    /// are then retried, enough times to get past every connection of the pool dying at once
+    /// (e.g. a server restart). Like the library, the client retries only idempotent reads.
+    ///
+    /// A pool left unused for `connection_idle_timeout` is not trusted: it is dropped and a fresh
+    /// one opened in its place, as the server may have closed its connections meanwhile, which
+    /// a write would otherwise only find out by failing.
+    async fn connect(&self) -> Result<FalkorAsyncClient, ClientError> {
+        let mut falkordb_client = self.falkordb_client.lock().await;
+        self.ensure_open()?;
+        if let Some((client, last_used)) = &mut *falkordb_client {
+            let idle = last_used.elapsed();
+            if self
+                .connection_idle_timeout
+                .is_none_or(|timeout| idle < timeout)
+            {
+                *last_used = Instant::now();
+                return Ok(client.clone());
+            }
+            self.log(LogLevel::Debug, None, || {
+                format!(
+                    "FalkorDB connections unused for {}ms, reconnecting",
+                    idle.as_millis()
+                )
+            });
+        }
+
+        let (index, client) = self
+            .retry_connect(|| self.first_reachable(0, |endpoint| self.open_pool(endpoint)))
+            .await?;
+        self.endpoint.store(index, Ordering::Release);
+        Ok(falkordb_client.insert((client, Instant::now())).0.clone())
+    }
+
+    /// Runs `open` until it connects or `connect_retry` runs out. Only a connection that was
+    /// refused, dropped, or timed out is retried: a malformed connection string (every endpoint
+    /// is parsed first), a rejected password, or a failed TLS handshake would only fail the same
+    /// way again. The error given up on has no category left, so that the retries of the call
+    /// that needed the connection do not start over on it.
+    async fn retry_connect<T, F, Fut>(&self, open: F) -> Result<T, ClientError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        for endpoint in self.endpoints() {
+            self.connection_info(endpoint)?;
+        }
+        self.connect_retry
+            .run(open)
+            .await
+            .map_err(|e| e.with_failure_category(None))
+    }
+
+    /// Opens a FalkorDB connection pool on `endpoint`.
+    async fn open_pool(&self, endpoint: &str) -> Result<FalkorAsyncClient, ClientError> {
+        let mut builder = FalkorClientBuilder::new_async()
+            .with_connection_info(self.connection_info(endpoint)?)
+            .with_connection_strategy(ConnectionStrategy::Pooled {
+                size: self.pool_size,
+            })
+            .with_retry_policy(RetryPolicy::read_only().max_attempts(self.pool_attempts()));
+        if let Some(idle) = self.connection_keepalive {
+            builder = builder.with_tcp_keepalive(idle);
+        }
+        match builder.build().await {
+            Ok(client) => Ok(client),
+            Err(e) => {
+                // The client reports most failures to connect as text; a plain connection to the
+                // same endpoint tells a server that is not up from one refusing the client.
+                let category = match &e {
+                    FalkorDBError::RedisError(_) => self
+                        .open_redis_connection(endpoint)
+                        .await
+                        .err()
+                        .and_then(|probe| probe.failure_category()),
+                    e => FailureCategory::of_falkordb(e),
+                };
+                Err(ClientError::new(
+                    ErrorCode::Connection,
+                    format!("Failed to build client: {e}"),
+                )
+                .with_failure_category(category))
+            }
+        }
+    }
+
+    /// Fails with `CLIENT_CLOSED` once [`Pipeline::close`] has been called.
+    pub(crate) fn ensure_open(&self) -> Result<(), ClientError> {
+        if self.closed.load(Ordering::Acquire) {
+            return Err(ClientError::new(
+                ErrorCode::ClientClosed,
+                "Client is closed",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Closes the FalkorDB connection pool and refuses further work. Commands already running
+    /// finish on their borrowed connections, which close as they are returned.
+    pub(crate) async fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.falkordb_client.lock().await.take();
+    }
+
+    /// Discovers the schema of `graph_name` as a JSON string, or takes it from the schema cache.
+    pub(crate) async fn discover_schema(&self, graph_name: &str) -> Result<String, ClientError> {
+        check_graph_name(graph_name)?;
+        if self.skip_discovery {
+            return Err(discovery_disabled());
+        }
+        let Some(cache) = &self.schema_cache else {
+            return self.discover_fresh_schema(graph_name).await;
+        };
+        let cached = cache.get(graph_name);
+        self.metrics.record_schema_cache(cached.is_some());
+        if let Some(schema) = cached {
+            return Ok(schema);
+        }
+        let schema = self.discover_fresh_schema(graph_name).await?;
+        cache.insert(graph_name, &schema);
+        Ok(schema)
+    }
+
+    /// Has the model describe the schema of `graph_name` in prose, retrying transient model
+    /// failures. The schema comes from the schema cache when it holds one, and the description is
+    /// then cached with it.
+    pub(crate) async fn describe_schema(&self, graph_name: &str) -> Result<String, ClientError> {
+        let schema = self.discover_schema(graph_name).await?;
+        if let Some(max_bytes) = self.max_schema_bytes.filter(|&max| schema.len() > max) {
+            return Err(schema_too_large(
+                &schema,
+                max_bytes,
+                "describeSchema always sends the whole schema",
+            ));
+        }
+        if let Some(description) = self
+            .schema_cache
+            .as_ref()
+            .and_then(|cache| cache.description(graph_name, &schema))
+        {
+            return Ok(description);
+        }
+
+        let client =
+            self.genai_client_with_options(self.chat_options.clone(), &self.api_key.current()?);
+        let model = self.answer_model.as_deref().unwrap_or(&self.model);
+        self.log(LogLevel::Info, None, || {
+            format!("Describing the schema of graph '{graph_name}' with model {model}")
+        });
+        let request = genai::chat::ChatRequest::from_messages(vec![
+            genai::chat::ChatMessage::system(SCHEMA_DESCRIPTION_PROMPT),
+            genai::chat::ChatMessage::user(format!("Graph schema:\n{schema}")),
+        ]);
+        let response = self
+            .retry
+            .run(|| async {
+                client
+                    .exec_chat(model, request.clone(), None)
+                    .await
+                    .map_err(|e| ProviderError::boxed(e.to_string(), &e))
+            })
+            .await
+            .map_err(|e| {
+                ClientError::new(
+                    ErrorCode::AnswerGeneration,
+                    format!("Failed to describe schema: {e}"),
+                )
+            })?;
+        let description = response.first_text().unwrap_or_default().trim().to_string();
+        if description.is_empty() {
+            return Err(ClientError::new(
+                ErrorCode::AnswerGeneration,
+                "The model returned an empty schema description",
+            ));
+        }
+
+        if let Some(cache) = &self.schema_cache {
+            cache.set_description(graph_name, &schema, &description);
+        }
+        Ok(description)
+    }
+
+    /// Estimates the tokens the query-generation prompt for `question` against `graph_name` takes,
+    /// built as [`Pipeline::run`] would build it: the schema from the cache or discovery, pruned
+    /// and size-checked, with the UDF context, skills, examples, system prompt, and graph hints.
+    /// Nothing is sent to the model.
+    pub(crate) async fn estimate_tokens(
+        &self,
+        graph_name: &str,
+        question: &str,
+    ) -> Result<u32, ClientError> {
+        check_graph_name(graph_name)?;
+        let mut llm = Llm {
+            client: self.genai_client(),
+            model: &self.model,
+            structured: false,
+            query_chunks: None,
+            cache_prompt: false,
+            cache_usage: Arc::default(),
+        };
+        let adapter_kind = match llm.client.resolve_service_target(llm.model).await {
+            Ok(target) => target.model.adapter_kind,
+            Err(e) => {
+                return Err(ClientError::new(
+                    ErrorCode::QueryGeneration,
+                    format!("Failed to resolve service target: {e}"),
+                ))
+            }
+        };
+        llm.structured = self.structured_output && structured_output::supported(adapter_kind);
+        llm.cache_prompt = self.prompt_caching && prompt_cache::supported(adapter_kind);
+
+        let schema = self.discover_schema(graph_name).await?;
+        let pruned = self.fit_schema(&schema, question)?;
+        let prompt_schema = pruned
+            .as_ref()
+            .map_or(schema.as_str(), |(pruned, _)| pruned);
+        let udfs = self.resolve_udfs().await;
+        let generation_request = self.generation_request(
+            graph_name,
+            &ChatRequest {
+                messages: vec![ChatMessage {
+                    role: ChatRole::User,
+                    content: question.to_string(),
+                }],
+            },
+        );
+        let skill_tools = self.reads_skills_with_tools(&llm);
+        let (system_prompt, messages) =
+            self.generation_messages(&llm, &generation_request, prompt_schema, &udfs, skill_tools);
+        let texts = system_prompt
+            .iter()
+            .map(String::as_str)
+            .chain(messages.iter().map(|message| message.content.as_str()));
+        Ok(approximate_tokens(adapter_kind, texts))
+    }
+
+    /// Discovers the schema of `graph_name` from FalkorDB, with each label's `samples` when
+    /// `sample_rows` is set, and the redacted properties' values removed.
+    async fn discover_fresh_schema(&self, graph_name: &str) -> Result<String, ClientError> {
+        let client = self.connect().await?;
+        let mut graph = client.select_graph(graph_name);
+        let schema = match Schema::discover_from_graph(&mut graph, 100).await {
+            Ok(schema) => schema,
+            Err(e) => {
+                let error = discovery_error("Failed to discover schema", e);
+                return Err(self.missing_graph_or(graph_name, error).await);
+            }
+        };
+        let serialization_failed = |e: serde_json::Error| {
+            ClientError::new(
+                ErrorCode::SchemaDiscovery,
+                format!("Failed to serialize schema: {e}"),
+            )
+        };
+        if self.sample_rows.is_none() && self.redaction.is_empty() {
+            return serde_json::to_string(&schema).map_err(serialization_failed);
+        }
+
+        let mut schema = serde_json::to_value(&schema).map_err(serialization_failed)?;
+        let entities = schema.get_mut("entities").and_then(Value::as_array_mut);
+        if let (Some(rows), Some(entities)) = (self.sample_rows, entities) {
+            let samples = futures::future::join_all(entities.iter().map(|entity| {
+                let label = entity.get("label").and_then(Value::as_str);
+                self.sample_nodes(&client, graph_name, label.unwrap_or_default(), rows)
+            }))
+            .await;
+            for (entity, samples) in entities.iter_mut().zip(samples) {
+                if !samples.is_empty() {
+                    entity["samples"] = Value::Array(samples);
+                }
+            }
+        }
+        self.redaction.schema(&mut schema);
+        Ok(schema.to_string())
+    }
+
+    /// The properties of up to `rows` nodes labelled `label`, cut down by [`sample_row`]. Like
+    /// upstream's collection of example values, a failure only loses the samples, with a warning.
+    async fn sample_nodes(
+        &self,
+        client: &FalkorAsyncClient,
+        graph_name: &str,
+        label: &str,
+        rows: u32,
+    ) -> Vec<Value> {
+        let query = format!(
+            "MATCH (n:`{}`) RETURN properties(n) LIMIT {rows}",
+            label.replace('`', "``")
+        );
+        let mut graph = client.select_graph(graph_name);
+        match graph.ro_query(&query).execute().await {
+            Ok(result) => result
+                .data
+                .into_values_lossy()
+                .filter_map(|row| match row.into_iter().next() {
+                    Some(FalkorValue::Map(properties)) => {
+                        Some(Value::Object(sample_row(&properties)))
+                    }
+                    _ => None,
+                })
+                .collect(),
+            Err(e) => {
+                self.log(LogLevel::Warn, None, || {
+                    format!("Failed to sample nodes labelled '{label}': {e}")
+                });
+                Vec::new()
+            }
+        }
+    }
+
+    /// Has FalkorDB plan `query` against `graph_name` without running it, returning the error
+    /// FalkorDB reports when the query does not parse (or otherwise cannot be planned).
+    pub(crate) async fn validate_query(
+        &self,
+        graph_name: &str,
+        query: &str,
+    ) -> Result<Option<String>, ClientError> {
+        Ok(self.explain_query(graph_name, query).await?.err())
+    }
+
+    /// Has FalkorDB plan `query` against `graph_name` without running it. Resolves to the plan's
+    /// operations, one per line, or to the error FalkorDB reports when it cannot plan the query.
+    ///
+    /// `GRAPH.EXPLAIN` goes over a plain redis connection: the FalkorDB client's `explain` future
+    /// is not `Send`, and it replaces FalkorDB's error reply with a plan parsing error.
+    pub(crate) async fn explain_query(
+        &self,
+        graph_name: &str,
+        query: &str,
+    ) -> Result<Result<String, String>, ClientError> {
+        check_graph_name(graph_name)?;
+        let mut connection = self.redis_connection().await?;
+        let explained = redis::cmd("GRAPH.EXPLAIN")
+            .arg(graph_name)
+            .arg(query)
+            .query_async::<Vec<String>>(&mut connection)
+            .await;
+
+        match explained {
+            Ok(plan) => Ok(Ok(plan.join("\n"))),
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    redis::ErrorKind::Server(_) | redis::ErrorKind::Extension
+                ) =>
+            {
+                Ok(Err(server_message(&e)))
+            }
+            Err(e) => Err(ClientError::new(
+                ErrorCode::Connection,
+                format!("Failed to explain query: {e}"),
+            )),
+        }
+    }
+
+    /// Opens the FalkorDB connection pool, with all of its connections, unless it is open already.
+    pub(crate) async fn open_pool_early(&self) -> Result<(), ClientError> {
+        self.connect().await.map(drop)
+    }
+
+    /// Checks that FalkorDB answers a `PING` on the configured connection.
+    pub(crate) async fn ping(&self) -> Result<(), ClientError> {
+        let mut connection = self.redis_connection().await?;
+        redis::cmd("PING")
+            .query_async::<()>(&mut connection)
+            .await
+            .map_err(|e| ClientError::new(ErrorCode::Connection, format!("No reply to PING: {e}")))
+    }
+
+    /// Opens a plain redis connection for commands the FalkorDB client does not cover, with the
+    /// same credentials, database and TLS settings. Tries the endpoint the pool is on first, then
+    /// the others in order, retrying them as `connect_retry` allows.
+    async fn redis_connection(&self) -> Result<MultiplexedConnection, ClientError> {
+        self.ensure_open()?;
+        let current = self.endpoint.load(Ordering::Acquire);
+        let (_, connection) = self
+            .retry_connect(|| {
+                self.first_reachable(current, |endpoint| self.open_redis_connection(endpoint))
+            })
+            .await?;
+        Ok(connection)
+    }
+
+    /// Opens a plain redis connection to `endpoint`.
+    async fn open_redis_connection(
+        &self,
+        endpoint: &str,
+    ) -> Result<MultiplexedConnection, ClientError> {
+        let FalkorConnectionInfo::Redis(connection_info) = self.connection_info(endpoint)?;
+        let connection_error = |e: redis::RedisError| {
+            ClientError::new(ErrorCode::Connection, format!("Failed to connect: {e}"))
+                .with_failure_category(FailureCategory::of_redis(&e))
+        };
+
+        redis::Client::open(connection_info)
+            .map_err(connection_error)?
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(connection_error)
+    }
+
+    /// `error`, or the `GraphNotFound` error when FalkorDB rejected a query because `graph_name`
+    /// is not one of its graphs. Reading a graph that does not exist fails like any other
+    /// rejected query, so the graph is looked up with `GRAPH.LIST` once a query is rejected.
+    async fn missing_graph_or(&self, graph_name: &str, error: ClientError) -> ClientError {
+        if error.server_message().is_none() {
+            return error;
+        }
+        match self.list_graphs().await {
+            Ok(graphs) if !graphs.iter().any(|graph| graph == graph_name) => {
+                graph_not_found(graph_name)
+            }
+            _ => error,
+        }
+    }
+
+    /// Lists the names of the graphs on the FalkorDB instance.
+    pub(crate) async fn list_graphs(&self) -> Result<Vec<String>, ClientError> {
+        let client = self.connect().await?;
+        // The client does not retry listing itself, so broken pooled connections are retried here.
+        let mut attempts = 1;
+        loop {
+            match client.list_graphs().await {
+                Err(FalkorDBError::ConnectionDown) if attempts < self.pool_attempts() => {
+                    attempts += 1
+                }
+                result => {
+                    break result.map_err(|e| {
+                        ClientError::new(
+                            ErrorCode::Connection,
+                            format!("Failed to list graphs: {e}"),
+                        )
+                    })
+                }
+            }
+        }
+    }
+
+    /// The node labels and relationship types of `graph_name`, from `db.labels()` and
+    /// `db.relationshipTypes()`, which read them from the graph's catalog without touching its
+    /// entities.
+    pub(crate) async fn graph_metadata(
+        &self,
+        graph_name: &str,
+    ) -> Result<GraphMetadata, ClientError> {
+        check_graph_name(graph_name)?;
+        let (labels, relationship_types) = futures::try_join!(
+            self.procedure_names(graph_name, "CALL db.labels()"),
+            self.procedure_names(graph_name, "CALL db.relationshipTypes()"),
+        )?;
+        Ok(GraphMetadata {
+            labels,
+            relationship_types,
+        })
+    }
+
+    /// The strings in the first column of `procedure`'s rows, retrying transient failures.
+    async fn procedure_names(
+        &self,
+        graph_name: &str,
+        procedure: &str,
+    ) -> Result<Vec<String>, ClientError> {
+        let names = self
+            .retry
+            .run(|| async {
+                let client = self.connect().await?;
+                let result = client
+                    .select_graph(graph_name)
+                    .ro_query(procedure)
+                    .execute()
+                    .await
+                    .map_err(|e| discovery_error("Failed to read graph metadata", e))?;
+                Ok(result
+                    .data
+                    .into_values_lossy()
+                    .filter_map(|row| match row.into_iter().next() {
+                        Some(FalkorValue::String(name)) => Some(name),
+                        _ => None,
+                    })
+                    .collect())
+            })
+            .await;
+        match names {
+            Err(e) => Err(self.missing_graph_or(graph_name, e).await),
+            names => names,
+        }
+    }
+
+    /// Attempts a read gets: one more than the pool has connections, so that once every broken
+    /// connection has been replaced there is still one attempt left.
+    fn pool_attempts(&self) -> u32 {
+        u32::from(self.pool_size.get()) + 1
+    }
+
+    /// In read-only mode, refuses `query` if it contains a write clause.
+    fn check_read_only(&self, query: &str) -> Result<(), ClientError> {
+        match cypher::find_write_clause(query) {
+            Some(clause) if self.read_only => Err(ClientError::new(
+                ErrorCode::ReadOnlyViolation,
+                format!("Read-only mode: query contains a {clause} clause and was not executed"),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// In strict mode, rejects a query that drew schema warnings instead of letting it run.
+    fn check_strict(&self, warnings: &[String]) -> Result<(), ClientError> {
+        if self.strict && !warnings.is_empty() {
+            return Err(ClientError::new(
+                ErrorCode::QueryGeneration,
+                format!(
+                    "Strict mode: generated query was rejected: {}",
+                    warnings.join("; ")
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Executes `query` read-only against `graph_name`, keeping at most `max_rows` rows, formatted
+    /// for the answer prompt and as a [`CypherResultSet`].
+    ///
+    /// Unlike `text_to_cypher::core::execute_cypher_query`, which parks a blocking thread on a
+    /// fresh runtime for every query, this is driven entirely on the caller's runtime, so
+    /// concurrent requests never tie up threads while they wait on FalkorDB.
+    ///
+    /// Dropped connections and timeouts are retried per the configured [`Retry`], except for the
+    /// query running past `query_timeout_ms`, which FalkorDB enforces itself. A query FalkorDB
+    /// rejects fails with FalkorDB's message attached as its server message.
+    ///
+    /// The query FalkorDB receives ends in a `/* trace_id: ... */` comment, which its logs and
+    /// slow log keep, so they can be matched to the call.
+    async fn execute_query(
+        &self,
+        graph_name: &str,
+        full_query: &str,
+        max_rows: Option<u32>,
+        trace_id: &str,
+    ) -> Result<QueryOutput, ClientError> {
+        self.execute_rows(graph_name, full_query, 0, max_rows, Some(trace_id), false)
+            .await
+    }
+
+    /// Runs `query`, written by the caller rather than generated, against `graph_name`, and
+    /// returns its rows in the shape of a response without an answer. Read-only mode refuses a
+    /// write before it reaches FalkorDB; otherwise a write runs as one (see
+    /// [`Pipeline::execute_rows`]). The client's `max_rows` caps the rows kept. The query is
+    /// tagged with `trace_id`.
+    pub(crate) async fn execute_cypher(
+        &self,
+        graph_name: &str,
+        query: &str,
+        trace_id: &str,
+    ) -> Result<crate::TextToCypherResponse, ClientError> {
+        check_graph_name(graph_name)?;
+        if query.trim().is_empty() {
+            return Err(ClientError::new(
+                ErrorCode::InvalidArgument,
+                "Invalid cypherQuery: must not be empty",
+            ));
+        }
+        let mut details = RunDetails {
+            trace_id: trace_id.to_string(),
+            ..Default::default()
+        };
+        let failed = |e: ClientError| {
+            let mut response = TextToCypherResponse::error(e.to_string());
+            response.cypher_query = Some(query.to_string());
+            (*e.code(), response)
+        };
+        if let Err(e) = self.check_read_only(query) {
+            let (code, response) = failed(e);
+            return Err(details.failure(code, response));
+        }
+
+        let writes = cypher::find_write_clause(query).is_some();
+        let started = Instant::now();
+        let executed = self
+            .execute_rows(
+                graph_name,
+                query,
+                0,
+                self.max_rows,
+                Some(&details.trace_id),
+                writes,
+            )
+            .await;
+        details
+            .timings
+            .record(PipelineStage::QueryExecution, started);
+        let output = match executed {
+            Ok(output) => output,
+            Err(e) => {
+                details.execution_error = e.server_message().map(str::to_string);
+                let (code, response) = failed(e);
+                return Err(details.failure(code, response));
+            }
+        };
+        self.log(LogLevel::Info, Some(PipelineStage::QueryExecution), || {
+            format!("Query returned {} rows", output.result_set.rows.len())
+        });
+        details.result_set = Some(output.result_set);
+        details.truncated = output.truncated;
+        Ok(details.response(TextToCypherResponse {
+            status: "success".to_string(),
+            schema: None,
+            cypher_query: Some(query.to_string()),
+            cypher_result: Some(output.text),
+            answer: None,
+            confidence: None,
+            error: None,
+            token_usage: None,
+        }))
+    }
+
+    /// Fetches the page of rows `cursor` points to, re-running its query with `SKIP` and `LIMIT`
+    /// so FalkorDB sends only the page and one row more, which tells whether another page
+    /// follows. A query that cannot take them (one with its own `LIMIT`, or a `UNION`) is run
+    /// whole and the page cut out of its rows.
+    pub(crate) async fn fetch_page(&self, cursor: &PageCursor) -> Result<ResultPage, ClientError> {
+        // The cursor is caller-supplied, so its graph and query get the checks others do.
+        check_graph_name(&cursor.graph)?;
+        self.check_read_only(&cursor.query)?;
+        let paged = cypher::add_page(
+            &cursor.query,
+            cursor.offset,
+            cursor.page_size.saturating_add(1),
+        );
+        let (query, offset) = match &paged {
+            Some(paged) => (paged.as_str(), 0),
+            None => (cursor.query.as_str(), cursor.offset),
+        };
+        let output = self
+            .execute_rows(
+                &cursor.graph,
+                query,
+                offset,
+                Some(cursor.page_size),
+                None,
+                false,
+            )
+            .await?;
+        let output = self.filter_rows(output).await?;
+        Ok(ResultPage {
+            result_set: output.result_set,
+            cursor: output.truncated.then(|| cursor.next().encode()),
+        })
+    }
+
+    /// `output` without the rows `row_filter` rejects. The rows are all handed over at once, so
+    /// the calls queue up on the JS thread together instead of each waiting for the last.
+    async fn filter_rows(&self, output: QueryOutput) -> Result<QueryOutput, ClientError> {
+        let Some(row_filter) = &self.row_filter else {
+            return Ok(output);
+        };
+        let CypherResultSet { columns, rows } = output.result_set;
+        let keep = futures::future::try_join_all(
+            rows.iter()
+                .map(|row| row_filter(columns.iter().cloned().zip(row.iter().cloned()).collect())),
+        )
+        .await
+        .map_err(|e| {
+            ClientError::new(ErrorCode::QueryExecution, format!("Row filter failed: {e}"))
+        })?;
+        fn kept<T>(items: Vec<T>, keep: &[bool]) -> Vec<T> {
+            items
+                .into_iter()
+                .zip(keep)
+                .filter_map(|(item, &keep)| keep.then_some(item))
+                .collect()
+        }
+        let records = kept(output.records, &keep);
+        Ok(QueryOutput {
+            text: format_query_records(&records),
+            result_set: CypherResultSet {
+                columns,
+                rows: kept(rows, &keep),
+            },
+            records,
+            truncated: output.truncated,
+        })
+    }
+
+    /// [`Pipeline::execute_query`], skipping the first `offset` rows, with the trace comment only
+    /// when there is a `trace_id`. With `writes` the query runs as `GRAPH.QUERY`, which may change
+    /// the graph, and is not retried, since re-sending it after a dropped connection could apply
+    /// it twice.
+    async fn execute_rows(
+        &self,
+        graph_name: &str,
+        full_query: &str,
+        offset: u32,
+        max_rows: Option<u32>,
+        trace_id: Option<&str>,
+        writes: bool,
+    ) -> Result<QueryOutput, ClientError> {
+        let full_query = match trace_id {
+            Some(trace_id) => format!("{full_query} /* trace_id: {trace_id} */"),
+            None => full_query.to_string(),
+        };
+        // Parameters go through the client's escaping rather than the model's header text.
+        let (query, parameters) = match cypher::split_parameters(&full_query) {
+            Some((parameters, body)) => (body, falkor_parameters(&parameters)),
+            None => (full_query.as_str(), HashMap::new()),
+        };
+        let retry = if writes {
+            Retry {
+                max_retries: 0,
+                policy: None,
+                ..self.retry
+            }
+        } else {
+            self.retry
+        };
+        let result = retry
+            .run(|| async {
+                let client = self.connect().await?;
+                let mut graph = client.select_graph(graph_name);
+                let builder = if writes {
+                    graph.query(query)
+                } else {
+                    graph.ro_query(query)
+                };
+                let mut builder = builder.with_params(parameters.clone());
+                if let Some(ms) = self.query_timeout_ms {
+                    builder = builder.with_timeout(i64::from(ms));
+                }
+                let started = Instant::now();
+                let result = builder.execute().await.map_err(|e| {
+                    // FalkorDB aborts a query past its timeout with a server reply like any
+                    // other rejection; one that comes no sooner than the timeout is that abort.
+                    // Connection timeouts surface as dropped connections instead.
+                    let timed_out = self
+                        .query_timeout_ms
+                        .filter(|&ms| started.elapsed() >= Duration::from_millis(u64::from(ms)));
+                    match (e, timed_out) {
+                        (FalkorDBError::RedisError(_), Some(ms)) => ClientError::new(
+                            ErrorCode::QueryTimeout,
+                            format!("Query execution timed out after {ms}ms"),
+                        ),
+                        // FalkorDB's reply to this attempt, as the client reports it: less its
+                        // leading error code (the "mismatch: ..." of "Type mismatch: ..."). The
+                        // query is not sent again to read the rest.
+                        (FalkorDBError::RedisError(reply), _) => {
+                            ClientError::new(ErrorCode::QueryExecution, &reply)
+                                .with_server_message(reply)
+                        }
+                        (e, _) => ClientError::new(ErrorCode::QueryExecution, e.to_string())
+                            .with_failure_category(FailureCategory::of_falkordb(&e)),
+                    }
+                })?;
+
+                let limit = max_rows.map_or(usize::MAX, |max| max as usize);
+                let truncated = result.data.len().saturating_sub(offset as usize) > limit;
+                let rows: Vec<_> = result
+                    .data
+                    .into_values_lossy()
+                    .skip(offset as usize)
+                    .take(limit)
+                    .collect();
+                Ok(QueryOutput {
+                    text: format_query_records(&rows),
+                    result_set: CypherResultSet::new(&result.header, &rows),
+                    records: rows,
+                    truncated,
+                })
+            })
+            .await;
+        match result {
+            Err(e) => Err(self.missing_graph_or(graph_name, e).await),
+            result => result,
+        }
+    }
+
+    /// Lists the models available from a single provider.
+    pub(crate) async fn list_models(
+        &self,
+        adapter_kind: AdapterKind,
+    ) -> Result<Vec<String>, BoxError> {
+        list_adapter_models_with_endpoint(
+            adapter_kind,
+            &self.genai_client(),
+            self.llm_endpoint.as_deref(),
+        )
+        .await
+    }
+
+    /// Lists the models available from every supported provider.
+    pub(crate) async fn list_all_models(
+        &self,
+    ) -> Result<HashMap<AdapterKind, Vec<String>>, BoxError> {
+        list_all_models_with_endpoint(&self.genai_client(), self.llm_endpoint.as_deref()).await
+    }
+
+    /// Runs the pipeline for `chat_request` against `graph_name`.
+    ///
+    /// With `cypher_only` the pipeline stops after query generation, and a supplied `schema` is
+    /// used as-is instead of being discovered; `detached` also keeps it away from FalkorDB. When
+    /// `on_answer_chunk` is given the answer is streamed into it as it is generated; providers
+    /// that cannot stream get the whole answer delivered as a single chunk. `on_query_chunk` gets
+    /// the generated query the same way. `on_progress` hears about each stage that starts and
+    /// each that succeeds.
+    ///
+    /// Failures carry the error-status response `text_to_cypher::processor` would have returned,
+    /// plus whatever was produced before the failing stage (e.g. a query refused by read-only
+    /// mode).
+    pub(crate) async fn run(
+        &self,
+        graph_name: &str,
+        chat_request: ChatRequest,
+        options: RunOptions<'_>,
+    ) -> Result<crate::TextToCypherResponse, ClientError> {
+        if !options.detached {
+            check_graph_name(graph_name)?;
+        }
+        let api_key = match options.api_key.clone() {
+            Some(api_key) => api_key,
+            None => self.api_key.current()?,
+        };
+        let mut llm = Llm {
+            client: self.genai_client_with_options(self.chat_options.clone(), &api_key),
+            model: options.model.as_deref().unwrap_or(&self.model),
+            structured: false,
+            query_chunks: options.on_query_chunk,
+            cache_prompt: false,
+            cache_usage: Arc::default(),
+        };
+        let mut details = RunDetails {
+            cache_usage: llm.cache_usage.clone(),
+            result_format: options.result_format,
+            trace_id: options.trace_id.clone().unwrap_or_else(new_request_id),
+            ..Default::default()
+        };
+        let on_progress = options.on_progress;
+        let report = |stage, phase| {
+            if let Some(sink) = on_progress {
+                sink(ProgressEvent::now(stage, phase));
+            }
+        };
+
+        match llm.client.resolve_service_target(llm.model).await {
+            Ok(target) => {
+                let (_, model_name) = target.model.model_name.namespace_and_name();
+                details.model_used = Some(model_name.to_string());
+                llm.structured = self.structured_output
+                    && structured_output::supported(target.model.adapter_kind);
+                llm.cache_prompt =
+                    self.prompt_caching && prompt_cache::supported(target.model.adapter_kind);
+            }
+            Err(e) => {
+                return Err(details.failure(
+                    ErrorCode::QueryGeneration,
+                    TextToCypherResponse::error(format!("Failed to resolve service target: {e}")),
+                ))
+            }
+        }
+
+        // Step 1: Discover schema, unless the caller already has it
+        let schema = match options.schema {
+            Some(schema) => schema,
+            None if self.skip_discovery => {
+                let e = discovery_disabled();
+                return Err(details.failure(*e.code(), TextToCypherResponse::error(e.to_string())));
+            }
+            None => {
+                report(PipelineStage::SchemaDiscovery, StagePhase::Started);
+                let started = Instant::now();
+                let discovered = self.discover_schema(graph_name).await;
+                details
+                    .timings
+                    .record(PipelineStage::SchemaDiscovery, started);
+                match discovered {
+                    Ok(schema) => {
+                        report(PipelineStage::SchemaDiscovery, StagePhase::Completed);
+                        self.log(
+                            LogLevel::Debug,
+                            Some(PipelineStage::SchemaDiscovery),
+                            || format!("Discovered the schema of graph '{graph_name}'"),
+                        );
+                        schema
+                    }
+                    Err(e) => {
+                        return Err(details.failure(
+                            *e.code(),
+                            TextToCypherResponse::error(format!("Failed to discover schema: {e}")),
+                        ))
+                    }
+                }
+            }
+        };
+
+        // Large schemas are cut down to the labels the question is about before prompting.
+        let question = chat_request
+            .messages
+            .iter()
+            .rfind(|message| matches!(message.role, ChatRole::User))
+            .map_or("", |message| message.content.as_str());
+        let pruned = match self.fit_schema(&schema, question) {
+            Ok(pruned) => pruned,
+            Err(e) => {
+                let mut response = TextToCypherResponse::error(e.to_string());
+                response.schema = Some(schema);
+                return Err(details.failure(*e.code(), response));
+            }
+        };
+        let prompt_schema = pruned
+            .as_ref()
+            .map_or(schema.as_str(), |(pruned, _)| pruned);
+
+        let udfs = if options.detached {
+            self.provided_udfs()
+        } else {
+            self.resolve_udfs().await
+        };
+        let mut token_usage = TokenUsage::new();
+        let generation_request = self.generation_request(graph_name, &chat_request);
+        if self.debug {
+            details.prompt =
+                Some(self.generation_prompt(&llm, &generation_request, prompt_schema, &udfs));
+            details.schema_labels = pruned.as_ref().map(|(_, labels)| labels.clone());
+        }
+
+        // Step 2: Generate Cypher query
+        report(PipelineStage::QueryGeneration, StagePhase::Started);
+        self.log(LogLevel::Info, Some(PipelineStage::QueryGeneration), || {
+            let model = details.model_used.as_deref().unwrap_or(llm.model);
+            if options.detached {
+                format!("Generating a Cypher query for the supplied schema with model {model}")
+            } else {
+                format!("Generating a Cypher query for graph '{graph_name}' with model {model}")
+            }
+        });
+        let started = Instant::now();
+        let generated = self
+            .generate_query(
+                &llm,
+                &generation_request,
+                prompt_schema,
+                &udfs,
+                &mut token_usage,
+            )
+            .await;
+        details
+            .timings
+            .record(PipelineStage::QueryGeneration, started);
+        let cypher_query = match generated {
+            Ok(query) => query,
+            Err(e) => {
+                let code = match e.downcast_ref::<NoQuery>() {
+                    Some(no_query) => {
+                        details.model_reply = no_query.reply.clone();
+                        ErrorCode::NoQueryGenerated
+                    }
+                    None => ErrorCode::QueryGeneration,
+                };
+                let mut response = TextToCypherResponse::error_with_usage(
+                    format!("Failed to generate query: {e}"),
+                    Some(token_usage),
+                );
+                response.schema = Some(schema);
+                return Err(details.failure(code, response));
+            }
+        };
+        report(PipelineStage::QueryGeneration, StagePhase::Completed);
+        self.log(LogLevel::Info, Some(PipelineStage::QueryGeneration), || {
+            format!("Generated query: {cypher_query}")
+        });
+
+        details.warnings = schema_warnings(&cypher_query, &schema);
+        for warning in &details.warnings {
+            self.log(LogLevel::Warn, Some(PipelineStage::QueryGeneration), || {
+                warning.clone()
+            });
+        }
+        if let Err(e) = self.check_strict(&details.warnings) {
+            let mut response =
+                TextToCypherResponse::error_with_usage(e.to_string(), Some(token_usage));
+            response.schema = Some(schema);
+            response.cypher_query = Some(cypher_query);
+            return Err(details.failure(*e.code(), response));
+        }
+        if self.with_confidence {
+            details.query_confidence = self
+                .rate_query(
+                    &llm,
+                    question,
+                    prompt_schema,
+                    &cypher_query,
+                    &mut token_usage,
+                )
+                .await;
+        }
+
+        if options.cypher_only && options.explain {
+            let failure = match self.explain_query(graph_name, &cypher_query).await {
+                Ok(Ok(plan)) => {
+                    details.query_plan = Some(plan);
+                    None
+                }
+                Ok(Err(message)) => Some(ClientError::new(
+                    ErrorCode::QueryExecution,
+                    format!("FalkorDB could not plan the query: {message}"),
+                )),
+                Err(e) => Some(e),
+            };
+            if let Some(e) = failure {
+                let mut response =
+                    TextToCypherResponse::error_with_usage(e.to_string(), Some(token_usage));
+                response.schema = Some(schema);
+                response.cypher_query = Some(cypher_query);
+                return Err(details.failure(*e.code(), response));
+            }
+        }
+
+        if options.cypher_only {
+            return Ok(details.response(TextToCypherResponse::success_with_usage(
+                schema,
+                cypher_query,
+                None,
+                None,
+                Some(token_usage),
+            )));
+        }
+
+        if let Err(e) = self.check_read_only(&cypher_query) {
+            let mut response =
+                TextToCypherResponse::error_with_usage(e.to_string(), Some(token_usage));
+            response.schema = Some(schema);
+            response.cypher_query = Some(cypher_query);
+            return Err(details.failure(*e.code(), response));
+        }
+
+        // Step 3: Execute query, regenerating it with the error as feedback on failure
+        report(PipelineStage::QueryExecution, StagePhase::Started);
+        let max_rows = options.max_rows.or(self.max_rows);
+        let started = Instant::now();
+        let executed = match self
+            .execute_query(graph_name, &cypher_query, max_rows, &details.trace_id)
+            .await
+        {
+            Ok(result) => Ok((cypher_query, result, 0)),
+            // A slow query is not a wrong one, and a missing graph is not the query's fault, so
+            // there is nothing for self-healing to fix.
+            Err(e) if matches!(e.code(), ErrorCode::QueryTimeout | ErrorCode::GraphNotFound) => {
+                details
+                    .timings
+                    .record(PipelineStage::QueryExecution, started);
+                details.self_heal_attempts = Some(0);
+                let mut response =
+                    TextToCypherResponse::error_with_usage(e.to_string(), Some(token_usage));
+                response.schema = Some(schema);
+                response.cypher_query = Some(cypher_query);
+                return Err(details.failure(*e.code(), response));
+            }
+            Err(e) => {
+                self.heal(
+                    &llm,
+                    (graph_name, &details.trace_id),
+                    &generation_request,
+                    prompt_schema,
+                    &udfs,
+                    (cypher_query, e),
+                    max_rows,
+                    &mut token_usage,
+                )
+                .await
+            }
+        };
+        details
+            .timings
+            .record(PipelineStage::QueryExecution, started);
+        let (cypher_query, output, healed) = match executed {
+            Ok(executed) => executed,
+            // Report the original failure's code: healing only retries it.
+            Err(failure) => {
+                let HealFailure {
+                    code,
+                    message,
+                    failed_query,
+                    execution_error,
+                    attempts,
+                } = failure;
+                let mut response =
+                    TextToCypherResponse::error_with_usage(message, Some(token_usage));
+                response.schema = Some(schema);
+                response.cypher_query = Some(failed_query);
+                details.execution_error = execution_error;
+                details.self_heal_attempts = Some(attempts);
+                return Err(details.failure(code, response));
+            }
+        };
+
+        details.self_heal_attempts = Some(healed);
+        let returned = output.result_set.rows.len();
+        let output = match self.filter_rows(output).await {
+            Ok(output) => output,
+            Err(e) => {
+                let mut response =
+                    TextToCypherResponse::error_with_usage(e.to_string(), Some(token_usage));
+                response.schema = Some(schema);
+                response.cypher_query = Some(cypher_query);
+                return Err(details.failure(*e.code(), response));
+            }
+        };
+        report(PipelineStage::QueryExecution, StagePhase::Completed);
+        self.log(LogLevel::Info, Some(PipelineStage::QueryExecution), || {
+            let more = if output.truncated {
+                ", more were dropped"
+            } else {
+                ""
+            };
+            let filtered = match returned - output.result_set.rows.len() {
+                0 => String::new(),
+                filtered => format!(", {filtered} filtered out"),
+            };
+            format!("Query returned {returned} rows{more}{filtered}")
+        });
+        // Self-healing may have replaced the query.
+        details.warnings = schema_warnings(&cypher_query, &schema);
+        if healed > 0 && self.with_confidence {
+            details.query_confidence = self
+                .rate_query(
+                    &llm,
+                    question,
+                    prompt_schema,
+                    &cypher_query,
+                    &mut token_usage,
+                )
+                .await;
+        }
+        let QueryOutput {
+            text: cypher_result,
+            result_set,
+            records,
+            truncated,
+        } = output;
+        details.result_set = Some(result_set);
+        details.truncated = truncated;
+        if let (Some(column), Some(result_set)) = (&options.group_by, &details.result_set) {
+            match result_set.group_by(column) {
+                Ok(groups) => details.groups = Some(groups),
+                Err(e) => {
+                    let mut response =
+                        TextToCypherResponse::error_with_usage(e.to_string(), Some(token_usage));
+                    response.schema = Some(schema);
+                    response.cypher_query = Some(cypher_query);
+                    response.cypher_result = Some(cypher_result);
+                    return Err(details.failure(*e.code(), response));
+                }
+            }
+        }
+        if options.paged && truncated {
+            let cursor = PageCursor {
+                graph: graph_name.to_string(),
+                query: cypher_query.clone(),
+                offset: 0,
+                page_size: max_rows.unwrap_or_default(),
+            };
+            details.cursor = Some(cursor.next().encode());
+        }
+
+        if options.skip_answer {
+            return Ok(details.response(TextToCypherResponse::success_with_usage(
+                schema,
+                cypher_query,
+                Some(cypher_result),
+                None,
+                Some(token_usage),
+            )));
+        }
+
+        // Step 4: Generate final answer from the selected columns, telling the model when there
+        // are no rows or it only sees part of them
+        let columns = details
+            .result_set
+            .as_ref()
+            .map_or(&[][..], |result_set| &result_set.columns);
+        let redacted =
+            (!self.redaction.is_empty()).then(|| self.redaction.records(columns, &records));
+        let answer_result = match &options.answer_columns {
+            Some(selected) => {
+                match format_columns(columns, redacted.as_deref().unwrap_or(&records), selected) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        let mut response = TextToCypherResponse::error_with_usage(
+                            e.to_string(),
+                            Some(token_usage),
+                        );
+                        response.schema = Some(schema);
+                        response.cypher_query = Some(cypher_query);
+                        response.cypher_result = Some(cypher_result);
+                        return Err(details.failure(*e.code(), response));
+                    }
+                }
+            }
+            None => match &redacted {
+                Some(records) => format_query_records(records),
+                None => cypher_result.clone(),
+            },
+        };
+        // The prompt reads "... which returned {answer_input}.", so an empty result is spelled
+        // out in place of the formatter's "No results returned."
+        let answer_input = if records.is_empty() {
+            "no rows: it ran successfully but matched nothing".to_string()
+        } else if truncated {
+            format!(
+                "{answer_result}\n(Only the first {} rows are shown; the query returned more.)",
+                max_rows.unwrap_or_default()
+            )
+        } else {
+            answer_result
+        };
+        let mut answer_llm = Llm {
+            client: match self.answer_max_tokens {
+                Some(max_tokens) => self.genai_client_with_options(
+                    self.chat_options.clone().with_max_tokens(max_tokens),
+                    &api_key,
+                ),
+                None => llm.client.clone(),
+            },
+            model: self.answer_model.as_deref().unwrap_or(llm.model),
+            structured: false,
+            query_chunks: None,
+            cache_prompt: false,
+            cache_usage: llm.cache_usage.clone(),
+        };
+        if self.answer_schema.is_some() {
+            answer_llm.structured = answer_llm
+                .client
+                .resolve_service_target(answer_llm.model)
+                .await
+                .is_ok_and(|target| structured_output::supported(target.model.adapter_kind));
+        }
+        report(PipelineStage::AnswerGeneration, StagePhase::Started);
+        self.log(
+            LogLevel::Info,
+            Some(PipelineStage::AnswerGeneration),
+            || {
+                let model = answer_llm.model;
+                let model = model.split_once("::").map_or(model, |(_, name)| name);
+                format!("Generating the answer with model {model}")
+            },
+        );
+        let started = Instant::now();
+        let answered = self
+            .generate_answer(
+                &answer_llm,
+                &self.answer_request(&chat_request),
+                &cypher_query,
+                &answer_input,
+                &mut token_usage,
+                options.on_answer_chunk,
+            )
+            .await;
+        details
+            .timings
+            .record(PipelineStage::AnswerGeneration, started);
+        let (answer, confidence) = match answered {
+            Ok(answer) => answer,
+            Err(e) => {
+                // The rows are still worth returning, so the call resolves without an answer.
+                let answer_error = format!("Failed to generate answer: {e}");
+                self.log(
+                    LogLevel::Warn,
+                    Some(PipelineStage::AnswerGeneration),
+                    || format!("{answer_error}; returning the query results without an answer"),
+                );
+                let mut response = details.response(TextToCypherResponse::success_with_usage(
+                    schema,
+                    cypher_query,
+                    Some(cypher_result),
+                    None,
+                    Some(token_usage),
+                ));
+                response.status = "partial".to_string();
+                response.answer_error = Some(answer_error);
+                return Ok(response);
+            }
+        };
+        report(PipelineStage::AnswerGeneration, StagePhase::Completed);
+
+        // A structured answer that does not match the schema stays a free-text one.
+        let answer_json = self
+            .answer_schema
+            .as_ref()
+            .filter(|_| answer_llm.structured)
+            .and_then(|answer_schema| answer_schema.parse(&answer));
+        let mut response = TextToCypherResponse::success_with_usage(
+            schema,
+            cypher_query,
+            Some(cypher_result),
+            Some(answer),
+            Some(token_usage),
+        );
+        response.confidence = confidence;
+        let mut response = details.response(response);
+        response.answer_json = answer_json;
+        Ok(response)
+    }
+
+    /// The conversation sent for query generation: `chat_request` preceded by the `cypher_dialect`
+    /// constraint, the configured `system_prompt`, and the hints set for `graph_name`, if any,
+    /// and then the few-shot examples as question and answer turns, unless a `promptTemplate`
+    /// places them itself.
+    ///
+    /// The library always sends its own schema and instructions prompt ahead of the conversation,
+    /// so the custom instructions follow it directly. Only the last user message gets the
+    /// library's question template, so the examples pass through as written.
+    fn generation_request(&self, graph_name: &str, chat_request: &ChatRequest) -> ChatRequest {
+        let dialect = self.cypher_dialect.iter().map(|dialect| ChatMessage {
+            role: ChatRole::System,
+            content: format!(
+                "Write the query in this Cypher dialect: {dialect}. Use only the clauses, \
+                 functions, and procedures it supports, even where other Cypher dialects offer \
+                 a shorter form."
+            ),
+        });
+        let system_prompt = self.system_prompt.iter().map(|system_prompt| ChatMessage {
+            role: ChatRole::System,
+            content: system_prompt.clone(),
+        });
+        let hints = self
+            .graph_hints
+            .prompt(graph_name)
+            .map(|content| ChatMessage {
+                role: ChatRole::System,
+                content,
+            });
+        let examples = match &self.prompt_template {
+            Some(template) if template.has_examples() => &[][..],
+            _ => &self.examples[..],
+        };
+        let examples = examples.iter().flat_map(|example| {
+            [
+                ChatMessage {
+                    role: ChatRole::User,
+                    content: example.question.clone(),
+                },
+                ChatMessage {
+                    role: ChatRole::Assistant,
+                    content: format!("```cypher\n{}\n```", example.cypher.trim()),
+                },
+            ]
+        });
+
+        ChatRequest {
+            messages: dialect
+                .chain(system_prompt)
+                .chain(hints)
+                .chain(examples)
+                .chain(chat_request.messages.iter().cloned())
+                .collect(),
+        }
+    }
+
+    /// Whether the model reads skills with `read_skill` tool calls, as `text_to_cypher` has it
+    /// do, rather than getting them in full in the prompt: when it supports tool calling, and the
+    /// query is not asked for as structured output, from a `promptTemplate`, as a stream, or with
+    /// a cacheable prompt, each of which takes a single reply.
+    fn reads_skills_with_tools(&self, llm: &Llm<'_>) -> bool {
+        self.skill_catalog
+            .as_ref()
+            .is_some_and(|catalog| !catalog.is_empty())
+            && skills::supports_tool_calling(llm.model)
+            && !(llm.structured
+                || self.prompt_template.is_some()
+                || llm.query_chunks.is_some()
+                || llm.cache_prompt)
+    }
+
+    /// The skills block of the prompt: a catalog of the skills for the model to read with
+    /// `skill_tools`, and their full content otherwise.
+    fn skills_prompt(&self, skill_tools: bool) -> String {
+        match &self.skill_catalog {
+            Some(catalog) if !catalog.is_empty() => {
+                if skill_tools {
+                    catalog.render_catalog()
+                } else {
+                    catalog.render_all_content()
+                }
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// The system prompt and conversation `generate_query` sends for `generation_request`, the
+    /// way `text_to_cypher::core` builds them, with the skills to be read with `skill_tools` (see
+    /// [`Pipeline::reads_skills_with_tools`]). The last user message gets the library's question
+    /// template, or is replaced by the rendered `promptTemplate`, which then stands in for the
+    /// library's system prompt as well.
+    fn generation_messages(
+        &self,
+        llm: &Llm<'_>,
+        generation_request: &ChatRequest,
+        schema: &str,
+        udfs: &str,
+        skill_tools: bool,
+    ) -> (Option<String>, Vec<ChatMessage>) {
+        let skills = self.skills_prompt(skill_tools);
+        let system_prompt = match &self.prompt_template {
+            Some(_) => None,
+            None => Some(TemplateEngine::render_system_prompt_with_context(
+                schema, &skills, udfs,
+            )),
+        };
+        let system_prompt = match system_prompt {
+            Some(system_prompt) if llm.structured => {
+                Some(format!("{system_prompt}\n\n{}", structured_output::PROMPT))
+            }
+            None if llm.structured => Some(structured_output::PROMPT.to_string()),
+            system_prompt => system_prompt,
+        };
+
+        let last = generation_request.messages.len().saturating_sub(1);
+        let messages = generation_request
+            .messages
+            .iter()
+            .enumerate()
+            .map(|(index, message)| match message.role {
+                ChatRole::User if index == last => ChatMessage {
+                    role: ChatRole::User,
+                    content: match &self.prompt_template {
+                        Some(template) => template.render(&PromptValues {
+                            schema,
+                            question: &message.content,
+                            examples: &self.examples,
+                            skills: &skills,
+                            udfs,
+                        }),
+                        None => TemplateEngine::render_user_prompt(&message.content),
+                    },
+                },
+                _ => message.clone(),
+            })
+            .collect();
+        (system_prompt, messages)
+    }
+
+    /// Renders the prompt `generate_query` sends for `generation_request`: the system prompt,
+    /// if any, then each message.
+    fn generation_prompt(
+        &self,
+        llm: &Llm<'_>,
+        generation_request: &ChatRequest,
+        schema: &str,
+        udfs: &str,
+    ) -> String {
+        let skill_tools = self.reads_skills_with_tools(llm);
+        let (system_prompt, messages) =
+            self.generation_messages(llm, generation_request, schema, udfs, skill_tools);
+        let messages = messages.iter().map(|message| match message.role {
+            ChatRole::User => format!("[user]\n{}", message.content),
+            ChatRole::Assistant => format!("[assistant]\n{}", message.content),
+            ChatRole::System => format!("[system]\n{}", message.content),
+        });
+
+        system_prompt
+            .map(|system_prompt| format!("[system]\n{system_prompt}"))
+            .into_iter()
+            .chain(messages)
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Prunes `schema` to the `schema_top_k` labels most relevant to `question`, when set, and
+    /// holds the schema sent to the model to `max_schema_bytes`. A pruned schema over the limit
+    /// keeps as many of its labels as fit instead; an unpruned one fails with `SchemaTooLarge`.
+    fn fit_schema(
+        &self,
+        schema: &str,
+        question: &str,
+    ) -> Result<Option<(String, Vec<String>)>, ClientError> {
+        let prune = |top_k| prune_schema(schema, question, top_k);
+        let pruned = self.schema_top_k.and_then(prune);
+        let Some(max_bytes) = self.max_schema_bytes else {
+            return Ok(pruned);
+        };
+        let size = |pruned: &Option<(String, Vec<String>)>| {
+            pruned
+                .as_ref()
+                .map_or(schema.len(), |(pruned, _)| pruned.len())
+        };
+        let too_large = |advice: &str| schema_too_large(schema, max_bytes, advice);
+        if size(&pruned) <= max_bytes {
+            return Ok(pruned);
+        }
+        let Some(top_k) = self.schema_top_k else {
+            return Err(too_large(
+                "set schemaTopK to send only the labels each question needs",
+            ));
+        };
+        if pruned.is_none() {
+            return Err(too_large(
+                "schemaTopK cannot prune it without an entities list",
+            ));
+        }
+
+        // Fewer labels never make a larger schema, so the most that fit are found by bisection.
+        let (mut fitting, mut low, mut high) = (None, 1, top_k - 1);
+        while low <= high {
+            let middle = low + (high - low) / 2;
+            let candidate = prune(middle);
+            if size(&candidate) <= max_bytes {
+                fitting = candidate;
+                low = middle + 1;
+            } else {
+                high = middle - 1;
+            }
+        }
+        match fitting {
+            Some((pruned, labels)) => {
+                self.log(LogLevel::Warn, Some(PipelineStage::QueryGeneration), || {
+                    format!(
+                        "Pruned the schema to {} of {top_k} labels to fit maxSchemaBytes",
+                        labels.len()
+                    )
+                });
+                Ok(Some((pruned, labels)))
+            }
+            None => Err(too_large(&format!(
+                "pruned to a single label it is still {} bytes",
+                size(&prune(1))
+            ))),
+        }
+    }
+
+    /// Resolves the UDF context block for the prompt; discovery failures degrade to no context.
+    async fn resolve_udfs(&self) -> String {
+        match &self.udf_source {
+            UdfSource::Discover => match discover_udfs(self.current_endpoint()).await {
+                Ok(catalog) => catalog.render(),
+                Err(UdfError::Unsupported | UdfError::Transport(_)) => String::new(),
+            },
+            _ => self.provided_udfs(),
+        }
+    }
+
+    /// The UDF context block from a provided catalog, never discovering one.
+    fn provided_udfs(&self) -> String {
+        match &self.udf_source {
+            UdfSource::Provided(catalog) => catalog.render(),
+            UdfSource::Off | UdfSource::Discover => String::new(),
+        }
+    }
+
+    /// Generates a Cypher query for `chat_request`, retrying transient model failures, and bounds
+    /// its rows with `enforce_limit` when it does not already. With `no_query_retry`, a reply
+    /// holding no query is followed up once with [`NO_QUERY_RETRY_PROMPT`].
+    async fn generate_query(
+        &self,
+        llm: &Llm<'_>,
+        chat_request: &ChatRequest,
+        schema: &str,
+        udfs: &str,
+        token_usage: &mut TokenUsage,
+    ) -> Result<String, BoxError> {
+        let generated = self
+            .generate_any_query(llm, chat_request, schema, udfs, token_usage)
+            .await;
+        let query = match generated {
+            Err(e) if self.no_query_retry && e.is::<NoQuery>() => {
+                self.log(LogLevel::Warn, Some(PipelineStage::QueryGeneration), || {
+                    "The model replied without a query; asking again for a query only".to_string()
+                });
+                let mut retry_request = chat_request.clone();
+                if let Some(reply) = e.downcast::<NoQuery>().ok().and_then(|e| e.reply) {
+                    retry_request.messages.push(ChatMessage {
+                        role: ChatRole::Assistant,
+                        content: reply,
+                    });
+                }
+                retry_request.messages.push(ChatMessage {
+                    role: ChatRole::User,
+                    content: NO_QUERY_RETRY_PROMPT.to_string(),
+                });
+                self.generate_any_query(llm, &retry_request, schema, udfs, token_usage)
+                    .await?
+            }
+            generated => generated?,
+        };
+
+        let limited = self
+            .enforce_limit
+            .and_then(|limit| cypher::add_limit(&query, limit));
+        match limited {
+            Some(limited) => {
+                self.log(
+                    LogLevel::Debug,
+                    Some(PipelineStage::QueryGeneration),
+                    || "Appended the enforceLimit LIMIT to the generated query".to_string(),
+                );
+                Ok(limited)
+            }
+            None => Ok(query),
+        }
+    }
+
+    /// Generates a Cypher query for `chat_request`, retrying transient model failures. A reply
+    /// holding no query fails with [`NoQuery`], and one that does not match the structured
+    /// output schema has the query extracted from its text instead.
+    ///
+    /// With `llm.query_chunks`, the reply is streamed into it as the model writes it, without
+    /// retries, since a retry would repeat chunks already delivered. Structured output, and
+    /// providers that cannot stream, get the finished query delivered as a single chunk.
+    async fn generate_any_query(
+        &self,
+        llm: &Llm<'_>,
+        chat_request: &ChatRequest,
+        schema: &str,
+        udfs: &str,
+        token_usage: &mut TokenUsage,
+    ) -> Result<String, BoxError> {
+        let skill_tools = self.reads_skills_with_tools(llm);
+        let request = self.generation_chat_request(llm, chat_request, schema, udfs, skill_tools);
+        // Sent instead when the provider refuses the request with the skill tool.
+        let fallback = skill_tools
+            .then(|| self.generation_chat_request(llm, chat_request, schema, udfs, false));
+        let options = if llm.structured {
+            ChatOptions::default().with_response_format(structured_output::response_format())
+        } else {
+            ChatOptions::default()
+        };
+
+        let streamed = match llm.query_chunks {
+            Some(on_chunk) if !llm.structured => {
+                self.stream_reply(llm, request.clone(), on_chunk, token_usage)
+                    .await
+            }
+            _ => None,
+        };
+        let query = match streamed {
+            Some(reply) => self.extract_query(llm, &reply?)?,
+            None => {
+                let spent = Mutex::new(TokenUsage::new());
+                // A reply with no valid query fails inside the retry, for a `generation` policy.
+                let query = self
+                    .retry
+                    .run(|| async {
+                        let reply = self
+                            .exec_generation(llm, &request, fallback.as_ref(), &options, &spent)
+                            .await?;
+                        self.extract_query(llm, &reply)
+                    })
+                    .await;
+                token_usage.accumulate(&spent.into_inner().unwrap());
+                let query = query?;
+                if let Some(on_chunk) = llm.query_chunks {
+                    on_chunk(query.clone());
+                }
+                query
+            }
+        };
+        Ok(query)
+    }
+
+    /// The query-generation request for `chat_request`, with the `read_skill` tool when
+    /// `skill_tools`.
+    fn generation_chat_request(
+        &self,
+        llm: &Llm<'_>,
+        chat_request: &ChatRequest,
+        schema: &str,
+        udfs: &str,
+        skill_tools: bool,
+    ) -> genai::chat::ChatRequest {
+        let (system_prompt, messages) =
+            self.generation_messages(llm, chat_request, schema, udfs, skill_tools);
+        let messages = messages.into_iter().map(|message| match message.role {
+            ChatRole::User => genai::chat::ChatMessage::user(message.content),
+            ChatRole::Assistant => genai::chat::ChatMessage::assistant(message.content),
+            ChatRole::System => genai::chat::ChatMessage::system(message.content),
+        });
+        let request = match system_prompt {
+            // A system message rather than the request's system text, which takes no cache marker.
+            Some(system_prompt) if llm.cache_prompt => {
+                let system = genai::chat::ChatMessage::system(system_prompt)
+                    .with_options(CacheControl::Ephemeral);
+                genai::chat::ChatRequest::from_messages(
+                    std::iter::once(system).chain(messages).collect(),
+                )
+            }
+            Some(system_prompt) => genai::chat::ChatRequest::from_messages(messages.collect())
+                .with_system(system_prompt),
+            None => genai::chat::ChatRequest::from_messages(messages.collect()),
+        };
+        match &self.skill_catalog {
+            Some(catalog) if skill_tools => request.append_tool(catalog.tool_definition()),
+            _ => request,
+        }
+    }
+
+    /// Sends query-generation `request` and returns the model's reply, tallying usage in
+    /// `spent`. The model's `read_skill` calls are answered for up to `MAX_TOOL_ROUNDS` rounds,
+    /// then a reply is asked for without the tool; a request the provider fails with the tool is
+    /// sent as `fallback` instead. This is the exchange `text_to_cypher::core` has with the model.
+    async fn exec_generation(
+        &self,
+        llm: &Llm<'_>,
+        request: &genai::chat::ChatRequest,
+        fallback: Option<&genai::chat::ChatRequest>,
+        options: &ChatOptions,
+        spent: &Mutex<TokenUsage>,
+    ) -> Result<String, BoxError> {
+        let exec = |request: genai::chat::ChatRequest| async move {
+            let response = llm
+                .client
+                .exec_chat(llm.model, request, Some(options))
+                .await?;
+            spent.lock().unwrap().add_genai_usage(&response.usage);
+            llm.cache_usage.add(&response.usage);
+            Ok::<_, genai::Error>(response)
+        };
+        let mut request = request.clone();
+        for _ in 0..skills::MAX_TOOL_ROUNDS {
+            let response = match (exec(request.clone()).await, fallback) {
+                (Ok(response), _) => response,
+                (Err(e), Some(fallback)) => {
+                    self.log(LogLevel::Warn, Some(PipelineStage::QueryGeneration), || {
+                        format!("Chat request with the skill tool failed; retrying without: {e}")
+                    });
+                    let response = exec(fallback.clone()).await.map_err(|fallback_error| {
+                        ProviderError::boxed(
+                            format!("Chat request failed: {e}; fallback failed: {fallback_error}"),
+                            &fallback_error,
+                        )
+                    })?;
+                    return Ok(response.into_first_text().unwrap_or_default());
+                }
+                (Err(e), None) => {
+                    return Err(ProviderError::boxed(
+                        format!("Chat request failed: {e}"),
+                        &e,
+                    ))
+                }
+            };
+            let tool_calls: Vec<_> = response.tool_calls().into_iter().cloned().collect();
+            if tool_calls.is_empty() {
+                return Ok(response.into_first_text().unwrap_or_default());
+            }
+            request = request.append_message(genai::chat::ChatMessage::from(tool_calls.clone()));
+            for tool_response in
+                skills::resolve_skill_tool_calls(&tool_calls, self.skill_catalog.as_ref())
+            {
+                request = request.append_message(genai::chat::ChatMessage::from(tool_response));
+            }
+        }
+        request.tools = None;
+        let response = exec(request).await.map_err(|e| {
+            ProviderError::boxed(format!("Chat request failed after tool rounds: {e}"), &e)
+        })?;
+        Ok(response.into_first_text().unwrap_or_default())
+    }
+
+    /// The validated query in a query-generation `reply`.
+    fn extract_query(&self, llm: &Llm<'_>, reply: &str) -> Result<String, BoxError> {
+        let structured = llm
+            .structured
+            .then(|| structured_output::parse_reply(reply))
+            .flatten();
+        let query = structured.unwrap_or_else(|| {
+            if !llm.structured {
+                return clean_generated_cypher_response(reply);
+            }
+            self.log(LogLevel::Warn, Some(PipelineStage::QueryGeneration), || {
+                "The model's reply did not match the structured output schema; extracting the \
+                 query from its text"
+                    .to_string()
+            });
+            clean_generated_cypher_response(reply)
+        });
+        if query.is_empty() || query == "NO ANSWER" || !cypher::holds_query(&query) {
+            let reply = reply.trim();
+            return Err(NoQuery {
+                reply: (!reply.is_empty()).then(|| reply.to_string()),
+            }
+            .into());
+        }
+        let validation = CypherValidator::validate(&query);
+        if !validation.is_valid {
+            return Err(InvalidQuery {
+                errors: validation.errors,
+            }
+            .into());
+        }
+        Ok(query)
+    }
+
+    /// Streams the reply to `request` into `on_chunk` as it is written; `None` when the provider
+    /// cannot stream, leaving the caller to make an ordinary request.
+    async fn stream_reply(
+        &self,
+        llm: &Llm<'_>,
+        request: genai::chat::ChatRequest,
+        on_chunk: ChunkSink<'_>,
+        token_usage: &mut TokenUsage,
+    ) -> Option<Result<String, BoxError>> {
+        let options = ChatOptions::default().with_capture_usage(true);
+        let mut stream = llm
+            .client
+            .exec_chat_stream(llm.model, request, Some(&options))
+            .await
+            .ok()?
+            .stream;
+
+        let mut text = String::new();
+        while let Some(event) = stream.next().await {
+            let event = match event {
+                Ok(event) => event,
+                Err(e) => return Some(Err(format!("Chat request failed: {e}").into())),
+            };
+            match event {
+                ChatStreamEvent::Chunk(chunk) => {
+                    text.push_str(&chunk.content);
+                    on_chunk(chunk.content);
+                }
+                ChatStreamEvent::End(end) => {
+                    if let Some(usage) = end.captured_usage {
+                        token_usage.add_genai_usage(&usage);
+                        llm.cache_usage.add(&usage);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some(Ok(text))
+    }
+
+    /// Generates the natural-language answer in one piece, retrying transient model failures.
+    /// The request is the one `text_to_cypher` would send, made here so that a rate-limited
+    /// reply's `Retry-After` can be seen.
+    async fn generate_whole_answer(
+        &self,
+        llm: &Llm<'_>,
+        chat_request: &ChatRequest,
+        cypher_query: &str,
+        cypher_result: &str,
+        token_usage: &mut TokenUsage,
+    ) -> Result<(String, Option<u8>), BoxError> {
+        let mut request = answer_chat_request(chat_request, cypher_query, cypher_result);
+        let mut options = None;
+        let answer_schema = self.answer_schema.as_ref().filter(|_| llm.structured);
+        if let Some(answer_schema) = answer_schema {
+            request = request.with_system(answer_format::PROMPT);
+            options =
+                Some(ChatOptions::default().with_response_format(answer_schema.response_format()));
+        }
+        let spent = Mutex::new(TokenUsage::new());
+        let reply = self
+            .retry
+            .run(|| async {
+                let response = llm
+                    .client
+                    .exec_chat(llm.model, request.clone(), options.as_ref())
+                    .await
+                    .map_err(|e| ProviderError::boxed(format!("Chat request failed: {e}"), &e))?;
+                spent.lock().unwrap().add_genai_usage(&response.usage);
+                llm.cache_usage.add(&response.usage);
+                Ok::<_, BoxError>(
+                    response
+                        .into_first_text()
+                        .unwrap_or_else(|| "Unable to generate answer".to_string()),
+                )
+            })
+            .await;
+        token_usage.accumulate(&spent.into_inner().unwrap());
+        let (answer, confidence) = parse_answer_confidence(&reply?);
+        // A matching structured answer is handed out as compact JSON, whatever its layout.
+        match answer_schema.and_then(|answer_schema| answer_schema.parse(&answer)) {
+            Some(json) => Ok((json.to_string(), confidence)),
+            None => Ok((answer, confidence)),
+        }
+    }
+
+    /// Asks the model how likely `cypher_query` is to answer `question` correctly against
+    /// `schema`, from 0 to 1, retrying transient model failures. Best-effort: a failed call or a
+    /// reply that is not such a number yields `None`.
+    async fn rate_query(
+        &self,
+        llm: &Llm<'_>,
+        question: &str,
+        schema: &str,
+        cypher_query: &str,
+        token_usage: &mut TokenUsage,
+    ) -> Option<f64> {
+        let request = genai::chat::ChatRequest::from_messages(vec![
+            genai::chat::ChatMessage::system(QUERY_RATING_PROMPT),
+            genai::chat::ChatMessage::user(format!(
+                "Graph schema:\n{schema}\n\nQuestion: {question}\n\nCypher query:\n{cypher_query}"
+            )),
+        ]);
+        let response = self
+            .retry
+            .run(|| async {
+                llm.client
+                    .exec_chat(llm.model, request.clone(), None)
+                    .await
+                    .map_err(|e| ProviderError::boxed(e.to_string(), &e))
+            })
+            .await
+            .ok()?;
+        token_usage.add_genai_usage(&response.usage);
+        llm.cache_usage.add(&response.usage);
+        parse_query_rating(response.first_text()?)
+    }
+
+    /// Regenerates a failed query with the execution error as feedback and executes it again,
+    /// up to `self_heal_attempts` times, returning the query that ran and the attempts it took.
+    /// Each attempt's conversation keeps the earlier failed queries and their errors, so the
+    /// model does not repeat a mistake. Stops early when a regenerated query fails for a reason
+    /// other than FalkorDB rejecting it.
+    #[allow(clippy::too_many_arguments)]
+    async fn heal(
+        &self,
+        llm: &Llm<'_>,
+        (graph_name, trace_id): (&str, &str),
+        chat_request: &ChatRequest,
+        schema: &str,
+        udfs: &str,
+        (first_query, first_error): (String, ClientError),
+        max_rows: Option<u32>,
+        token_usage: &mut TokenUsage,
+    ) -> Result<(String, QueryOutput, u32), HealFailure> {
+        let code = *first_error.code();
+        let mut message = format!("Query execution failed: {first_error}");
+        let mut retry_request = chat_request.clone();
+        let (mut failed_query, mut error) = (first_query, first_error);
+        let mut attempts = 0;
+        let last_error = loop {
+            if attempts == self.self_heal_attempts {
+                break None;
+            }
+            attempts += 1;
+            self.log(LogLevel::Warn, Some(PipelineStage::QueryExecution), || {
+                format!(
+                    "Query failed, regenerating it (attempt {attempts} of {}): {error}",
+                    self.self_heal_attempts
+                )
+            });
+            retry_request.messages.push(ChatMessage {
+                role: ChatRole::Assistant,
+                content: failed_query.clone(),
+            });
+            retry_request.messages.push(ChatMessage {
+                role: ChatRole::User,
+                content: format!(
+                    "The previous query failed with error: {error}. Please generate a corrected Cypher query."
+                ),
+            });
+
+            let healed_query = match self
+                .generate_query(llm, &retry_request, schema, udfs, token_usage)
+                .await
+                .map_err(|e| ClientError::new(ErrorCode::QueryGeneration, e.to_string()))
+                .and_then(|query| {
+                    self.check_read_only(&query)?;
+                    self.check_strict(&schema_warnings(&query, schema))?;
+                    Ok(query)
+                }) {
+                Ok(query) => query,
+                Err(e) => break Some(e),
+            };
+            match self
+                .execute_query(graph_name, &healed_query, max_rows, trace_id)
+                .await
+            {
+                Ok(result) => return Ok((healed_query, result, attempts)),
+                Err(e) if *e.code() == ErrorCode::QueryExecution => {
+                    (failed_query, error) = (healed_query, e);
+                }
+                Err(e) => break Some(e),
+            }
+        };
+
+        if attempts > 0 {
+            let last_error = last_error.as_ref().unwrap_or(&error);
+            if attempts == 1 {
+                message.push_str(&format!(". Self-healing also failed: {last_error}"));
+            } else {
+                message.push_str(&format!(
+                    ". Self-healing also failed after {attempts} attempts: {last_error}"
+                ));
+            }
+        }
+        Err(HealFailure {
+            code,
+            message,
+            execution_error: error.server_message().map(str::to_string),
+            failed_query,
+            attempts,
+        })
+    }
+
+    /// `chat_request` as the answer step gets it: with `answer_language`, a system message asking
+    /// for the answer in that language goes first. Query generation never sees it.
+    fn answer_request(&self, chat_request: &ChatRequest) -> ChatRequest {
+        let Some(language) = &self.answer_language else {
+            return chat_request.clone();
+        };
+        let instruction = ChatMessage {
+            role: ChatRole::System,
+            content: format!(
+                "Write the answer in this language: {language}. Use it whatever language the \
+                 question or the data is in, but keep the final CONFIDENCE line exactly as \
+                 specified."
+            ),
+        };
+        ChatRequest {
+            messages: std::iter::once(instruction)
+                .chain(chat_request.messages.iter().cloned())
+                .collect(),
+        }
+    }
+
+    /// Generates the natural-language answer, streaming it into `on_chunk` when given.
+    async fn generate_answer(
+        &self,
+        llm: &Llm<'_>,
+        chat_request: &ChatRequest,
+        cypher_query: &str,
+        cypher_result: &str,
+        token_usage: &mut TokenUsage,
+        on_chunk: Option<ChunkSink<'_>>,
+    ) -> Result<(String, Option<u8>), BoxError> {
+        let Some(on_chunk) = on_chunk else {
+            return self
+                .generate_whole_answer(llm, chat_request, cypher_query, cypher_result, token_usage)
+                .await;
+        };
+        if llm.structured {
+            // A structured answer is only usable once whole: deliver it as one chunk.
+            let (answer, confidence) = self
+                .generate_whole_answer(llm, chat_request, cypher_query, cypher_result, token_usage)
+                .await?;
+            if !answer.is_empty() {
+                on_chunk(answer.clone());
+            }
+            return Ok((answer, confidence));
+        }
+
+        let request = answer_chat_request(chat_request, cypher_query, cypher_result);
+        let options = ChatOptions::default().with_capture_usage(true);
+        let mut stream = match llm
+            .client
+            .exec_chat_stream(llm.model, request, Some(&options))
+            .await
+        {
+            Ok(response) => response.stream,
+            Err(_) => {
+                // Backend cannot stream: deliver the whole answer as one chunk.
+                let (answer, confidence) = self
+                    .generate_whole_answer(
+                        llm,
+                        chat_request,
+                        cypher_query,
+                        cypher_result,
+                        token_usage,
+                    )
+                    .await?;
+                if !answer.is_empty() {
+                    on_chunk(answer.clone());
+                }
+                return Ok((answer, confidence));
+            }
+        };
+
+        let mut text = String::new();
+        let mut emitted = 0;
+        while let Some(event) = stream.next().await {
+            match event.map_err(|e| format!("Chat request failed: {e}"))? {
+                ChatStreamEvent::Chunk(chunk) => {
+                    text.push_str(&chunk.content);
+                    // Hold back anything that may turn out to be the trailing confidence marker.
+                    let streamable = streamable_len(&text);
+                    if streamable > emitted {
+                        on_chunk(text[emitted..streamable].to_string());
+                        emitted = streamable;
+                    }
+                }
+                ChatStreamEvent::End(end) => {
+                    if let Some(usage) = end.captured_usage {
+                        token_usage.add_genai_usage(&usage);
+                        llm.cache_usage.add(&usage);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let (answer, confidence) = parse_answer_confidence(&text);
+        if answer.len() > emitted && answer.is_char_boundary(emitted) {
+            on_chunk(answer[emitted..].to_string());
+        }
+        Ok((answer, confidence))
+    }
+}
+
+/// A failed query that self-healing could not fix.
+struct HealFailure {
+    /// Code of the original failure; healing only retries it.
+    code: ErrorCode,
+    message: String,
+    /// The last query FalkorDB rejected.
+    failed_query: String,
+    /// FalkorDB's message for `failed_query`.
+    execution_error: Option<String>,
+    /// Times the query was regenerated.
+    attempts: u32,
+}
+
+/// Binding-level details of a run, attached to whichever response it ends with.
+#[derive(Default)]
+struct RunDetails {
+    /// Model name the LLM calls were sent with, once resolved.
+    model_used: Option<String>,
+    /// Structured result of the executed query, once it ran.
+    result_set: Option<CypherResultSet>,
+    /// Row counts by value of the `groupBy` column, once the query ran.
+    groups: Option<Vec<ResultGroup>>,
+    /// The executed query returned more rows than were kept.
+    truncated: bool,
+    /// Durations of the stages run so far.
+    timings: StageTimings,
+    /// FalkorDB's plan for the generated query, when one was requested.
+    query_plan: Option<String>,
+    /// The query-generation prompt, when the client is in debug mode.
+    prompt: Option<String>,
+    /// Node labels kept in the prompt by schema pruning, when the client is in debug mode.
+    schema_labels: Option<Vec<String>>,
+    /// Labels and relationship types the query names that the schema lacks.
+    warnings: Vec<String>,
+    /// The model's rating of the query, when the client asks for one.
+    query_confidence: Option<f64>,
+    /// FalkorDB's message for the query it rejected.
+    execution_error: Option<String>,
+    /// The model's reply to query generation, when it held no query.
+    model_reply: Option<String>,
+    /// Times self-healing regenerated the query, once it was executed.
+    self_heal_attempts: Option<u32>,
+    /// Where the next page of a paged run's rows starts.
+    cursor: Option<String>,
+    /// How `cypher_result` is rendered from `result_set`.
+    result_format: ResultFormat,
+    /// Identifier tagged onto the queries the run executes.
+    trace_id: String,
+    /// Prompt tokens the run's LLM calls read from or wrote to the provider's cache.
+    cache_usage: Arc<CacheUsage>,
+}
+
+impl RunDetails {
+    /// Converts a library response into the JS response, filling in the run's details. A
+    /// generated `CYPHER name=value ...` header moves from the query into `parameters`.
+    fn response(&self, response: TextToCypherResponse) -> crate::TextToCypherResponse {
+        let mut response = crate::TextToCypherResponse::from(response);
+        if let Some((parameters, body)) = response
+            .cypher_query
+            .as_deref()
+            .and_then(cypher::split_parameters)
+        {
+            response.cypher_query = Some(body.to_string());
+            response.parameters = Some(parameters);
+        }
+        response.query_kind = response
+            .cypher_query
+            .as_deref()
+            .map(|query| cypher::query_kind(query).to_string());
+        response.model_used = self.model_used.clone();
+        if let Some(rendered) = self
+            .result_set
+            .as_ref()
+            .and_then(|result_set| result_set.render(self.result_format))
+        {
+            response.cypher_result = Some(rendered);
+        }
+        response.result_set = self.result_set.clone();
+        response.groups = self.groups.clone();
+        response.row_count = self
+            .result_set
+            .as_ref()
+            .map(|result_set| u32::try_from(result_set.rows.len()).unwrap_or(u32::MAX));
+        response.truncated = self.truncated;
+        response.timings = Some(self.timings.clone());
+        response.query_plan = self.query_plan.clone();
+        response.prompt = self.prompt.clone();
+        response.schema_labels = self.schema_labels.clone();
+        response.warnings = self.warnings.clone();
+        response.query_confidence = self.query_confidence;
+        response.execution_error = self.execution_error.clone();
+        response.model_reply = self.model_reply.clone();
+        response.self_heal_attempts = self.self_heal_attempts;
+        response.cursor = self.cursor.clone();
+        response.trace_id = Some(self.trace_id.clone());
+        response.request_id = log::request_id();
+        if let Some(token_usage) = response.token_usage.as_mut() {
+            self.cache_usage.fill(token_usage);
+        }
+        response
+    }
+
+    /// Turns an error-status response into a [`ClientError`] that carries it.
+    fn failure(&self, code: ErrorCode, response: TextToCypherResponse) -> ClientError {
+        let message = response
+            .error
+            .clone()
+            .unwrap_or_else(|| "Unknown error".to_string());
+        ClientError::new(code, message).with_response(self.response(response))
+    }
+}
+
+/// Approximates the input tokens of a request made of `messages` for a model of
+/// `adapter_kind`, without its tokenizer: about 4 characters per token (3.5 for Claude, whose
+/// tokenizer splits text finer), plus the few tokens each message's framing costs.
+fn approximate_tokens<'a>(
+    adapter_kind: AdapterKind,
+    messages: impl Iterator<Item = &'a str>,
+) -> u32 {
+    const TOKENS_PER_MESSAGE: f64 = 4.0;
+    let chars_per_token = match adapter_kind {
+        AdapterKind::Anthropic => 3.5,
+        _ => 4.0,
+    };
+    let tokens: f64 = messages
+        .map(|message| message.chars().count() as f64 / chars_per_token + TOKENS_PER_MESSAGE)
+        .sum();
+    tokens.ceil() as u32
+}
+
+/// A `SchemaDiscovery` error for a failed metadata read, keeping FalkorDB's reply when FalkorDB
+/// rejected the read (see [`Pipeline::missing_graph_or`]).
+fn discovery_error(context: &str, e: FalkorDBError) -> ClientError {
+    match e {
+        FalkorDBError::RedisError(reply) => {
+            ClientError::new(ErrorCode::SchemaDiscovery, format!("{context}: {reply}"))
+                .with_server_message(reply)
+        }
+        e => ClientError::new(ErrorCode::SchemaDiscovery, format!("{context}: {e}"))
+            .with_failure_category(FailureCategory::of_falkordb(&e)),
+    }
+}
+
+/// The `GraphNotFound` error for `graph_name`.
+fn graph_not_found(graph_name: &str) -> ClientError {
+    ClientError::new(
+        ErrorCode::GraphNotFound,
+        format!("Graph '{graph_name}' does not exist"),
+    )
+}
+
+/// The error for a call that would discover a schema while `skipDiscovery` is set.
+fn discovery_disabled() -> ClientError {
+    ClientError::new(
+        ErrorCode::InvalidArgument,
+        "Schema discovery is disabled by skipDiscovery: pass the graph's schema",
+    )
+}
+
+/// The `SchemaTooLarge` error for a `schema` over `max_bytes`, with `advice` on getting under it.
+fn schema_too_large(schema: &str, max_bytes: usize, advice: &str) -> ClientError {
+    ClientError::new(
+        ErrorCode::SchemaTooLarge,
+        format!(
+            "The schema is {} bytes, over the maxSchemaBytes limit of {max_bytes}; {advice}",
+            schema.len()
+        ),
+    )
+}
+
+/// Describes each node label and relationship type `query` names that `schema` does not list. A
+/// schema without `entities` or `relations` lists, e.g. a hand-written one, is not checked.
+fn schema_warnings(query: &str, schema: &str) -> Vec<String> {
+    let Ok(schema) = serde_json::from_str::<Value>(schema) else {
+        return Vec::new();
+    };
+    let known = |key: &str| {
+        schema.get(key)?.as_array().map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.get("label")?.as_str())
+                .collect::<Vec<_>>()
+        })
+    };
+    let references = cypher::references(query);
+    let mut warnings = Vec::new();
+    if let Some(labels) = known("entities") {
+        for label in references
+            .labels
+            .iter()
+            .filter(|label| !labels.contains(label))
+        {
+            warnings.push(format!("Node label `{label}` is not in the graph schema"));
+        }
+    }
+    if let Some(types) = known("relations") {
+        for relationship_type in references
+            .relationship_types
+            .iter()
+            .filter(|relationship_type| !types.contains(relationship_type))
+        {
+            warnings.push(format!(
+                "Relationship type `{relationship_type}` is not in the graph schema"
+            ));
+        }
+    }
+    warnings
+}
+
+/// Reads the rating a model replied to [`QUERY_RATING_PROMPT`] with: the first number in the
+/// reply, when it is between 0 and 1.
+fn parse_query_rating(reply: &str) -> Option<f64> {
+    let start = reply.find(|c: char| c.is_ascii_digit() || c == '.')?;
+    let number = &reply[start..];
+    let end = number
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(number.len());
+    let rating = number[..end].trim_end_matches('.').parse::<f64>().ok()?;
+    (0.0..=1.0).contains(&rating).then_some(rating)
+}
+
+/// Rejects a graph name that is empty or contains control characters. Graph names are sent to
+/// FalkorDB as separate command arguments, never spliced into a command or query, so any other
+/// character (quotes included) reaches FalkorDB as part of the name and cannot change the command.
+pub(crate) fn check_graph_name(graph_name: &str) -> Result<(), ClientError> {
+    if graph_name.is_empty() {
+        return Err(ClientError::new(
+            ErrorCode::InvalidArgument,
+            "Invalid graph name: must not be empty",
+        ));
+    }
+    if graph_name.chars().any(char::is_control) {
+        return Err(ClientError::new(
+            ErrorCode::InvalidArgument,
+            format!("Invalid graph name {graph_name:?}: control characters are not allowed"),
+        ));
+    }
+    Ok(())
+}
+
+/// FalkorDB's error reply as it sent it: the error code (its first word) and the rest.
+fn server_message(error: &redis::RedisError) -> String {
+    match (error.code(), error.detail()) {
+        (Some(code), Some(detail)) => format!("{code} {detail}"),
+        _ => error.to_string(),
+    }
+}
+
+/// Formats the `selected` columns of `records`, in the order given, for the answer prompt.
+fn format_columns(
+    columns: &[String],
+    records: &[Vec<FalkorValue>],
+    selected: &[String],
+) -> Result<String, ClientError> {
+    let indices = selected
+        .iter()
+        .map(|name| {
+            columns
+                .iter()
+                .position(|column| column == name)
+                .ok_or_else(|| {
+                    ClientError::new(
+                        ErrorCode::InvalidArgument,
+                        format!(
+                            "Invalid answerColumns: the query returned no column named '{name}' \
+                         (its columns are {})",
+                            columns.join(", ")
+                        ),
+                    )
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let records = records
+        .iter()
+        .map(|record| {
+            let value = |i: usize| record.get(i).cloned().unwrap_or(FalkorValue::None);
+            indices.iter().map(|&i| value(i)).collect()
+        })
+        .collect::<Vec<_>>();
+    Ok(format_query_records(&records))
+}
+
+/// Converts parameters read from a query's `CYPHER` header into FalkorDB values.
+fn falkor_parameters(parameters: &Map<String, Value>) -> HashMap<String, FalkorValue> {
+    parameters
+        .iter()
+        .map(|(name, value)| (name.clone(), falkor_value(value)))
+        .collect()
+}
+
+fn falkor_value(value: &Value) -> FalkorValue {
+    match value {
+        Value::Null => FalkorValue::None,
+        Value::Bool(value) => FalkorValue::Bool(*value),
+        Value::Number(number) => number.as_i64().map_or_else(
+            || FalkorValue::F64(number.as_f64().unwrap_or_default()),
+            FalkorValue::I64,
+        ),
+        Value::String(value) => FalkorValue::String(value.clone()),
+        Value::Array(items) => FalkorValue::Array(items.iter().map(falkor_value).collect()),
+        Value::Object(entries) => FalkorValue::Map(falkor_parameters(entries)),
+    }
+}
+
+/// A random (version 4) UUID identifying a call that was not given a trace id, which also
+/// stands in for the trace id.
+pub(crate) fn new_request_id() -> String {
+    let bits = fastrand::u128(..) & !(0xf << 76 | 0x3 << 62) | (0x4 << 76 | 0x2 << 62);
+    let hex = format!("{bits:032x}");
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// Trims an endpoint override and gives it the trailing slash genai expects; blank means unset.
+fn normalize_llm_endpoint(endpoint: &str) -> Option<String> {
+    let endpoint = endpoint.trim();
+    if endpoint.is_empty() {
+        return None;
+    }
+    Some(if endpoint.ends_with('/') {
+        endpoint.to_string()
+    } else {
+        format!("{endpoint}/")
+    })
+}
+
+/// Builds the answer-generation request the same way the library's answer step does.
+fn answer_chat_request(
+    chat_request: &ChatRequest,
+    cypher_query: &str,
+    cypher_result: &str,
+) -> genai::chat::ChatRequest {
+    let last = chat_request.messages.len().saturating_sub(1);
+    let messages = chat_request
+        .messages
+        .iter()
+        .enumerate()
+        .map(|(index, message)| match message.role {
+            ChatRole::User if index == last => {
+                genai::chat::ChatMessage::user(TemplateEngine::render_last_request_prompt(
+                    &message.content,
+                    cypher_query,
+                    cypher_result,
+                ))
+            }
+            ChatRole::User => genai::chat::ChatMessage::user(message.content.clone()),
+            ChatRole::Assistant => genai::chat::ChatMessage::assistant(message.content.clone()),
+            ChatRole::System => genai::chat::ChatMessage::system(message.content.clone()),
+        });
+    genai::chat::ChatRequest::from_messages(messages.collect())
+}
+
+/// Length of the prefix of a partially streamed answer that is safe to hand out: everything before
+/// a (possibly still incomplete) trailing confidence marker, without trailing whitespace.
+fn streamable_len(text: &str) -> usize {
+    let upper = text.to_ascii_uppercase();
+    let end = upper.find(CONFIDENCE_MARKER).unwrap_or_else(|| {
+        (1..CONFIDENCE_MARKER.len())
+            .rev()
+            .find(|&len| upper.ends_with(&CONFIDENCE_MARKER[..len]))
+            .map_or(text.len(), |len| text.len() - len)
+    });
+    text[..end].trim_end().len()
+}
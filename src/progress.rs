@@ -0,0 +1,87 @@
+//! Pipeline progress events
+//!
+//! A call to `textToCypher` can take several seconds, most of it waiting on FalkorDB or the model.
+//! Progress events tell the caller which stage is running so a UI can show more than a spinner.
+
+use napi_derive::napi;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// A stage of the text-to-cypher pipeline, as reported to progress callbacks
+#[napi(string_enum = "UPPER_SNAKE")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipelineStage {
+    /// Discovering the graph schema (skipped when a schema is supplied)
+    SchemaDiscovery,
+    /// Generating the Cypher query
+    QueryGeneration,
+    /// Executing the query, including self-healing retries
+    QueryExecution,
+    /// Generating the natural-language answer
+    AnswerGeneration,
+}
+
+/// Whether a progress event marks the start or the end of a stage
+#[napi(string_enum = "UPPER_SNAKE")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StagePhase {
+    Started,
+    /// The stage succeeded; a failed stage rejects the call instead
+    Completed,
+}
+
+/// A pipeline stage starting or completing
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct ProgressEvent {
+    pub stage: PipelineStage,
+    pub phase: StagePhase,
+    /// When the event happened, in milliseconds since the Unix epoch (like `Date.now()`)
+    pub timestamp: f64,
+}
+
+impl ProgressEvent {
+    pub(crate) fn now(stage: PipelineStage, phase: StagePhase) -> Self {
+        Self {
+            stage,
+            phase,
+            timestamp: unix_millis(),
+        }
+    }
+}
+
+/// The current time in milliseconds since the Unix epoch, like `Date.now()`.
+pub(crate) fn unix_millis() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |elapsed| elapsed.as_millis() as f64)
+}
+
+/// How long each pipeline stage took, in milliseconds
+///
+/// Durations come from a monotonic clock, so they are unaffected by system clock changes. A stage
+/// that did not run is omitted; a stage that failed reports the time spent until it failed.
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct StageTimings {
+    /// Discovering the graph schema; omitted when a schema was supplied
+    pub schema_discovery_ms: Option<f64>,
+    /// Generating the Cypher query
+    pub query_generation_ms: Option<f64>,
+    /// Executing the query, including self-healing retries
+    pub query_execution_ms: Option<f64>,
+    /// Generating the natural-language answer
+    pub answer_generation_ms: Option<f64>,
+}
+
+impl StageTimings {
+    /// Records the time elapsed since `started` as the duration of `stage`.
+    pub(crate) fn record(&mut self, stage: PipelineStage, started: Instant) {
+        let elapsed = Some(started.elapsed().as_secs_f64() * 1000.0);
+        match stage {
+            PipelineStage::SchemaDiscovery => self.schema_discovery_ms = elapsed,
+            PipelineStage::QueryGeneration => self.query_generation_ms = elapsed,
+            PipelineStage::QueryExecution => self.query_execution_ms = elapsed,
+            PipelineStage::AnswerGeneration => self.answer_generation_ms = elapsed,
+        }
+    }
+}
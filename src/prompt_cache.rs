@@ -0,0 +1,49 @@
+//! Prompt caching
+//!
+//! Query generation sends the same long system prompt, holding the graph's schema, with every
+//! question about a graph. Anthropic caches such a prefix only when the request marks it, and
+//! then bills reads of it at a fraction of the input price. With `promptCaching` set, the client
+//! marks the system prompt for those providers ([`supported`]); others either cache long
+//! prefixes on their own (OpenAI, Gemini) or not at all, and get the request unchanged.
+//! [`CacheUsage`] tallies the cache reads and writes providers report, for `tokenUsage`.
+
+use genai::chat::Usage;
+use std::sync::atomic::{AtomicU64, Ordering};
+use text_to_cypher::AdapterKind;
+
+/// Whether `adapter_kind`'s API caches a prompt prefix only when the request marks it, which
+/// genai does for system messages carrying a cache control.
+pub(crate) fn supported(adapter_kind: AdapterKind) -> bool {
+    adapter_kind == AdapterKind::Anthropic
+}
+
+/// Prompt tokens read from and written to providers' caches by a run's LLM calls.
+#[derive(Debug, Default)]
+pub(crate) struct CacheUsage {
+    read: AtomicU64,
+    written: AtomicU64,
+}
+
+impl CacheUsage {
+    /// Adds the cache counts of one LLM call; providers that report none add nothing.
+    pub(crate) fn add(&self, usage: &Usage) {
+        let Some(details) = &usage.prompt_tokens_details else {
+            return;
+        };
+        let count = |tokens: Option<i32>| u64::try_from(tokens.unwrap_or(0)).unwrap_or(0);
+        self.read
+            .fetch_add(count(details.cached_tokens), Ordering::Relaxed);
+        self.written
+            .fetch_add(count(details.cache_creation_tokens), Ordering::Relaxed);
+    }
+
+    /// Sets the cache fields of `usage`, leaving unset the ones no call reported tokens for.
+    pub(crate) fn fill(&self, usage: &mut crate::TokenUsage) {
+        let tokens = |counter: &AtomicU64| match counter.load(Ordering::Relaxed) {
+            0 => None,
+            tokens => Some(tokens as f64),
+        };
+        usage.cached_prompt_tokens = tokens(&self.read);
+        usage.cache_creation_tokens = tokens(&self.written);
+    }
+}
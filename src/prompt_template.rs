@@ -0,0 +1,116 @@
+//! Custom query-generation prompts
+//!
+//! The `promptTemplate` option replaces the library's query-generation prompt with the caller's
+//! own text, so prompts can be tuned without rebuilding. The template is checked for its
+//! placeholders when the client is constructed, and rendered in a single pass, so a value such as
+//! a question that itself contains `{{schema}}` is inserted as written.
+
+use crate::error::{ClientError, ErrorCode};
+use crate::CypherExample;
+
+/// Placeholders a template must contain.
+const REQUIRED: &[&str] = &["schema", "question"];
+
+/// Every placeholder a template may contain.
+const KNOWN: &[&str] = &["schema", "question", "examples", "skills", "udfs"];
+
+/// What the placeholders of a [`PromptTemplate`] are filled with.
+pub(crate) struct PromptValues<'a> {
+    pub(crate) schema: &'a str,
+    pub(crate) question: &'a str,
+    pub(crate) examples: &'a [CypherExample],
+    pub(crate) skills: &'a str,
+    pub(crate) udfs: &'a str,
+}
+
+/// A validated `promptTemplate`.
+#[derive(Debug, Clone)]
+pub(crate) struct PromptTemplate {
+    template: String,
+}
+
+impl PromptTemplate {
+    /// Checks that `template` contains every required placeholder and no unknown ones.
+    pub(crate) fn parse(template: String) -> Result<Self, ClientError> {
+        let invalid = |message: String| {
+            ClientError::new(
+                ErrorCode::InvalidArgument,
+                format!("Invalid promptTemplate: {message}"),
+            )
+        };
+        let names: Vec<&str> = placeholders(&template).map(|(_, name, _)| name).collect();
+        if let Some(unknown) = names.iter().find(|name| !KNOWN.contains(name)) {
+            return Err(invalid(format!(
+                "unknown placeholder {{{{{unknown}}}}}. Available: {}",
+                list(KNOWN)
+            )));
+        }
+        if let Some(missing) = REQUIRED.iter().find(|required| !names.contains(required)) {
+            return Err(invalid(format!(
+                "missing the {{{{{missing}}}}} placeholder. Required: {}",
+                list(REQUIRED)
+            )));
+        }
+        Ok(Self { template })
+    }
+
+    /// Whether the examples go into the prompt itself rather than ahead of it as conversation
+    /// turns.
+    pub(crate) fn has_examples(&self) -> bool {
+        placeholders(&self.template).any(|(_, name, _)| name == "examples")
+    }
+
+    pub(crate) fn render(&self, values: &PromptValues<'_>) -> String {
+        let mut rendered = String::with_capacity(self.template.len() + values.schema.len());
+        let mut copied = 0;
+        for (start, name, end) in placeholders(&self.template) {
+            rendered.push_str(&self.template[copied..start]);
+            match name {
+                "schema" => rendered.push_str(values.schema),
+                "question" => rendered.push_str(values.question),
+                "examples" => rendered.push_str(&render_examples(values.examples)),
+                "skills" => rendered.push_str(values.skills),
+                "udfs" => rendered.push_str(values.udfs),
+                _ => rendered.push_str(&self.template[start..end]),
+            }
+            copied = end;
+        }
+        rendered.push_str(&self.template[copied..]);
+        rendered
+    }
+}
+
+/// The `{{name}}` placeholders in `template`, as start offset, trimmed name, and end offset.
+fn placeholders(template: &str) -> impl Iterator<Item = (usize, &str, usize)> {
+    let mut position = 0;
+    std::iter::from_fn(move || {
+        let start = position + template[position..].find("{{")?;
+        let length = template[start + 2..].find("}}")?;
+        let end = start + 2 + length + 2;
+        position = end;
+        Some((start, template[start + 2..end - 2].trim(), end))
+    })
+}
+
+/// The examples as `Question:` and `Cypher:` pairs separated by blank lines; empty without any.
+fn render_examples(examples: &[CypherExample]) -> String {
+    examples
+        .iter()
+        .map(|example| {
+            format!(
+                "Question: {}\nCypher: {}",
+                example.question.trim(),
+                example.cypher.trim()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn list(names: &[&str]) -> String {
+    names
+        .iter()
+        .map(|name| format!("{{{{{name}}}}}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,144 @@
+//! Property redaction
+//!
+//! Graphs holding personal data often have a few properties (an email, a national id) whose
+//! values must not reach the model provider or debug output, even though queries may still
+//! filter on them. With `redactProperties` set, [`Redaction::records`] masks those properties'
+//! values in the rows the answer prompt is built from, and [`Redaction::schema`] removes them
+//! from the example values and samples schema discovery collects, which the query-generation
+//! prompt (and so the debug `prompt`) embeds. Queries run unchanged, and the rows a call resolves
+//! with are the caller's own data, so `cypherResult` and `resultSet` are left as FalkorDB returned
+//! them.
+
+use falkordb::FalkorValue;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// What a redacted value is replaced with.
+pub(crate) const MASK: &str = "[REDACTED]";
+
+/// The property names whose values are masked.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Redaction {
+    properties: HashSet<String>,
+}
+
+impl Redaction {
+    pub(crate) fn new(properties: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            properties: properties.into_iter().collect(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+    }
+
+    /// `records` with the listed properties masked: whole columns named after one (`ssn`, or
+    /// `p.ssn` as FalkorDB names the column of `RETURN p.ssn`), and matching keys of the nodes,
+    /// relationships, paths, and maps in other columns. A value returned under another alias
+    /// (`RETURN p.ssn AS id`) cannot be told apart from other values and is not masked.
+    pub(crate) fn records(
+        &self,
+        columns: &[String],
+        records: &[Vec<FalkorValue>],
+    ) -> Vec<Vec<FalkorValue>> {
+        records
+            .iter()
+            .map(|record| {
+                record
+                    .iter()
+                    .enumerate()
+                    .map(|(index, value)| match columns.get(index) {
+                        Some(column) if self.masks_column(column) => mask(),
+                        _ => self.value(value.clone()),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Removes the `examples` of the listed properties' attributes from a discovered `schema`
+    /// and masks their values in its `samples`, keeping the attributes themselves so the model
+    /// can still write queries using them.
+    pub(crate) fn schema(&self, schema: &mut Value) {
+        for items in ["entities", "relations"] {
+            let Some(items) = schema.get_mut(items).and_then(Value::as_array_mut) else {
+                continue;
+            };
+            for item in items {
+                let attributes = item.get_mut("attributes").and_then(Value::as_array_mut);
+                for attribute in attributes.into_iter().flatten() {
+                    let name = attribute.get("name").and_then(Value::as_str);
+                    if name.is_some_and(|name| self.properties.contains(name)) {
+                        if let Some(attribute) = attribute.as_object_mut() {
+                            attribute.remove("examples");
+                        }
+                    }
+                }
+                let samples = item.get_mut("samples").and_then(Value::as_array_mut);
+                for sample in samples
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_object_mut)
+                {
+                    for (name, value) in sample.iter_mut() {
+                        if self.properties.contains(name) {
+                            *value = Value::String(MASK.to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn masks_column(&self, column: &str) -> bool {
+        let property = column
+            .rsplit_once('.')
+            .map_or(column, |(_, property)| property);
+        self.properties.contains(property)
+    }
+
+    fn value(&self, value: FalkorValue) -> FalkorValue {
+        match value {
+            FalkorValue::Node(mut node) => {
+                self.properties(&mut node.properties);
+                FalkorValue::Node(node)
+            }
+            FalkorValue::Edge(mut edge) => {
+                self.properties(&mut edge.properties);
+                FalkorValue::Edge(edge)
+            }
+            FalkorValue::Path(mut path) => {
+                for node in &mut path.nodes {
+                    self.properties(&mut node.properties);
+                }
+                for edge in &mut path.relationships {
+                    self.properties(&mut edge.properties);
+                }
+                FalkorValue::Path(path)
+            }
+            FalkorValue::Map(mut map) => {
+                self.properties(&mut map);
+                FalkorValue::Map(map)
+            }
+            FalkorValue::Array(items) => {
+                FalkorValue::Array(items.into_iter().map(|item| self.value(item)).collect())
+            }
+            value => value,
+        }
+    }
+
+    fn properties(&self, properties: &mut HashMap<String, FalkorValue>) {
+        for (name, value) in properties.iter_mut() {
+            *value = if self.properties.contains(name) {
+                mask()
+            } else {
+                self.value(std::mem::replace(value, FalkorValue::None))
+            };
+        }
+    }
+}
+
+fn mask() -> FalkorValue {
+    FalkorValue::String(MASK.to_string())
+}
@@ -0,0 +1,135 @@
+//! Result cache for repeated questions
+//!
+//! Bursty workloads ask the same question about the same graph many times in a row, and each
+//! call runs the whole pipeline again: schema discovery, two LLM calls, and the query. With
+//! `resultCacheTtlMs` set, [`ResultCache`] keeps `textToCypher`'s successful responses for a fixed
+//! time, shared by every call on the client, and evicts the least recently used one when full.
+
+use crate::result_set::ResultFormat;
+use crate::TextToCypherResponse;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Entries kept when `resultCacheSize` is not set.
+pub(crate) const DEFAULT_CAPACITY: u32 = 100;
+
+/// What makes two `textToCypher` calls interchangeable: the graph, the question up to
+/// whitespace, the model, and every argument that shapes the response.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ResultKey {
+    pub(crate) graph: String,
+    pub(crate) question: String,
+    pub(crate) model: String,
+    pub(crate) schema: Option<String>,
+    pub(crate) max_rows: Option<u32>,
+    pub(crate) generate_answer: bool,
+    pub(crate) answer_columns: Option<Vec<String>>,
+    pub(crate) result_format: ResultFormat,
+    pub(crate) group_by: Option<String>,
+    /// A hash of the per-call `apiKey`, so a response fetched with one key is never handed to a
+    /// caller with another; `None` when the call uses the client's key.
+    pub(crate) api_key: Option<u64>,
+}
+
+impl ResultKey {
+    /// Collapses the whitespace in `question`, so spacing alone does not split an entry. Case is
+    /// kept: the model copies string literals from the question into the query, and those are
+    /// matched case-sensitively.
+    pub(crate) fn normalize_question(question: &str) -> String {
+        question.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// Hashes a per-call API key for [`ResultKey::api_key`], so the key itself is not kept.
+    pub(crate) fn hash_api_key(api_key: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        api_key.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+struct Entry {
+    stored: Instant,
+    /// Value of [`Entries::clock`] when the entry was last stored or returned.
+    used: u64,
+    response: TextToCypherResponse,
+}
+
+#[derive(Default)]
+struct Entries {
+    by_key: HashMap<ResultKey, Entry>,
+    /// Ticks on every use, ordering entries by recency.
+    clock: u64,
+}
+
+/// Successful responses by [`ResultKey`], each valid for `ttl` after it was stored, holding at
+/// most `capacity` of them.
+pub(crate) struct ResultCache {
+    ttl: Duration,
+    capacity: usize,
+    entries: Mutex<Entries>,
+}
+
+impl ResultCache {
+    pub(crate) fn new(ttl: Duration, capacity: u32) -> Self {
+        Self {
+            ttl,
+            capacity: capacity as usize,
+            entries: Mutex::new(Entries::default()),
+        }
+    }
+
+    /// The response cached for `key`, unless there is none or it has expired.
+    pub(crate) fn get(&self, key: &ResultKey) -> Option<TextToCypherResponse> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.clock += 1;
+        let clock = entries.clock;
+        match entries.by_key.get_mut(key) {
+            Some(entry) if entry.stored.elapsed() < self.ttl => {
+                entry.used = clock;
+                Some(entry.response.clone())
+            }
+            Some(_) => {
+                entries.by_key.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Stores `response` for `key`, first evicting expired entries and then, when the cache is
+    /// still full, the least recently used one.
+    pub(crate) fn insert(&self, key: ResultKey, response: TextToCypherResponse) {
+        let mut entries = self.entries.lock().unwrap();
+        if !entries.by_key.contains_key(&key) && entries.by_key.len() >= self.capacity {
+            entries
+                .by_key
+                .retain(|_, entry| entry.stored.elapsed() < self.ttl);
+            if entries.by_key.len() >= self.capacity {
+                let oldest = entries
+                    .by_key
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.used)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    entries.by_key.remove(&oldest);
+                }
+            }
+        }
+        entries.clock += 1;
+        let used = entries.clock;
+        entries.by_key.insert(
+            key,
+            Entry {
+                stored: Instant::now(),
+                used,
+                response,
+            },
+        );
+    }
+
+    pub(crate) fn clear(&self) {
+        self.entries.lock().unwrap().by_key.clear();
+    }
+}
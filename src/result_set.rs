@@ -0,0 +1,249 @@
+//! Structured Cypher results
+//!
+//! `cypherResult` is the text rendering the answer prompt is built from. [`CypherResultSet`] is
+//! the same result as columns and rows of JSON values, so callers can use it without parsing.
+//! A call's `resultFormat` can have `cypherResult` rendered from the result set as JSON, CSV, or
+//! NDJSON instead; the answer prompt keeps using the text rendering. Its `groupBy` counts the rows
+//! by one column's value, without aggregating in the query.
+
+use crate::error::{ClientError, ErrorCode};
+use falkordb::{Edge, FalkorValue, Node, Path, Point};
+use napi_derive::napi;
+use serde_json::{json, Map, Number, Value};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+/// Tabular result of an executed Cypher query
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct CypherResultSet {
+    /// Column names, in `RETURN` order
+    pub columns: Vec<String>,
+    /// One array per record, holding one value per column. Nodes become
+    /// `{ type: "node", id, labels, properties }`, relationships
+    /// `{ type: "edge", id, relationship_type, src_node_id, dst_node_id, properties }`,
+    /// and paths `{ type: "path", nodes, relationships }`. Ids are FalkorDB's internal ids, for
+    /// follow-up queries such as `MATCH (n) WHERE id(n) = $id`; FalkorDB reuses the id of a
+    /// deleted entity, so an id kept for long may name a different one.
+    pub rows: Vec<Vec<Value>>,
+}
+
+/// Rows of a result set sharing one value of the `groupBy` column
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct ResultGroup {
+    /// The column's value, as it appears in `resultSet`
+    pub value: Value,
+    /// Rows holding the value
+    pub count: u32,
+}
+
+/// How `cypherResult` is rendered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub(crate) enum ResultFormat {
+    /// The text the answer prompt is built from.
+    #[default]
+    Text,
+    /// A JSON array with one object per row, keyed by column.
+    Json,
+    /// A header row of column names, then one line per row.
+    Csv,
+    /// One JSON object per row, keyed by column, one per line.
+    Ndjson,
+}
+
+impl ResultFormat {
+    /// Reads a `resultFormat` argument; `None` is the text rendering.
+    pub(crate) fn parse(format: Option<&str>) -> Result<Self, ClientError> {
+        let Some(format) = format else {
+            return Ok(Self::Text);
+        };
+        match format.trim().to_ascii_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            "ndjson" => Ok(Self::Ndjson),
+            _ => Err(ClientError::new(
+                ErrorCode::InvalidArgument,
+                format!("Invalid resultFormat: '{format}'. Must be one of text, json, csv, ndjson"),
+            )),
+        }
+    }
+}
+
+impl CypherResultSet {
+    pub(crate) fn new(columns: &[String], rows: &[Vec<FalkorValue>]) -> Self {
+        Self {
+            columns: columns.to_vec(),
+            rows: rows
+                .iter()
+                .map(|row| row.iter().map(to_json).collect())
+                .collect(),
+        }
+    }
+
+    /// Renders the rows in `format`; `None` for [`ResultFormat::Text`], which is rendered from
+    /// FalkorDB's values instead.
+    pub(crate) fn render(&self, format: ResultFormat) -> Option<String> {
+        match format {
+            ResultFormat::Text => None,
+            ResultFormat::Json => Some(format!(
+                "[{}]",
+                self.rows
+                    .iter()
+                    .map(|row| self.row_object(row))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            )),
+            ResultFormat::Ndjson => Some(
+                self.rows
+                    .iter()
+                    .map(|row| self.row_object(row) + "\n")
+                    .collect(),
+            ),
+            ResultFormat::Csv => {
+                let header = self.columns.iter().map(|column| csv_field(column));
+                let mut csv = header.collect::<Vec<_>>().join(",") + "\n";
+                for row in &self.rows {
+                    csv += &row.iter().map(csv_cell).collect::<Vec<_>>().join(",");
+                    csv += "\n";
+                }
+                Some(csv)
+            }
+        }
+    }
+
+    /// Counts the rows by their value in `column`, most frequent value first and ties in the order
+    /// the values first appear.
+    pub(crate) fn group_by(&self, column: &str) -> Result<Vec<ResultGroup>, ClientError> {
+        let index = self
+            .columns
+            .iter()
+            .position(|name| name == column)
+            .ok_or_else(|| {
+                ClientError::new(
+                    ErrorCode::InvalidArgument,
+                    format!(
+                        "Invalid groupBy: the query returned no column named '{column}' (its \
+                         columns are {})",
+                        self.columns.join(", ")
+                    ),
+                )
+            })?;
+        // Values are told apart by their JSON text, which is the same for equal values.
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        let mut groups: Vec<ResultGroup> = Vec::new();
+        for row in &self.rows {
+            let value = row.get(index).cloned().unwrap_or(Value::Null);
+            match positions.entry(value.to_string()) {
+                Entry::Occupied(entry) => groups[*entry.get()].count += 1,
+                Entry::Vacant(entry) => {
+                    entry.insert(groups.len());
+                    groups.push(ResultGroup { value, count: 1 });
+                }
+            }
+        }
+        groups.sort_by_key(|group| std::cmp::Reverse(group.count));
+        Ok(groups)
+    }
+
+    /// A row as a JSON object keyed by column, in column order.
+    fn row_object(&self, row: &[Value]) -> String {
+        let fields = self
+            .columns
+            .iter()
+            .zip(row)
+            .map(|(column, value)| format!("{}:{value}", Value::from(column.as_str())))
+            .collect::<Vec<_>>();
+        format!("{{{}}}", fields.join(","))
+    }
+}
+
+/// A CSV cell: empty for null, strings as they are, and nested values (nodes, lists, maps) as
+/// JSON.
+fn csv_cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => csv_field(s),
+        other => csv_field(&other.to_string()),
+    }
+}
+
+/// Quotes a CSV field when it holds a comma, a quote, or a line break, doubling its quotes.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Converts a FalkorDB value to JSON, keeping graph entities' labels and properties.
+pub(crate) fn to_json(value: &FalkorValue) -> Value {
+    match value {
+        FalkorValue::None => Value::Null,
+        FalkorValue::Bool(b) => Value::Bool(*b),
+        FalkorValue::I64(i) => Value::from(*i),
+        // JSON has no NaN or infinities
+        FalkorValue::F64(f) => Number::from_f64(*f).map_or(Value::Null, Value::Number),
+        FalkorValue::String(s) => Value::String(s.clone()),
+        FalkorValue::Array(values) => values.iter().map(to_json).collect(),
+        FalkorValue::Vec32(vector) => vector
+            .values
+            .iter()
+            .map(|v| Number::from_f64(f64::from(*v)).map_or(Value::Null, Value::Number))
+            .collect(),
+        FalkorValue::Map(map) => properties_json(map),
+        FalkorValue::Node(node) => node_json(node),
+        FalkorValue::Edge(edge) => edge_json(edge),
+        FalkorValue::Path(path) => path_json(path),
+        FalkorValue::Point(Point {
+            latitude,
+            longitude,
+        }) => json!({ "latitude": latitude, "longitude": longitude }),
+        FalkorValue::DateTime(v) => Value::String(v.to_string()),
+        FalkorValue::Date(v) => Value::String(v.to_string()),
+        FalkorValue::Time(v) => Value::String(v.to_string()),
+        FalkorValue::Duration(v) => Value::String(v.to_string()),
+        FalkorValue::Unparseable(s) => Value::String(s.clone()),
+        // Types added to FalkorDB after this was written
+        other => Value::String(format!("{other:?}")),
+    }
+}
+
+fn properties_json(properties: &HashMap<String, FalkorValue>) -> Value {
+    Value::Object(
+        properties
+            .iter()
+            .map(|(key, value)| (key.clone(), to_json(value)))
+            .collect::<Map<_, _>>(),
+    )
+}
+
+fn node_json(node: &Node) -> Value {
+    json!({
+        "type": "node",
+        "id": node.entity_id,
+        "labels": node.labels,
+        "properties": properties_json(&node.properties),
+    })
+}
+
+fn edge_json(edge: &Edge) -> Value {
+    json!({
+        "type": "edge",
+        "id": edge.entity_id,
+        "relationship_type": edge.relationship_type,
+        "src_node_id": edge.src_node_id,
+        "dst_node_id": edge.dst_node_id,
+        "properties": properties_json(&edge.properties),
+    })
+}
+
+fn path_json(path: &Path) -> Value {
+    json!({
+        "type": "path",
+        "nodes": path.nodes.iter().map(node_json).collect::<Vec<_>>(),
+        "relationships": path.relationships.iter().map(edge_json).collect::<Vec<_>>(),
+    })
+}
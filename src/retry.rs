@@ -0,0 +1,358 @@
+//! Automatic retries of transient failures
+//!
+//! LLM providers answer with 429/5xx under load and FalkorDB connections occasionally drop; both
+//! usually succeed a moment later. [`Retry`] re-runs such an operation with exponential backoff and
+//! full jitter, but only when its error is transient: a query the model got wrong or FalkorDB
+//! rejected fails the same way every time, so those are returned straight away. Errors are sorted
+//! by what they are, never by their text: an HTTP status or transport error kept from the model
+//! call, or the FalkorDB and redis error kinds. Opening a
+//! FalkorDB connection has a [`Retry`] of its own, for a server that is not up yet.
+//!
+//! A provider that rate-limits a request usually says how long to wait in a `Retry-After` header.
+//! [`ProviderError`] keeps that wait from the model call that failed, and the retry waits it out
+//! instead of guessing.
+//!
+//! A `retryPolicy` ([`RetryPolicy`]) replaces the single retry count with one per
+//! [`FailureCategory`], so that, say, rate limits are waited out patiently while a dropped
+//! connection is retried once. It can also retry a model reply holding no valid query, which is
+//! otherwise final.
+
+use crate::error::{ClientError, ErrorCode};
+use crate::pipeline::{BoxError, InvalidQuery, NoQuery};
+use falkordb::FalkorDBError;
+use napi_derive::napi;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Duration;
+
+/// Upper bound on any single backoff delay.
+const MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// Longest `Retry-After` waited out; a provider asking for more fails the call straight away.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// HTTP statuses of upstreams that are overloaded or down rather than rejecting the request.
+const SERVER_ERROR_STATUSES: &[u16] = &[502, 503, 504];
+
+/// The kinds of failure a [`RetryPolicy`] sets retries for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum FailureCategory {
+    /// HTTP 429.
+    RateLimit,
+    /// HTTP 502, 503, or 504.
+    ServerError,
+    /// A request that timed out in transport; not a FalkorDB query over `queryTimeoutMs`.
+    Timeout,
+    /// A connection that was reset or closed mid-request.
+    Connection,
+    /// A model reply holding no valid query.
+    Generation,
+}
+
+impl FailureCategory {
+    /// Whether the failure may clear up on its own, making it worth retrying under `maxRetries`.
+    fn is_transient(self) -> bool {
+        self != Self::Generation
+    }
+
+    /// The category of a redis error: a connection that timed out, was refused, or dropped. A
+    /// rejected password or TLS handshake is none of these.
+    pub(crate) fn of_redis(e: &redis::RedisError) -> Option<Self> {
+        if e.is_timeout() {
+            Some(Self::Timeout)
+        } else if e.is_connection_refusal() || e.is_connection_dropped() {
+            Some(Self::Connection)
+        } else {
+            None
+        }
+    }
+
+    /// The category of a FalkorDB client error: a connection the client found broken.
+    pub(crate) fn of_falkordb(e: &FalkorDBError) -> Option<Self> {
+        match e {
+            FalkorDBError::ConnectionDown | FalkorDBError::NoConnection => Some(Self::Connection),
+            _ => None,
+        }
+    }
+
+    /// The category of a failed model call: the status the provider answered with, or the
+    /// transport error that kept it from answering.
+    fn of_genai(e: &genai::Error) -> Option<Self> {
+        match e {
+            genai::Error::WebModelCall { webc_error, .. }
+            | genai::Error::WebAdapterCall { webc_error, .. } => match webc_error {
+                genai::webc::Error::ResponseFailedStatus { status, .. } => Self::of_status(*status),
+                genai::webc::Error::Reqwest(e) => Self::of_reqwest(e),
+                _ => None,
+            },
+            genai::Error::HttpError { status, .. } => Self::of_status(*status),
+            genai::Error::WebStream { error, .. } => {
+                if let Some(e) = error.downcast_ref::<genai::Error>() {
+                    Self::of_genai(e)
+                } else {
+                    Self::of_reqwest(error.downcast_ref()?)
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn of_status(status: StatusCode) -> Option<Self> {
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            Some(Self::RateLimit)
+        } else if SERVER_ERROR_STATUSES.contains(&status.as_u16()) {
+            Some(Self::ServerError)
+        } else {
+            None
+        }
+    }
+
+    /// A request that timed out, or whose connection failed once it was made. A connection
+    /// that could not be made at all points at a wrong endpoint rather than a passing fault.
+    fn of_reqwest(e: &reqwest::Error) -> Option<Self> {
+        if e.is_timeout() {
+            Some(Self::Timeout)
+        } else if (e.is_request() || e.is_body()) && !e.is_connect() {
+            Some(Self::Connection)
+        } else {
+            None
+        }
+    }
+}
+
+/// Retries of one failure category, as set in a `retryPolicy`
+#[napi(object)]
+#[derive(Debug, Clone, Copy)]
+pub struct RetryRule {
+    /// How many times a failure of the category is retried within a call
+    pub retries: u32,
+    /// Delay, in milliseconds, before the first retry; each further retry doubles it (capped at
+    /// 10s) and applies random jitter. Defaults to `retryBaseDelayMs`.
+    pub delay_ms: Option<u32>,
+}
+
+/// Retries by failure category, replacing `maxRetries`. A category left out is not retried, and
+/// neither is a failure outside every category, such as a rejected API key or invalid Cypher.
+#[napi(object)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RetryPolicy {
+    /// HTTP 429 responses
+    pub rate_limit: Option<RetryRule>,
+    /// HTTP 502, 503, and 504 responses
+    pub server_error: Option<RetryRule>,
+    /// LLM and FalkorDB requests that timed out in transport. A query FalkorDB aborts for running
+    /// past `queryTimeoutMs` is never retried.
+    pub timeout: Option<RetryRule>,
+    /// Connections reset or closed during an LLM call or a query
+    pub connection: Option<RetryRule>,
+    /// Query generation whose reply held no valid query; each retry asks the model again
+    pub generation: Option<RetryRule>,
+}
+
+impl RetryPolicy {
+    fn rule(&self, category: FailureCategory) -> Option<RetryRule> {
+        match category {
+            FailureCategory::RateLimit => self.rate_limit,
+            FailureCategory::ServerError => self.server_error,
+            FailureCategory::Timeout => self.timeout,
+            FailureCategory::Connection => self.connection,
+            FailureCategory::Generation => self.generation,
+        }
+    }
+}
+
+/// How many times, and how patiently, a failed operation is re-issued.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Retry {
+    /// Retries after the first attempt; `0` disables retrying.
+    pub(crate) max_retries: u32,
+    /// Delay before the first retry, doubled for each one after it.
+    pub(crate) base_delay: Duration,
+    /// Retries by failure category, in place of `max_retries` and of the errors the caller
+    /// deems retryable; `base_delay` stands in for a rule's unset delay.
+    pub(crate) policy: Option<RetryPolicy>,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            base_delay: Duration::from_millis(500),
+            policy: None,
+        }
+    }
+}
+
+impl Retry {
+    /// Runs `operation` until it succeeds, fails with a non-transient error, or the retries run
+    /// out; under a policy, until it fails in a category whose retries ran out. An error that
+    /// persisted through at least one retry says how many were spent.
+    pub(crate) async fn run<T, E, F, Fut>(&self, mut operation: F) -> Result<T, E>
+    where
+        E: Retryable,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut retries = 0;
+        // Retries spent on each category, under a policy.
+        let mut spent: HashMap<FailureCategory, u32> = HashMap::new();
+        loop {
+            let e = match operation().await {
+                Ok(value) => return Ok(value),
+                Err(e) => e,
+            };
+            // The retries allowed for `e`, the delay they start from, and the policy category
+            // they are counted against.
+            let (max_retries, base_delay, counted) = match &self.policy {
+                None if e.is_transient() => (self.max_retries, self.base_delay, None),
+                None => (0, self.base_delay, None),
+                Some(policy) => {
+                    let category = e.category();
+                    match category.and_then(|category| policy.rule(category)) {
+                        Some(rule) => (
+                            rule.retries,
+                            rule.delay_ms
+                                .map_or(self.base_delay, |ms| Duration::from_millis(u64::from(ms))),
+                            category,
+                        ),
+                        None => (0, self.base_delay, None),
+                    }
+                }
+            };
+            let used = counted.map_or(retries, |category| {
+                spent.get(&category).copied().unwrap_or(0)
+            });
+            let delay = e.retry_after().unwrap_or_else(|| backoff(base_delay, used));
+            if used < max_retries && delay <= MAX_RETRY_AFTER {
+                tokio::time::sleep(delay).await;
+                retries += 1;
+                if let Some(category) = counted {
+                    *spent.entry(category).or_default() += 1;
+                }
+            } else if retries > 0 {
+                return Err(e.exhausted(retries));
+            } else {
+                return Err(e);
+            }
+        }
+    }
+}
+
+/// Full-jitter delay before retry number `retry` (0-based): a uniform value in
+/// `[0, base_delay * 2^retry)`, capped at [`MAX_DELAY`].
+fn backoff(base_delay: Duration, retry: u32) -> Duration {
+    let ceiling = base_delay
+        .saturating_mul(2u32.saturating_pow(retry))
+        .min(MAX_DELAY);
+    ceiling.mul_f64(fastrand::f64())
+}
+
+/// An error [`Retry::run`] can classify and annotate once retries are exhausted.
+pub(crate) trait Retryable: Display {
+    /// This error with its message noting that it persisted through `retries` retries.
+    fn exhausted(self, retries: u32) -> Self;
+
+    /// The kind of failure this is, when it is one a retry may help with.
+    fn category(&self) -> Option<FailureCategory>;
+
+    /// Whether another attempt may succeed.
+    fn is_transient(&self) -> bool {
+        self.category().is_some_and(FailureCategory::is_transient)
+    }
+
+    /// How long the other side asked to wait before another attempt, if it said.
+    fn retry_after(&self) -> Option<Duration> {
+        None
+    }
+}
+
+impl Retryable for ClientError {
+    fn exhausted(self, retries: u32) -> Self {
+        self.suffix(&exhausted_suffix(retries))
+    }
+
+    /// A query that ran out of time would only run out of time again.
+    fn category(&self) -> Option<FailureCategory> {
+        match self.code() {
+            ErrorCode::QueryTimeout => None,
+            _ => self.failure_category(),
+        }
+    }
+}
+
+impl Retryable for BoxError {
+    fn exhausted(self, retries: u32) -> Self {
+        format!("{self}{}", exhausted_suffix(retries)).into()
+    }
+
+    fn category(&self) -> Option<FailureCategory> {
+        if let Some(e) = self.downcast_ref::<ProviderError>() {
+            e.category
+        } else if self.is::<NoQuery>() || self.is::<InvalidQuery>() {
+            Some(FailureCategory::Generation)
+        } else {
+            None
+        }
+    }
+
+    fn retry_after(&self) -> Option<Duration> {
+        self.downcast_ref::<ProviderError>()?.retry_after
+    }
+}
+
+/// A failed model call, with its failure category and the wait its provider asked for before
+/// another attempt.
+#[derive(Debug)]
+pub(crate) struct ProviderError {
+    message: String,
+    category: Option<FailureCategory>,
+    retry_after: Option<Duration>,
+}
+
+impl ProviderError {
+    /// `cause` under `message`, keeping its category and any `Retry-After` from the provider's
+    /// reply.
+    pub(crate) fn boxed(message: String, cause: &genai::Error) -> BoxError {
+        let headers = match cause {
+            genai::Error::WebModelCall { webc_error, .. }
+            | genai::Error::WebAdapterCall { webc_error, .. } => match webc_error {
+                genai::webc::Error::ResponseFailedStatus { headers, .. } => Some(headers),
+                _ => None,
+            },
+            _ => None,
+        };
+        Box::new(Self {
+            message,
+            category: FailureCategory::of_genai(cause),
+            retry_after: headers.and_then(|headers| retry_after(headers)),
+        })
+    }
+}
+
+impl Display for ProviderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ProviderError {}
+
+/// The wait a rate-limited reply asks for: OpenAI's `retry-after-ms`, or the standard
+/// `Retry-After` in seconds. The HTTP-date form of `Retry-After` is not used by model providers
+/// and is ignored.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name)?.to_str().ok()?.trim().parse::<f64>().ok();
+    let wait = match header("retry-after-ms") {
+        Some(ms) => ms / 1000.0,
+        None => header("retry-after")?,
+    };
+    Duration::try_from_secs_f64(wait).ok()
+}
+
+fn exhausted_suffix(retries: u32) -> String {
+    let plural = if retries == 1 { "retry" } else { "retries" };
+    format!(" (gave up after {retries} {plural})")
+}
@@ -0,0 +1,261 @@
+//! Typed graph schemas
+//!
+//! `discoverSchema` returns the schema as the JSON string the model is prompted with. [`Schema`]
+//! is the same document as a typed object with the same field names, so TypeScript callers get
+//! autocomplete instead of parsing the string and guessing its shape.
+//!
+//! The schema of a graph with hundreds of labels can crowd the question out of the prompt;
+//! [`prune_schema`] cuts it down to the labels a question is most likely about.
+//!
+//! With `sampleRows` set, each label also carries a few of its nodes' properties, cut down by
+//! [`sample_row`] so a long text or an embedding cannot blow up the prompt.
+
+use crate::error::{ClientError, ErrorCode};
+use crate::result_set::to_json;
+use falkordb::FalkorValue;
+use napi_derive::napi;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+
+/// Longest string value kept in a sample row, in characters.
+const SAMPLE_STRING_CHARS: usize = 80;
+
+/// Most items kept of a list value in a sample row.
+const SAMPLE_LIST_ITEMS: usize = 5;
+
+/// A graph's node labels and relationship types, with their properties
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct Schema {
+    /// One entry per node label
+    pub entities: Vec<SchemaEntity>,
+    /// One entry per relationship type and pair of endpoint labels it connects
+    pub relations: Vec<SchemaRelation>,
+}
+
+/// A node label and the properties its nodes carry
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct SchemaEntity {
+    pub label: String,
+    #[serde(default)]
+    pub attributes: Vec<SchemaAttribute>,
+    /// Free-text description of the label, when the schema has one
+    pub description: Option<String>,
+    /// Properties of a few of the label's nodes, when the schema was discovered with `sampleRows`
+    pub samples: Option<Vec<Map<String, Value>>>,
+}
+
+/// A relationship type between nodes of two labels, and the properties its edges carry
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct SchemaRelation {
+    /// The relationship type
+    pub label: String,
+    /// Label of the nodes the relationships start from
+    pub source: String,
+    /// Label of the nodes the relationships point to
+    pub target: String,
+    #[serde(default)]
+    pub attributes: Vec<SchemaAttribute>,
+}
+
+/// A property of a node label or relationship type
+#[napi(object)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct SchemaAttribute {
+    pub name: String,
+    /// Value type: `String`, `Number`, `Integer`, `Float`, `Boolean`, `DateTime`, `List`, `Map`,
+    /// `Vector`, or `Point`
+    #[napi(js_name = "type")]
+    #[serde(rename = "type")]
+    pub value_type: String,
+    /// Whether every value is distinct
+    #[serde(default)]
+    pub unique: bool,
+    /// Whether every sampled node or relationship has the property
+    #[serde(default)]
+    pub required: bool,
+    /// A few sampled values, as strings
+    pub examples: Option<Vec<String>>,
+}
+
+impl Schema {
+    /// Reads the JSON string schema discovery produces.
+    pub(crate) fn from_json(schema: &str) -> Result<Self, ClientError> {
+        serde_json::from_str(schema).map_err(|e| {
+            ClientError::new(
+                ErrorCode::SchemaDiscovery,
+                format!("Failed to read schema: {e}"),
+            )
+        })
+    }
+}
+
+/// Keeps the `top_k` node labels of `schema` most relevant to `question`, and the relationship
+/// types between them, returning the pruned schema and the labels kept, in schema order.
+///
+/// A label scores two points for each of its words the question uses, and one for each such word
+/// in its properties' names and in the relationship types it takes part in; ties keep schema
+/// order. Words are compared lowercased and singular, so `Actor` matches "actors" and
+/// `releaseYear` matches "release year". Returns `None` when the schema has no `entities` list,
+/// e.g. a hand-written one.
+pub(crate) fn prune_schema(
+    schema: &str,
+    question: &str,
+    top_k: usize,
+) -> Option<(String, Vec<String>)> {
+    let mut schema = serde_json::from_str::<Value>(schema).ok()?;
+    let question = words(question).into_iter().collect::<HashSet<_>>();
+    let mentions = |item: &Value| {
+        let attributes = item.get("attributes").and_then(Value::as_array);
+        let attribute_mentions = attributes.into_iter().flatten().map(|attribute| {
+            let name = attribute.get("name").and_then(Value::as_str);
+            shared_words(&question, name.unwrap_or_default())
+        });
+        (
+            shared_words(&question, label(item)),
+            attribute_mentions.sum::<usize>(),
+        )
+    };
+
+    let entities = schema.get("entities")?.as_array()?;
+    let relations = schema.get("relations").and_then(Value::as_array);
+    let mut ranked = entities
+        .iter()
+        .enumerate()
+        .map(|(index, entity)| {
+            let (label_mentions, attribute_mentions) = mentions(entity);
+            let relation_mentions = relations
+                .into_iter()
+                .flatten()
+                .filter(|relation| endpoints(relation).contains(&label(entity)))
+                .map(|relation| {
+                    let (label_mentions, attribute_mentions) = mentions(relation);
+                    label_mentions + attribute_mentions
+                })
+                .sum::<usize>();
+            let score = 2 * label_mentions + attribute_mentions + relation_mentions;
+            (index, score)
+        })
+        .collect::<Vec<_>>();
+    // A stable sort, so equally relevant labels keep their schema order.
+    ranked.sort_by(|(_, a), (_, b)| b.cmp(a));
+    ranked.truncate(top_k);
+    ranked.sort_by_key(|&(index, _)| index);
+
+    let entities = ranked
+        .iter()
+        .map(|&(index, _)| entities[index].clone())
+        .collect::<Vec<_>>();
+    let labels = entities
+        .iter()
+        .map(|entity| label(entity).to_string())
+        .collect::<Vec<_>>();
+    let relations = relations.map(|relations| {
+        relations
+            .iter()
+            .filter(|relation| {
+                endpoints(relation)
+                    .iter()
+                    .all(|end| labels.iter().any(|label| label == end))
+            })
+            .cloned()
+            .collect::<Vec<_>>()
+    });
+    schema["entities"] = Value::Array(entities);
+    if let Some(relations) = relations {
+        schema["relations"] = Value::Array(relations);
+    }
+    Some((schema.to_string(), labels))
+}
+
+/// A sampled node's `properties`, cut down for the prompt: strings longer than
+/// [`SAMPLE_STRING_CHARS`] are truncated, lists keep their first [`SAMPLE_LIST_ITEMS`] items, and
+/// vectors, usually embeddings with no meaning to the model, are left out.
+pub(crate) fn sample_row(properties: &HashMap<String, FalkorValue>) -> Map<String, Value> {
+    properties
+        .iter()
+        .filter(|(_, value)| !matches!(value, FalkorValue::Vec32(_)))
+        .map(|(name, value)| (name.clone(), shorten(to_json(value))))
+        .collect()
+}
+
+fn shorten(value: Value) -> Value {
+    match value {
+        Value::String(text) if text.chars().count() > SAMPLE_STRING_CHARS => {
+            let kept: String = text.chars().take(SAMPLE_STRING_CHARS).collect();
+            Value::String(format!("{kept}…"))
+        }
+        Value::Array(items) => items
+            .into_iter()
+            .take(SAMPLE_LIST_ITEMS)
+            .map(shorten)
+            .collect(),
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(name, value)| (name, shorten(value)))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+/// The `label` of a schema entity or relation.
+fn label(item: &Value) -> &str {
+    item.get("label")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+}
+
+/// The labels a schema relation connects.
+fn endpoints(relation: &Value) -> [&str; 2] {
+    ["source", "target"].map(|end| {
+        relation
+            .get(end)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+    })
+}
+
+/// How many of the words of `text` are in `question`.
+fn shared_words(question: &HashSet<String>, text: &str) -> usize {
+    words(text)
+        .iter()
+        .filter(|word| question.contains(*word))
+        .count()
+}
+
+/// Splits `text` into lowercase singular words, breaking identifiers at non-letters and at
+/// camelCase humps.
+fn words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut after_lowercase = false;
+    for c in text.chars() {
+        if (!c.is_alphabetic() || (c.is_uppercase() && after_lowercase)) && !word.is_empty() {
+            words.push(singular(&std::mem::take(&mut word)));
+        }
+        if c.is_alphabetic() {
+            word.extend(c.to_lowercase());
+        }
+        after_lowercase = c.is_lowercase();
+    }
+    if !word.is_empty() {
+        words.push(singular(&word));
+    }
+    words
+}
+
+/// Strips a plural ending (`-ies`, `-s`) from a lowercase English word.
+fn singular(word: &str) -> String {
+    if let Some(stem) = word.strip_suffix("ies").filter(|stem| stem.len() > 1) {
+        format!("{stem}y")
+    } else if word.len() > 3 && word.ends_with('s') && !word.ends_with("ss") {
+        word[..word.len() - 1].to_string()
+    } else {
+        word.to_string()
+    }
+}
@@ -0,0 +1,90 @@
+//! Discovered-schema cache
+//!
+//! Schema discovery runs several queries against FalkorDB, which adds up when the same graph is
+//! asked about again and again. [`SchemaCache`] keeps each graph's discovered schema for a fixed
+//! time, shared by every call on the client, along with the description `describeSchema`
+//! generated from it.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+struct Entry {
+    discovered: Instant,
+    schema: String,
+    /// Natural-language description of `schema`, once one was generated.
+    description: Option<String>,
+}
+
+/// Discovered schemas by graph name, each valid for `ttl` after discovery.
+#[derive(Debug)]
+pub(crate) struct SchemaCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl SchemaCache {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The schema cached for `graph_name`, unless there is none or it has expired.
+    pub(crate) fn get(&self, graph_name: &str) -> Option<String> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(graph_name) {
+            Some(entry) if entry.discovered.elapsed() < self.ttl => Some(entry.schema.clone()),
+            Some(_) => {
+                entries.remove(graph_name);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub(crate) fn insert(&self, graph_name: &str, schema: &str) {
+        self.entries.lock().unwrap().insert(
+            graph_name.to_string(),
+            Entry {
+                discovered: Instant::now(),
+                schema: schema.to_string(),
+                description: None,
+            },
+        );
+    }
+
+    /// The description stored for `graph_name`, if its entry is still valid and holds `schema`.
+    pub(crate) fn description(&self, graph_name: &str, schema: &str) -> Option<String> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(graph_name)
+            .filter(|entry| entry.discovered.elapsed() < self.ttl && entry.schema == schema)
+            .and_then(|entry| entry.description.clone())
+    }
+
+    /// Stores `description` with the entry for `graph_name`, unless the entry is gone or now
+    /// holds a schema other than `schema`; it then expires with the schema.
+    pub(crate) fn set_description(&self, graph_name: &str, schema: &str, description: &str) {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(entry) = entries
+            .get_mut(graph_name)
+            .filter(|entry| entry.schema == schema)
+        {
+            entry.description = Some(description.to_string());
+        }
+    }
+
+    /// Drops the entry for `graph_name`, or every entry when it is `None`.
+    pub(crate) fn invalidate(&self, graph_name: Option<&str>) {
+        let mut entries = self.entries.lock().unwrap();
+        match graph_name {
+            Some(graph_name) => {
+                entries.remove(graph_name);
+            }
+            None => entries.clear(),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//! Structured query generation
+//!
+//! With `structuredOutput` set, query generation asks providers that can constrain a reply to a
+//! JSON schema (OpenAI, Anthropic, Gemini) for `{ query, parameters }` instead of free text, so
+//! the query needs no extracting from prose or Markdown. The parameters are folded into a
+//! `CYPHER name=value ...` header, the form the rest of the pipeline already handles. Other
+//! providers, and replies that do not match the schema, fall back to the text extraction.
+
+use crate::cypher;
+use genai::chat::JsonSpec;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use text_to_cypher::AdapterKind;
+
+/// Appended to the query-generation system prompt, which otherwise asks for the bare query.
+pub(crate) const PROMPT: &str = "Reply with a JSON object instead of the bare query: put the \
+    Cypher query in `query`, without a CYPHER header, Markdown, or commentary, and list each \
+    $parameter it uses in `parameters`, with its value written as JSON.";
+
+/// Whether genai sends a JSON schema response format to `adapter_kind`'s API.
+pub(crate) fn supported(adapter_kind: AdapterKind) -> bool {
+    matches!(
+        adapter_kind,
+        AdapterKind::OpenAI
+            | AdapterKind::OpenAIResp
+            | AdapterKind::Anthropic
+            | AdapterKind::Gemini
+    )
+}
+
+/// The schema the reply is constrained to. Parameter values are JSON strings because strict
+/// schemas cannot leave a property's type open.
+pub(crate) fn response_format() -> JsonSpec {
+    JsonSpec::new(
+        "cypher_query",
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "The Cypher query",
+                },
+                "parameters": {
+                    "type": "array",
+                    "description": "One entry per $parameter the query uses",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string", "description": "Name, without the $" },
+                            "value": {
+                                "type": "string",
+                                "description": "Value as JSON, e.g. \"Tom Hanks\", 1999, or [1, 2]",
+                            },
+                        },
+                        "required": ["name", "value"],
+                    },
+                },
+            },
+            "required": ["query", "parameters"],
+        }),
+    )
+}
+
+#[derive(Deserialize)]
+struct Reply {
+    query: String,
+    #[serde(default)]
+    parameters: Vec<Parameter>,
+}
+
+#[derive(Deserialize)]
+struct Parameter {
+    name: String,
+    value: Value,
+}
+
+/// Reads a reply in the [`response_format`] schema as a query with its parameters in a `CYPHER`
+/// header. `None` when the reply is not such an object, or names a parameter that is not an
+/// identifier.
+pub(crate) fn parse_reply(reply: &str) -> Option<String> {
+    let reply = reply.trim();
+    let reply = reply
+        .strip_prefix("```json")
+        .and_then(|rest| rest.strip_suffix("```"))
+        .unwrap_or(reply);
+    let Reply { query, parameters } = serde_json::from_str(reply).ok()?;
+
+    let mut values = Map::new();
+    for Parameter { name, value } in parameters {
+        let name = name.trim().trim_start_matches('$');
+        if !cypher::is_identifier(name) {
+            return None;
+        }
+        // A value that is not valid JSON is taken as the string it holds.
+        let value = match value {
+            Value::String(json) => serde_json::from_str(&json).unwrap_or(Value::String(json)),
+            value => value,
+        };
+        values.insert(name.to_string(), value);
+    }
+    Some(cypher::join_parameters(&values, query.trim()))
+}